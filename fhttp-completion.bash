@@ -1,18 +1,16 @@
 #/usr/bin/env bash
 
-_jq_installed() {
-  if ! command -v jq &> /dev/null; then
-    exit
-  fi
-}
-
 _available_profiles() {
   profile_file="$1"
-  cat $profile_file |jq --raw-output 'keys |join("\n")'
+  if [ "$profile_file" != "" ]; then
+    fhttp profiles list --profile-file "$profile_file" 2> /dev/null
+  else
+    fhttp profiles list 2> /dev/null
+  fi
 }
 
 _config_file() {
-  config_file="fhttp-config.json"
+  config_file=""
   index=0
   for i in ${COMP_WORDS[@]}; do
     if [ "$i" == "-f" ] || [ "$i" == "--profile-file" ]; then
@@ -33,7 +31,6 @@ _config_file() {
 }
 
 _fhttp_completions() {
-  _jq_installed
   config_file=$(_config_file)
 
   current_word="${COMP_WORDS[$COMP_CWORD - 1]}"
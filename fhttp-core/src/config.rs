@@ -1,5 +1,11 @@
+use std::borrow::Cow;
 use std::{fmt::Display, time::Duration};
 
+use crate::execution::dry_run::DryRunFormat;
+use crate::execution::export::{CurlExporter, Exporter};
+use crate::request::status_set::StatusSet;
+use crate::secrets;
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Config {
     no_prompt: bool,
@@ -8,6 +14,16 @@ pub struct Config {
     print_paths: bool,
     timeout_ms: Option<u64>,
     curl: bool,
+    include_headers: bool,
+    parallelism: Option<usize>,
+    retries: u32,
+    retry_on: Option<StatusSet>,
+    retry_non_idempotent: bool,
+    retry_delay_ms: Option<u64>,
+    show_secrets: bool,
+    dry_run: Option<DryRunFormat>,
+    curl_script: bool,
+    export: Option<&'static dyn Exporter>,
 }
 
 impl Config {
@@ -26,9 +42,69 @@ impl Config {
             print_paths,
             timeout_ms,
             curl,
+            include_headers: false,
+            parallelism: None,
+            retries: 0,
+            retry_on: None,
+            retry_non_idempotent: false,
+            retry_delay_ms: None,
+            show_secrets: false,
+            dry_run: None,
+            curl_script: false,
+            export: None,
         }
     }
 
+    pub fn with_include_headers(mut self, include_headers: bool) -> Self {
+        self.include_headers = include_headers;
+        self
+    }
+
+    pub fn with_parallelism(mut self, parallelism: Option<usize>) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn with_retry_on(mut self, retry_on: Option<StatusSet>) -> Self {
+        self.retry_on = retry_on;
+        self
+    }
+
+    pub fn with_retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    pub fn with_retry_delay_ms(mut self, retry_delay_ms: Option<u64>) -> Self {
+        self.retry_delay_ms = retry_delay_ms;
+        self
+    }
+
+    pub fn with_show_secrets(mut self, show_secrets: bool) -> Self {
+        self.show_secrets = show_secrets;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: Option<DryRunFormat>) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_curl_script(mut self, curl_script: bool) -> Self {
+        self.curl_script = curl_script;
+        self
+    }
+
+    pub fn with_export(mut self, export: Option<&'static dyn Exporter>) -> Self {
+        self.export = export;
+        self
+    }
+
     pub fn prompt_missing_env_vars(&self) -> bool {
         !self.no_prompt
     }
@@ -52,15 +128,80 @@ impl Config {
         self.curl
     }
 
+    /// Whether the curl commands of the requests and their dependencies are exported as a script.
+    pub fn curl_script(&self) -> bool {
+        self.curl && self.curl_script
+    }
+
+    /// Whether secrets are printed as the commands resolving them, e.g. `$(pass path)`, instead
+    /// of being resolved.
+    pub fn secret_commands(&self, for_dependency: bool) -> bool {
+        match self.export() {
+            Some(exporter) => {
+                exporter.is_shell_command() && (!for_dependency || self.curl_script())
+            }
+            None => false,
+        }
+    }
+
+    /// How to export requests instead of executing them, `--curl` exports curl commands.
+    pub fn export(&self) -> Option<&'static dyn Exporter> {
+        match self.curl {
+            true => Some(self.export.unwrap_or(&CurlExporter)),
+            false => self.export,
+        }
+    }
+
+    /// The format to print resolved requests in instead of executing them, if any.
+    pub fn dry_run(&self) -> Option<DryRunFormat> {
+        self.dry_run
+    }
+
+    pub fn include_headers(&self) -> bool {
+        self.include_headers
+    }
+
+    pub fn parallelism(&self) -> Option<usize> {
+        self.parallelism
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    pub fn retry_on(&self) -> Option<StatusSet> {
+        self.retry_on
+    }
+
+    pub fn retry_non_idempotent(&self) -> bool {
+        self.retry_non_idempotent
+    }
+
+    pub fn retry_delay(&self) -> Option<Duration> {
+        self.retry_delay_ms.map(Duration::from_millis)
+    }
+
+    pub fn show_secrets(&self) -> bool {
+        self.show_secrets
+    }
+
+    /// Masks the values of resolved secrets, unless secrets should be shown.
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self.show_secrets {
+            true => Cow::Borrowed(text),
+            false => secrets::redact(text),
+        }
+    }
+
     pub fn log<S: Display>(&self, level: u8, message: S) {
         if self.verbosity() >= level {
-            eprint!("{}", message);
+            eprint!("{}", self.redact(&message.to_string()));
         }
     }
 
     pub fn logln<S: Display>(&self, level: u8, message: S) {
         if self.verbosity() >= level {
-            eprintln!("{}", message);
+            eprintln!("{}", self.redact(&message.to_string()));
         }
     }
 }
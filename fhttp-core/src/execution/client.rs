@@ -1,40 +1,144 @@
+use std::sync::Arc;
+use std::thread;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use reqwest::blocking::multipart;
-use reqwest::header::HeaderMap;
-use reqwest::{Method, Url};
+use reqwest::blocking::{multipart, RequestBuilder};
+use reqwest::redirect::Policy;
+use reqwest::Url;
 
+use crate::execution::retry::RetryPolicy;
+use crate::postprocessing::assertion::Assertion;
 use crate::request::body::{Body, MultipartPart};
-use crate::{Response, ResponseHandler};
+use crate::request::Request;
+use crate::{Config, CookieJar, Response, ResponseHandler};
 use crate::postprocessing::response_handler::ResponseHandlerInput;
 
-pub struct Client;
+/// How long idle connections are kept in the pool for reuse by later requests.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// Wraps a single `reqwest` client that is shared by all requests of a run,
+/// so connections (and TLS sessions) are pooled and kept alive between requests.
+/// Cookies set by any response are stored in the given jar and sent along with later requests.
+/// Requests with the `no-redirect` directive use a second client that doesn't follow redirects.
+#[derive(Debug, Clone)]
+pub struct Client {
+    client: reqwest::blocking::Client,
+    no_redirect_client: reqwest::blocking::Client,
+    config: Config,
+}
 
 impl Client {
-    pub fn new() -> Self {
-        Client {}
+    pub fn new(config: &Config, cookie_jar: Arc<CookieJar>) -> Result<Self> {
+        let builder = || {
+            let mut builder = reqwest::blocking::Client::builder()
+                .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+                .tcp_keepalive(TCP_KEEPALIVE)
+                .cookie_provider(cookie_jar.clone());
+            if let Some(timeout) = config.timeout() {
+                builder = builder.timeout(timeout);
+            }
+            builder
+        };
+
+        let client = builder().build().context("failed to initialize http client")?;
+        let no_redirect_client = builder()
+            .redirect(Policy::none())
+            .build()
+            .context("failed to initialize http client")?;
+
+        Ok(Client {
+            client,
+            no_redirect_client,
+            config: *config,
+        })
+    }
+
+    /// The underlying client following redirects, for other lookups of the run like vault
+    /// secrets, so they share its connection pool and timeout.
+    pub(crate) fn http_client(&self) -> &reqwest::blocking::Client {
+        &self.client
+    }
+
+    /// Sends the request, retrying transient failures according to its `RetryPolicy`.
+    /// The request's metadata overrides the timeout and redirect behaviour of this client.
+    pub fn exec(&self, request: Request) -> Result<Response> {
+        let url = Url::parse(&request.url)
+            .with_context(|| format!("Invalid URL: '{}'", &request.url))?;
+        let policy = RetryPolicy::new(&self.config, &request.method, &request.metadata);
+
+        let mut attempt = 1;
+        let response = loop {
+            let result = self.build(&request, url.clone())?.send();
+            let (delay, reason) = match result {
+                Ok(ref response) => match policy.retry_response(
+                    attempt,
+                    response.status(),
+                    response.headers(),
+                ) {
+                    Some(delay) => (delay, response.status().to_string()),
+                    None => break result?,
+                },
+                Err(ref e) => match policy.retry_error(attempt, e) {
+                    Some(delay) => (delay, e.to_string()),
+                    None => break result?,
+                },
+            };
+
+            self.config.logln(
+                2,
+                format!(
+                    "attempt {} of {} failed: {}, retrying in {}ms",
+                    attempt,
+                    policy.attempts(),
+                    reason,
+                    delay.as_millis()
+                ),
+            );
+            thread::sleep(delay);
+            attempt += 1;
+        };
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let text = response.text()?;
+        let response_handler_input = ResponseHandlerInput {
+            status_code: status.as_u16(),
+            headers: headers.clone(),
+            body: text,
+        };
+
+        let expected = request.metadata.is_expected_status(status);
+        let body = match (expected, request.response_handler) {
+            | (_, Some(handler @ ResponseHandler::Rhai { .. }))
+            | (true, Some(handler))
+            => handler.process_body(response_handler_input.clone())?,
+            _ => response_handler_input.body.clone(),
+        };
+
+        // assertions check the original response, not the handler's result
+        Assertion::verify_all(&request.assertions, &response_handler_input)?;
+
+        Ok(Response::new(status, headers, body))
     }
 
-    pub fn exec(
-        &self,
-        method: Method,
-        url: &str,
-        headers: HeaderMap,
-        body: Body,
-        response_handler: Option<ResponseHandler>,
-        timeout: Option<Duration>,
-    ) -> Result<Response> {
-        let client = reqwest::blocking::Client::new();
-        let url = Url::parse(url).with_context(|| format!("Invalid URL: '{}'", url))?;
-        let mut req_builder = client.request(method, url).headers(headers);
-        if let Some(timeout) = timeout {
+    /// Builds a fresh `RequestBuilder` for every attempt, multipart bodies can't be reused.
+    fn build(&self, request: &Request, url: Url) -> Result<RequestBuilder> {
+        let client = match request.metadata.no_redirect() {
+            true => &self.no_redirect_client,
+            false => &self.client,
+        };
+        let mut req_builder = client
+            .request(request.method.clone(), url)
+            .headers(request.headers.clone());
+        if let Some(timeout) = request.metadata.timeout() {
             req_builder = req_builder.timeout(timeout);
         }
 
-        let req_builder = match body {
-            Body::Plain(body) => req_builder.body(body),
-            Body::Multipart(parts) => {
+        let req_builder = match request.body {
+            Body::Plain(ref body) => req_builder.body(body.clone()),
+            Body::Multipart(ref parts) => {
                 let mut multipart = multipart::Form::new();
                 for part in parts {
                     match part {
@@ -49,11 +153,11 @@ impl Client {
                                     format!("Error opening file {}", path_clone.to_str())
                                 })?;
                             if let Some(mime_str) = mime_str {
-                                tmp = tmp.mime_str(&mime_str).with_context(|| {
+                                tmp = tmp.mime_str(mime_str).with_context(|| {
                                     format!("error parsing mime string '{}'", &mime_str)
                                 })?;
                             }
-                            multipart = multipart.part(name, tmp);
+                            multipart = multipart.part(name.clone(), tmp);
                         }
                         MultipartPart::Text {
                             name,
@@ -62,11 +166,11 @@ impl Client {
                         } => {
                             let mut tmp = multipart::Part::text(text.clone());
                             if let Some(mime_str) = mime_str {
-                                tmp = tmp.mime_str(&mime_str).with_context(|| {
+                                tmp = tmp.mime_str(mime_str).with_context(|| {
                                     format!("error parsing mime string '{}'", &mime_str)
                                 })?;
                             }
-                            multipart = multipart.part(name, tmp);
+                            multipart = multipart.part(name.clone(), tmp);
                         }
                     }
                 }
@@ -74,40 +178,39 @@ impl Client {
             }
         };
 
-        let response = req_builder.send()?;
-        let status = response.status();
-        let text = response.text()?;
-        let response_handler_input = ResponseHandlerInput { status_code: status.as_u16(), body: text };
-
-        let body = match (status.is_success(), response_handler) {
-            | (_, Some(handler @ ResponseHandler::Rhai { .. }))
-            | (true, Some(handler))
-            => handler.process_body(response_handler_input)?,
-            _ => response_handler_input.body,
-        };
-        
-        Ok(Response::new(status, body))
-    }
-}
-
-impl Default for Client {
-    fn default() -> Self {
-        Self::new()
+        Ok(req_builder)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use reqwest::header::HeaderMap;
+    use reqwest::Method;
     use rstest::rstest;
-    use wiremock::matchers::method;
+    use wiremock::matchers::{header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
     use wiremock_multipart::prelude::*;
 
     use crate::request::body::MultipartPart;
+    use crate::request::metadata::RequestMetadata;
     use crate::test_utils::root;
 
     use super::*;
 
+    fn request<S: Into<String>>(method: Method, url: S, body: Body) -> Request {
+        Request {
+            method,
+            url: url.into(),
+            headers: HeaderMap::new(),
+            body,
+            response_handler: None,
+            assertions: vec![],
+            metadata: RequestMetadata::default(),
+        }
+    }
+
     #[rstest]
     async fn should_correctly_handle_new_multiparts_async() -> Result<()> {
         let mock_server = MockServer::start().await;
@@ -139,10 +242,9 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        Client::new().exec(
+        Client::new(&Config::default(), Arc::new(CookieJar::new()))?.exec(request(
             Method::POST,
-            &mock_server.uri().to_string(),
-            HeaderMap::new(),
+            mock_server.uri(),
             Body::Multipart(vec![
                 MultipartPart::Text {
                     name: "text".to_string(),
@@ -160,9 +262,218 @@ mod tests {
                     mime_str: Some("image/jpeg".to_string()),
                 },
             ]),
-            None,
-            None,
-        )?;
+        ))?;
+
+        Ok(())
+    }
+
+    #[rstest]
+    async fn should_reuse_the_client_for_multiple_requests() -> Result<()> {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let client = Client::new(&Config::default(), Arc::new(CookieJar::new()))?;
+        for _ in 0..3 {
+            let response = client.exec(request(
+                Method::GET,
+                mock_server.uri(),
+                Body::plain(""),
+            ))?;
+            assert_eq!(response.body(), "ok");
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    async fn should_send_cookies_set_by_previous_responses() -> Result<()> {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/login"))
+            .respond_with(ResponseTemplate::new(200).insert_header("set-cookie", "session=abc; Path=/"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .and(header("cookie", "session=abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("me"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let cookie_jar = Arc::new(CookieJar::new());
+        let client = Client::new(&Config::default(), cookie_jar.clone())?;
+        client.exec(request(
+            Method::POST,
+            format!("{}/login", mock_server.uri()),
+            Body::plain(""),
+        ))?;
+        let response = client.exec(request(
+            Method::GET,
+            format!("{}/me", mock_server.uri()),
+            Body::plain(""),
+        ))?;
+
+        assert_eq!(response.body(), "me");
+        assert_eq!(cookie_jar.cookies_list().len(), 1);
+
+        Ok(())
+    }
+
+    #[rstest]
+    async fn should_apply_the_timeout_from_the_config() -> Result<()> {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/slow"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(1_000)))
+            .mount(&mock_server)
+            .await;
+
+        let config = Config::new(false, 0, false, false, Some(100), false);
+        let result = Client::new(&config, Arc::new(CookieJar::new()))?.exec(request(
+            Method::GET,
+            format!("{}/slow", mock_server.uri()),
+            Body::plain(""),
+        ));
+
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[rstest]
+    async fn should_retry_failed_attempts() -> Result<()> {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(502))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = Config::default()
+            .with_retries(2)
+            .with_retry_delay_ms(Some(1));
+        let response = Client::new(&config, Arc::new(CookieJar::new()))?.exec(request(
+            Method::GET,
+            mock_server.uri(),
+            Body::plain(""),
+        ))?;
+
+        assert_eq!(response.status(), &reqwest::StatusCode::OK);
+        assert_eq!(response.body(), "ok");
+
+        Ok(())
+    }
+
+    #[rstest]
+    async fn should_return_the_last_response_when_retries_are_exhausted() -> Result<()> {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "0"))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let mut request = request(Method::PUT, mock_server.uri(), Body::plain(""));
+        request.metadata.retries = Some(2);
+        let response = Client::new(&Config::default(), Arc::new(CookieJar::new()))?.exec(request)?;
+
+        assert_eq!(response.status(), &reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+        Ok(())
+    }
+
+    #[rstest]
+    async fn should_not_retry_non_idempotent_requests_by_default() -> Result<()> {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = Config::default()
+            .with_retries(2)
+            .with_retry_delay_ms(Some(1));
+        let response = Client::new(&config, Arc::new(CookieJar::new()))?.exec(request(
+            Method::POST,
+            mock_server.uri(),
+            Body::plain(""),
+        ))?;
+
+        assert_eq!(response.status(), &reqwest::StatusCode::BAD_GATEWAY);
+
+        Ok(())
+    }
+
+    #[rstest]
+    async fn should_apply_the_timeout_from_the_request() -> Result<()> {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/slow"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(1_000)))
+            .mount(&mock_server)
+            .await;
+
+        let config = Config::new(false, 0, false, false, Some(5_000), false);
+        let mut request = request(
+            Method::GET,
+            format!("{}/slow", mock_server.uri()),
+            Body::plain(""),
+        );
+        request.metadata.timeout_ms = Some(100);
+        let result = Client::new(&config, Arc::new(CookieJar::new()))?.exec(request);
+
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[rstest]
+    async fn should_not_follow_redirects_if_requested() -> Result<()> {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/old"))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", "/new"))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/new"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("new"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Client::new(&Config::default(), Arc::new(CookieJar::new()))?;
+        let url = format!("{}/old", mock_server.uri());
+
+        let response = client.exec(request(Method::GET, url.clone(), Body::plain("")))?;
+        assert_eq!(response.body(), "new");
+
+        let mut request = request(Method::GET, url, Body::plain(""));
+        request.metadata.no_redirect = Some(true);
+        let response = client.exec(request)?;
+        assert_eq!(response.status(), &reqwest::StatusCode::FOUND);
 
         Ok(())
     }
@@ -0,0 +1,420 @@
+use std::cmp::Reverse;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File";
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub include_subdomains: bool,
+    pub path: String,
+    pub secure: bool,
+    pub http_only: bool,
+    /// unix timestamp in seconds, 0 for session cookies
+    pub expires: u64,
+}
+
+impl StoredCookie {
+    fn is_expired(&self, now: u64) -> bool {
+        self.expires != 0 && self.expires <= now
+    }
+
+    fn matches(&self, url: &Url, now: u64) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host.to_lowercase(),
+            None => return false,
+        };
+
+        !self.is_expired(now)
+            && (!self.secure || url.scheme() == "https")
+            && domain_matches(&host, &self.domain, self.include_subdomains)
+            && path_matches(url.path(), &self.path)
+    }
+
+    fn same_identity(&self, other: &StoredCookie) -> bool {
+        self.name == other.name && self.domain == other.domain && self.path == other.path
+    }
+}
+
+/// A cookie store shared by all requests of a run. Can be loaded from and saved to
+/// a file in the netscape format that curl uses for `--cookie-jar`.
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    cookies: RwLock<Vec<StoredCookie>>,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads cookies from the given file. A file that doesn't exist yet results in an empty jar.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Error opening cookie jar {}", path.to_str().unwrap()))?;
+        let cookies = parse_netscape(&content)
+            .with_context(|| format!("error reading cookie jar {}", path.to_str().unwrap()))?;
+
+        Ok(CookieJar {
+            cookies: RwLock::new(cookies),
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let now = now();
+        let cookies = self.cookies.read().unwrap();
+        let content = format_netscape(cookies.iter().filter(|it| !it.is_expired(now)));
+
+        std::fs::write(path, content)
+            .with_context(|| format!("Error writing cookie jar {}", path.to_str().unwrap()))
+    }
+
+    pub fn cookies_list(&self) -> Vec<StoredCookie> {
+        self.cookies.read().unwrap().clone()
+    }
+
+    fn store(&self, cookie: StoredCookie) {
+        let mut cookies = self.cookies.write().unwrap();
+        cookies.retain(|it| !it.same_identity(&cookie));
+        if !cookie.is_expired(now()) {
+            cookies.push(cookie);
+        }
+    }
+}
+
+impl CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        for header in cookie_headers {
+            if let Some(cookie) = header
+                .to_str()
+                .ok()
+                .and_then(|it| parse_set_cookie(it, url, now()))
+            {
+                self.store(cookie);
+            }
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let now = now();
+        let cookies = self.cookies.read().unwrap();
+        let mut matching = cookies
+            .iter()
+            .filter(|it| it.matches(url, now))
+            .collect::<Vec<_>>();
+        if matching.is_empty() {
+            return None;
+        }
+
+        // cookies with longer paths are sent first, see RFC 6265 5.4
+        matching.sort_by_key(|it| Reverse(it.path.len()));
+        let header = matching
+            .into_iter()
+            .map(|it| format!("{}={}", it.name, it.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|it| it.as_secs())
+        .unwrap_or(0)
+}
+
+fn parse_set_cookie(header: &str, url: &Url, now: u64) -> Option<StoredCookie> {
+    let cookie = cookie::Cookie::parse(header).ok()?;
+    let host = url.host_str()?.to_lowercase();
+
+    let (domain, include_subdomains) = match cookie.domain() {
+        Some(domain) if !domain.is_empty() => {
+            let domain = domain.trim_start_matches('.').to_lowercase();
+            if !domain_matches(&host, &domain, true) {
+                return None;
+            }
+            (domain, true)
+        }
+        _ => (host, false),
+    };
+
+    let path = match cookie.path() {
+        Some(path) if path.starts_with('/') => path.to_string(),
+        _ => default_path(url.path()),
+    };
+
+    // max-age takes precedence over expires, see RFC 6265 5.3
+    let expires = match (cookie.max_age(), cookie.expires_datetime()) {
+        (Some(max_age), _) => match max_age.whole_seconds() {
+            secs if secs <= 0 => 1,
+            secs => now + secs as u64,
+        },
+        (None, Some(expires)) => expires.unix_timestamp().max(1) as u64,
+        (None, None) => 0,
+    };
+
+    Some(StoredCookie {
+        name: cookie.name().to_string(),
+        value: cookie.value().to_string(),
+        domain,
+        include_subdomains,
+        path,
+        secure: cookie.secure().unwrap_or(false),
+        http_only: cookie.http_only().unwrap_or(false),
+        expires,
+    })
+}
+
+fn domain_matches(host: &str, domain: &str, include_subdomains: bool) -> bool {
+    host == domain
+        || (include_subdomains
+            && host.len() > domain.len()
+            && host.ends_with(domain)
+            && host[..host.len() - domain.len()].ends_with('.'))
+}
+
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/')
+                || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => request_path[..index].to_string(),
+    }
+}
+
+fn parse_netscape(content: &str) -> Result<Vec<StoredCookie>> {
+    let mut ret = vec![];
+
+    for (index, line) in content.lines().enumerate() {
+        let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields = line.split('\t').collect::<Vec<_>>();
+        if fields.len() != 7 {
+            return Err(anyhow!(
+                "line {}: expected 7 tab-separated fields, found {}",
+                index + 1,
+                fields.len()
+            ));
+        }
+
+        let expires = fields[4]
+            .parse::<u64>()
+            .map_err(|_| anyhow!("line {}: invalid expiry '{}'", index + 1, fields[4]))?;
+
+        ret.push(StoredCookie {
+            domain: fields[0].trim_start_matches('.').to_lowercase(),
+            include_subdomains: fields[1].eq_ignore_ascii_case("TRUE"),
+            path: fields[2].to_string(),
+            secure: fields[3].eq_ignore_ascii_case("TRUE"),
+            expires,
+            name: fields[5].to_string(),
+            value: fields[6].to_string(),
+            http_only,
+        });
+    }
+
+    Ok(ret)
+}
+
+fn format_netscape<'a, I: Iterator<Item = &'a StoredCookie>>(cookies: I) -> String {
+    let mut ret = format!("{}\n", NETSCAPE_HEADER);
+
+    for cookie in cookies {
+        let bool_str = |b: bool| if b { "TRUE" } else { "FALSE" };
+        let domain = match cookie.include_subdomains {
+            true => format!(".{}", cookie.domain),
+            false => cookie.domain.clone(),
+        };
+
+        let _ = writeln!(
+            ret,
+            "{prefix}{domain}\t{subdomains}\t{path}\t{secure}\t{expires}\t{name}\t{value}",
+            prefix = if cookie.http_only { HTTP_ONLY_PREFIX } else { "" },
+            domain = domain,
+            subdomains = bool_str(cookie.include_subdomains),
+            path = cookie.path,
+            secure = bool_str(cookie.secure),
+            expires = cookie.expires,
+            name = cookie.name,
+            value = cookie.value,
+        );
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use temp_dir::TempDir;
+
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    fn set_cookie(jar: &CookieJar, url: &Url, headers: &[&'static str]) {
+        let headers = headers
+            .iter()
+            .map(|it| HeaderValue::from_static(it))
+            .collect::<Vec<_>>();
+        jar.set_cookies(&mut headers.iter(), url);
+    }
+
+    #[test]
+    fn should_send_cookies_back_to_the_same_host() {
+        let jar = CookieJar::new();
+        let login = url("http://localhost:8080/login");
+        set_cookie(&jar, &login, &["session=abc; Path=/; HttpOnly"]);
+
+        assert_eq!(
+            jar.cookies(&url("http://localhost:8080/api/me")),
+            Some(HeaderValue::from_static("session=abc"))
+        );
+        assert_eq!(jar.cookies(&url("http://otherhost/api/me")), None);
+    }
+
+    #[test]
+    fn should_respect_domain_path_and_secure_attributes() {
+        let jar = CookieJar::new();
+        set_cookie(
+            &jar,
+            &url("https://auth.example.com/login"),
+            &[
+                "a=1; Domain=example.com; Path=/",
+                "b=2; Path=/admin",
+                "c=3; Secure",
+                "d=4; Domain=other.com",
+            ],
+        );
+
+        assert_eq!(
+            jar.cookies(&url("https://api.example.com/")),
+            Some(HeaderValue::from_static("a=1"))
+        );
+        assert_eq!(
+            jar.cookies(&url("https://auth.example.com/admin/users")),
+            Some(HeaderValue::from_static("b=2; a=1; c=3"))
+        );
+        assert_eq!(
+            jar.cookies(&url("http://auth.example.com/administrator")),
+            Some(HeaderValue::from_static("a=1"))
+        );
+    }
+
+    #[test]
+    fn should_replace_and_delete_cookies() {
+        let jar = CookieJar::new();
+        let target = url("http://localhost/");
+        set_cookie(&jar, &target, &["session=abc", "other=1"]);
+        set_cookie(&jar, &target, &["session=def", "other=; Max-Age=0"]);
+
+        assert_eq!(
+            jar.cookies(&target),
+            Some(HeaderValue::from_static("session=def"))
+        );
+    }
+
+    #[test]
+    fn should_save_and_load_netscape_files() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let file = workdir.child("cookies.txt");
+
+        let jar = CookieJar::new();
+        set_cookie(
+            &jar,
+            &url("https://auth.example.com/login"),
+            &[
+                "session=abc; Path=/; HttpOnly",
+                "pref=dark; Domain=example.com; Path=/app; Secure; Expires=Wed, 01 Jan 2020 00:00:00 GMT",
+                "theme=light; Domain=example.com; Path=/; Expires=Fri, 01 Jan 2100 00:00:00 GMT",
+            ],
+        );
+        jar.save(&file)?;
+
+        assert_eq!(
+            std::fs::read_to_string(&file)?,
+            indoc!(
+                "
+                # Netscape HTTP Cookie File
+                #HttpOnly_auth.example.com\tFALSE\t/\tFALSE\t0\tsession\tabc
+                .example.com\tTRUE\t/\tFALSE\t4102444800\ttheme\tlight
+            "
+            )
+        );
+
+        let loaded = CookieJar::load(&file)?;
+        assert_eq!(loaded.cookies_list(), jar.cookies_list());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_load_curl_cookie_jars() -> Result<()> {
+        let cookies = parse_netscape(indoc!(
+            "
+            # Netscape HTTP Cookie File
+            # https://curl.se/docs/http-cookies.html
+
+            .example.com\tTRUE\t/\tTRUE\t0\tsid\t123
+        "
+        ))?;
+
+        assert_eq!(
+            cookies,
+            vec![StoredCookie {
+                name: "sid".to_string(),
+                value: "123".to_string(),
+                domain: "example.com".to_string(),
+                include_subdomains: true,
+                path: "/".to_string(),
+                secure: true,
+                http_only: false,
+                expires: 0,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_start_with_an_empty_jar_for_missing_files() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let jar = CookieJar::load(workdir.child("does-not-exist.txt"))?;
+
+        assert!(jar.cookies_list().is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use anyhow::{anyhow, Result};
+
+use crate::execution::curl::Curl;
+use crate::parsers::Request;
+use crate::postprocessing::response_handler::ResponseHandler;
+use crate::request_sources::request_id::RequestId;
+use crate::shell::single_quote;
+
+/// The start of scripts exported with `--curl --script`. The response headers of dependencies
+/// are written to a temporary directory, `response_header VARIABLE NAME` reads them.
+pub const SCRIPT_PREAMBLE: &str = r#"#!/usr/bin/env bash
+set -euo pipefail
+
+headers=$(mktemp -d)
+trap 'rm -rf "$headers"' EXIT
+
+response_header() {
+    grep -i "^$2:" "$headers/$1" | tail -n 1 | cut -d ' ' -f 2- | tr -d '\r'
+}"#;
+
+/// The name of the shell variable holding the response of a dependency, e.g. `TOKEN` for
+/// `token.http` or `API_LOGIN` for `api.http#login`. Requests with the same name get numeric
+/// suffixes, see `ResponseStore::store_shell_variable`.
+pub fn shell_variable_name(id: &RequestId) -> String {
+    let file_name = id.path.file_name();
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+    let name = match id.name {
+        Some(ref name) => format!("{}_{}", stem, name),
+        None => stem.to_owned(),
+    };
+
+    let mut ret = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect::<String>();
+    if ret.starts_with(|c: char| c.is_ascii_digit()) {
+        ret.insert(0, '_');
+    }
+
+    ret
+}
+
+/// A command assigning the processed response of a dependency to its shell variable. Json
+/// response handlers become `jq` filters.
+pub fn dependency_assignment(id: &RequestId, variable: &str, req: &Request) -> Result<String> {
+    let filter = match &req.response_handler {
+        None => String::new(),
+        Some(ResponseHandler::Json { json_path }) => {
+            format!(" \\\n| jq -rc {}", single_quote(&jq_filter(json_path)?))
+        }
+        Some(_) => {
+            return Err(anyhow!(
+            "{} has a response handler that can't be exported, only json handlers are supported",
+            id
+        ))
+        }
+    };
+
+    Ok(format!(
+        "{variable}=$({curl} \\\n--silent --show-error --fail --dump-header \"$headers/{variable}\"{filter})",
+        variable = variable,
+        curl = req.curl(),
+        filter = filter,
+    ))
+}
+
+/// Translates a jsonpath into a jq filter selecting its first match.
+fn jq_filter(json_path: &str) -> Result<String> {
+    let error = || anyhow!("jsonpath '{}' can't be translated to jq", json_path);
+    let mut rest = json_path.strip_prefix('$').ok_or_else(error)?;
+    let mut filter = String::new();
+    let mut wildcard = false;
+
+    while !rest.is_empty() {
+        if rest.starts_with("..") {
+            return Err(error());
+        } else if let Some(tail) = rest.strip_prefix(".*").or_else(|| rest.strip_prefix("[*]")) {
+            filter.push_str("[]");
+            wildcard = true;
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix('.') {
+            let end = tail
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(tail.len());
+            if end == 0 {
+                return Err(error());
+            }
+            filter.push('.');
+            filter.push_str(&jq_key(&tail[..end]));
+            rest = &tail[end..];
+        } else if let Some(tail) = rest.strip_prefix('[') {
+            let end = tail.find(']').ok_or_else(error)?;
+            let selector = &tail[..end];
+            match selector.parse::<i64>() {
+                Ok(index) => filter.push_str(&format!("[{}]", index)),
+                Err(_) => {
+                    let key = selector
+                        .strip_prefix('\'')
+                        .and_then(|it| it.strip_suffix('\''))
+                        .or_else(|| {
+                            selector
+                                .strip_prefix('"')
+                                .and_then(|it| it.strip_suffix('"'))
+                        })
+                        .ok_or_else(error)?;
+                    filter.push('.');
+                    filter.push_str(&jq_key(key));
+                }
+            }
+            rest = &tail[end + 1..];
+        } else {
+            return Err(error());
+        }
+    }
+
+    Ok(match (filter.is_empty(), wildcard) {
+        (true, _) => ".".to_owned(),
+        (false, true) => format!("first({})", filter),
+        (false, false) => filter,
+    })
+}
+
+fn jq_key(key: &str) -> String {
+    let identifier = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match identifier {
+        true => key.to_owned(),
+        false => serde_json::to_string(key).unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use rstest::rstest;
+
+    use crate::test_utils::root;
+
+    use super::*;
+
+    #[rstest]
+    #[case("$", ".")]
+    #[case("$.token", ".token")]
+    #[case("$.data.access_token", ".data.access_token")]
+    #[case("$.items[0].id", ".items[0].id")]
+    #[case("$['user-id']", r#"."user-id""#)]
+    #[case("$.data.user-id", r#".data."user-id""#)]
+    #[case("$.items[*].id", "first(.items[].id)")]
+    #[case("$.items.*", "first(.items[])")]
+    fn should_translate_jsonpaths_to_jq(#[case] json_path: &str, #[case] expected: &str) {
+        assert_eq!(jq_filter(json_path).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("token")]
+    #[case("$..id")]
+    #[case("$.items[?(@.id > 1)]")]
+    fn should_reject_untranslatable_jsonpaths(#[case] json_path: &str) {
+        assert_eq!(
+            jq_filter(json_path).unwrap_err().to_string(),
+            format!("jsonpath '{}' can't be translated to jq", json_path)
+        );
+    }
+
+    #[test]
+    fn should_name_shell_variables_after_requests() {
+        let path = root().join("resources/test/requests/nested_dependencies/1.http");
+
+        assert_eq!(
+            shell_variable_name(&RequestId::new(path.clone(), None)),
+            "_1"
+        );
+        assert_eq!(
+            shell_variable_name(&RequestId::new(path, Some("get token".into()))),
+            "_1_GET_TOKEN"
+        );
+    }
+
+    #[test]
+    fn should_assign_processed_responses_to_shell_variables() -> Result<()> {
+        let id = RequestId::new(
+            root().join("resources/test/requests/nested_dependencies/1.http"),
+            None,
+        );
+        let mut req = Request::basic("POST", "http://localhost/token");
+        req.response_handler = Some(ResponseHandler::Json {
+            json_path: r#"$["it's"]"#.into(),
+        });
+
+        assert_eq!(
+            dependency_assignment(&id, "_1", &req)?,
+            indoc!(
+                r#"
+                _1=$(curl -X POST \
+                --url "http://localhost/token" \
+                --silent --show-error --fail --dump-header "$headers/_1" \
+                | jq -rc '."it'\''s"')"#
+            )
+        );
+
+        req.response_handler = Some(ResponseHandler::Rhai {
+            program: "body".into(),
+        });
+        assert!(dependency_assignment(&id, "_1", &req).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::execution::export::joined_headers;
+use crate::parsers::Request;
+use crate::request::body::{Body, MultipartPart};
+
+/// How `--dry-run` prints resolved requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DryRunFormat {
+    /// like a `.http` request file
+    #[default]
+    Http,
+    /// like a json request file
+    Json,
+    /// like a yaml request file
+    Yaml,
+}
+
+impl FromStr for DryRunFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "http" => Ok(DryRunFormat::Http),
+            "json" => Ok(DryRunFormat::Json),
+            "yaml" => Ok(DryRunFormat::Yaml),
+            _ => Err(anyhow!(
+                "unknown format '{}', expected http, json or yaml",
+                s
+            )),
+        }
+    }
+}
+
+pub trait DryRun {
+    /// The resolved method, url, headers and body of the request.
+    fn dry_run(&self, format: DryRunFormat) -> Result<String>;
+}
+
+impl DryRun for Request {
+    fn dry_run(&self, format: DryRunFormat) -> Result<String> {
+        match format {
+            DryRunFormat::Http => http(self),
+            DryRunFormat::Json => Ok(serde_json::to_string_pretty(&structured(self))?),
+            DryRunFormat::Yaml => Ok(serde_yaml::to_string(&structured(self))?),
+        }
+    }
+}
+
+fn http(req: &Request) -> Result<String> {
+    let mut lines = vec![format!("{} {}", req.method, req.url)];
+    for (name, value) in req.headers.iter() {
+        lines.push(format!(
+            "{}: {}",
+            name,
+            String::from_utf8_lossy(value.as_bytes())
+        ));
+    }
+
+    match &req.body {
+        Body::Plain(body) if body.is_empty() => (),
+        Body::Plain(body) => {
+            lines.push(String::new());
+            lines.push(body.clone());
+        }
+        Body::Multipart(parts) => {
+            lines.push(String::new());
+            for part in parts {
+                match part {
+                    MultipartPart::File {
+                        name, file_path, ..
+                    } => lines.push(format!(
+                        r#"${{file("{}", "{}")}}"#,
+                        name,
+                        file_path.to_str()
+                    )),
+                    MultipartPart::Text { .. } => {
+                        return Err(anyhow!(
+                            "multipart text parts can't be printed as a .http request, print it as json or yaml instead"
+                        ))
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(lines.join("\n"))
+}
+
+#[derive(Serialize)]
+struct StructuredRequest<'a> {
+    method: &'a str,
+    url: &'a str,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<&'a str, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<StructuredBody<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum StructuredBody<'a> {
+    Plain(&'a str),
+    Multipart(Vec<StructuredPart<'a>>),
+}
+
+#[derive(Serialize)]
+struct StructuredPart<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filepath: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mime: Option<&'a str>,
+}
+
+fn structured(req: &Request) -> StructuredRequest<'_> {
+    let headers: BTreeMap<&str, String> = joined_headers(req).into_iter().collect();
+
+    let body = match &req.body {
+        Body::Plain(body) if body.is_empty() => None,
+        Body::Plain(body) => Some(StructuredBody::Plain(body)),
+        Body::Multipart(parts) => Some(StructuredBody::Multipart(
+            parts
+                .iter()
+                .map(|part| match part {
+                    MultipartPart::File {
+                        name,
+                        file_path,
+                        mime_str,
+                    } => StructuredPart {
+                        name,
+                        text: None,
+                        filepath: Some(file_path.to_str()),
+                        mime: mime_str.as_deref(),
+                    },
+                    MultipartPart::Text {
+                        name,
+                        text,
+                        mime_str,
+                    } => StructuredPart {
+                        name,
+                        text: Some(text),
+                        filepath: None,
+                        mime: mime_str.as_deref(),
+                    },
+                })
+                .collect(),
+        )),
+    };
+
+    StructuredRequest {
+        method: req.method.as_str(),
+        url: &req.url,
+        headers,
+        body,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::{formatdoc, indoc};
+
+    use crate::request::body::MultipartPart;
+    use crate::test_utils::root;
+
+    use super::*;
+
+    #[test]
+    fn should_print_requests_in_http_form() -> Result<()> {
+        let request = Request::basic("POST", "http://localhost/users")
+            .add_header("content-type", "application/json")
+            .body("{\n    \"name\": \"jane\"\n}");
+
+        assert_eq!(
+            request.dry_run(DryRunFormat::Http)?,
+            indoc!(
+                r#"
+                POST http://localhost/users
+                content-type: application/json
+
+                {
+                    "name": "jane"
+                }"#
+            )
+        );
+        assert_eq!(
+            Request::basic("GET", "http://localhost/users").dry_run(DryRunFormat::Http)?,
+            "GET http://localhost/users"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_print_requests_in_json_form() -> Result<()> {
+        let request = Request::basic("POST", "http://localhost/users")
+            .add_header("content-type", "application/json")
+            .body(r#"{"name":"jane"}"#);
+
+        assert_eq!(
+            request.dry_run(DryRunFormat::Json)?,
+            indoc!(
+                r#"
+                {
+                  "method": "POST",
+                  "url": "http://localhost/users",
+                  "headers": {
+                    "content-type": "application/json"
+                  },
+                  "body": "{\"name\":\"jane\"}"
+                }"#
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_print_multipart_requests() -> Result<()> {
+        let image = root().join("resources/image.jpg");
+        let request = Request {
+            body: Body::Multipart(vec![
+                MultipartPart::File {
+                    name: "image".to_owned(),
+                    file_path: image.clone(),
+                    mime_str: Some("image/jpeg".to_owned()),
+                },
+                MultipartPart::Text {
+                    name: "title".to_owned(),
+                    text: "holiday".to_owned(),
+                    mime_str: None,
+                },
+            ]),
+            ..Request::basic("POST", "http://localhost/upload")
+        };
+
+        assert_eq!(
+            request.dry_run(DryRunFormat::Json)?,
+            formatdoc!(
+                r#"
+                {{
+                  "method": "POST",
+                  "url": "http://localhost/upload",
+                  "body": [
+                    {{
+                      "name": "image",
+                      "filepath": "{image}",
+                      "mime": "image/jpeg"
+                    }},
+                    {{
+                      "name": "title",
+                      "text": "holiday"
+                    }}
+                  ]
+                }}"#,
+                image = image.to_str(),
+            )
+        );
+        assert_eq!(
+            request.dry_run(DryRunFormat::Yaml)?,
+            formatdoc!(
+                r#"
+                method: POST
+                url: http://localhost/upload
+                body:
+                - name: image
+                  filepath: {image}
+                  mime: image/jpeg
+                - name: title
+                  text: holiday
+                "#,
+                image = image.to_str(),
+            )
+        );
+        assert!(request.dry_run(DryRunFormat::Http).is_err());
+
+        let request = Request {
+            body: Body::Multipart(vec![MultipartPart::File {
+                name: "image".to_owned(),
+                file_path: image.clone(),
+                mime_str: None,
+            }]),
+            ..Request::basic("POST", "http://localhost/upload")
+        };
+        assert_eq!(
+            request.dry_run(DryRunFormat::Http)?,
+            formatdoc!(
+                r#"
+                POST http://localhost/upload
+
+                ${{file("image", "{image}")}}"#,
+                image = image.to_str(),
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use anyhow::{anyhow, Result};
 use linked_hash_set::LinkedHashSet;
 
-use crate::path_utils::{CanonicalizedPathBuf, RelativePath};
+use crate::request_sources::request_id::RequestId;
 use crate::request_sources::variable_support::{get_env_vars, EnvVarOccurrence};
 use crate::Profile;
 use crate::RequestSource;
@@ -14,8 +16,8 @@ pub fn plan_request_order(
     let mut requests_with_dependencies = LinkedHashSet::new();
 
     for req in &initial_requests {
-        for path in get_env_vars_defined_through_requests(profile, req)? {
-            let req = RequestSource::from_file(&path, true)?;
+        for id in get_env_vars_defined_through_requests(profile, req)? {
+            let req = RequestSource::load(&id, true)?;
             preprocess_request(
                 req,
                 &mut requests_with_dependencies,
@@ -38,18 +40,18 @@ pub fn plan_request_order(
 fn preprocess_request(
     req: RequestSource,
     all_requests: &mut LinkedHashSet<RequestSource>,
-    preprocessor_stack: &mut Vec<CanonicalizedPathBuf>,
+    preprocessor_stack: &mut Vec<RequestId>,
 ) -> Result<()> {
     if all_requests.contains(&req) {
         return Ok(());
     }
-    if preprocessor_stack.contains(&req.source_path) {
+    if preprocessor_stack.contains(&req.id()) {
         return Err(anyhow!("cyclic dependency detected!"));
     }
-    preprocessor_stack.push(req.source_path.clone());
+    preprocessor_stack.push(req.id());
 
-    for dep in req.unescaped_dependency_paths()? {
-        let dep = RequestSource::from_file(dep, true)?;
+    for dep in req.unescaped_dependency_ids()? {
+        let dep = RequestSource::load(&dep, true)?;
         preprocess_request(dep, all_requests, preprocessor_stack)?;
     }
 
@@ -59,14 +61,43 @@ fn preprocess_request(
     Ok(())
 }
 
+/// Maps every planned request to the planned requests it directly depends on, either through
+/// `${request(...)}` expressions or through profile variables that are defined by requests.
+pub fn plan_dependency_graph(
+    planned_requests: &LinkedHashSet<RequestSource>,
+    profile: &Profile,
+) -> Result<HashMap<RequestId, Vec<RequestId>>> {
+    let planned_ids = planned_requests
+        .iter()
+        .map(RequestSource::id)
+        .collect::<Vec<_>>();
+    let mut graph = HashMap::new();
+
+    for req in planned_requests {
+        let mut dependencies: Vec<RequestId> = vec![];
+        for dep in req
+            .unescaped_dependency_ids()?
+            .into_iter()
+            .chain(get_env_vars_defined_through_requests(profile, req)?)
+        {
+            if planned_ids.contains(&dep) && !dependencies.contains(&dep) {
+                dependencies.push(dep);
+            }
+        }
+        graph.insert(req.id(), dependencies);
+    }
+
+    Ok(graph)
+}
+
 fn get_env_vars_defined_through_requests(
     profile: &Profile,
     req: &RequestSource,
-) -> Result<Vec<CanonicalizedPathBuf>> {
+) -> Result<Vec<RequestId>> {
     let vars: Vec<EnvVarOccurrence> = get_env_vars(&req.text);
     vars.into_iter()
         .flat_map(|occ| profile.defined_through_request(occ.name))
-        .map(|path| profile.get_dependency_path(path.to_str().unwrap()))
+        .map(|reference| RequestId::resolve(profile.source_path(), reference.to_str().unwrap()))
         .collect()
 }
 
@@ -78,8 +109,10 @@ mod tests {
     use indoc::indoc;
     use temp_dir::TempDir;
 
-    use crate::execution::execution_order::plan_request_order;
+    use crate::execution::execution_order::{plan_dependency_graph, plan_request_order};
     use crate::path_utils::canonicalize;
+    use crate::profiles::ProfileVariable;
+    use crate::request_sources::request_id::RequestId;
     use crate::test_utils::write_test_file;
     use crate::{Profile, RequestSource, ResponseStore};
 
@@ -137,6 +170,63 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn should_resolve_request_header_dependencies() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let r1 = write_test_file(
+            &workdir,
+            "1.http",
+            r#"GET ${request_header("create.http", "Location")}"#,
+        )?;
+        let create = write_test_file(&workdir, "create.http", r#"POST http://localhost"#)?;
+
+        let request = RequestSource::from_file(&r1, false)?;
+
+        let coll = plan_request_order(vec![request], &Profile::empty(env::current_dir()?))?
+            .into_iter()
+            .map(|req| req.source_path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(&coll, &[create, r1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_plan_the_dependency_graph() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let r1 = write_test_file(
+            &workdir,
+            "1.http",
+            r#"GET ${request("token.http")}/${env(ID)}"#,
+        )?;
+        let r2 = write_test_file(&workdir, "2.http", r#"GET ${request("token.http")}"#)?;
+        let token = write_test_file(&workdir, "token.http", r#"GET http://localhost"#)?;
+        let create = write_test_file(&workdir, "create.http", r#"POST ${request("token.http")}"#)?;
+
+        let profile = Profile::new(
+            workdir.path(),
+            maplit::hashmap! {
+                "ID".to_string() => ProfileVariable::Request { request: "create.http".to_string() },
+            },
+        );
+        let requests = vec![
+            RequestSource::from_file(&r1, false)?,
+            RequestSource::from_file(&r2, false)?,
+        ];
+
+        let plan = plan_request_order(requests, &profile)?;
+        let graph = plan_dependency_graph(&plan, &profile)?;
+
+        let [token, create, r1, r2] = [token, create, r1, r2].map(RequestId::from);
+        assert_eq!(graph[&token], vec![]);
+        assert_eq!(graph[&create], vec![token.clone()]);
+        assert_eq!(graph[&r1], vec![token.clone(), create]);
+        assert_eq!(graph[&r2], vec![token]);
+
+        Ok(())
+    }
+
     #[test]
     fn should_not_resolve_escaped_dependencies() -> Result<()> {
         let workdir = TempDir::new()?;
@@ -0,0 +1,176 @@
+use anyhow::Result;
+
+use crate::execution::export::{joined_headers, string_literal, Exporter};
+use crate::parsers::Request;
+use crate::request::body::{Body, MultipartPart};
+
+/// Exports requests as javascript modules for node, using `fetch`.
+pub struct FetchExporter;
+
+impl Exporter for FetchExporter {
+    fn name(&self) -> &'static str {
+        "fetch"
+    }
+
+    fn is_shell_command(&self) -> bool {
+        false
+    }
+
+    fn export(&self, req: &Request) -> Result<String> {
+        let mut lines = vec![];
+        let mut options = vec![format!("method: {}", string_literal(req.method.as_str()))];
+
+        let headers = joined_headers(req);
+        if !headers.is_empty() {
+            let entries = headers
+                .iter()
+                .map(|(name, value)| {
+                    format!("    {}: {},", string_literal(name), string_literal(value))
+                })
+                .collect::<Vec<_>>();
+            options.push(format!("headers: {{\n{}\n  }}", entries.join("\n")));
+        }
+
+        match &req.body {
+            Body::Plain(body) if body.is_empty() => (),
+            Body::Plain(body) => options.push(format!("body: {}", string_literal(body))),
+            Body::Multipart(parts) => {
+                if parts
+                    .iter()
+                    .any(|part| matches!(part, MultipartPart::File { .. }))
+                {
+                    lines.push("import { openAsBlob } from \"node:fs\";\n".to_owned());
+                }
+                lines.push("const body = new FormData();".to_owned());
+                for part in parts {
+                    lines.push(match part {
+                        MultipartPart::File {
+                            name,
+                            file_path,
+                            mime_str,
+                        } => {
+                            let options = match mime_str {
+                                Some(mime) => format!(", {{ type: {} }}", string_literal(mime)),
+                                None => String::new(),
+                            };
+                            format!(
+                                "body.append({}, await openAsBlob({}{}), {});",
+                                string_literal(name),
+                                string_literal(file_path.to_str()),
+                                options,
+                                string_literal(file_path.file_name())
+                            )
+                        }
+                        MultipartPart::Text {
+                            name,
+                            text,
+                            mime_str: Some(mime),
+                        } => format!(
+                            "body.append({}, new Blob([{}], {{ type: {} }}));",
+                            string_literal(name),
+                            string_literal(text),
+                            string_literal(mime)
+                        ),
+                        MultipartPart::Text {
+                            name,
+                            text,
+                            mime_str: None,
+                        } => format!(
+                            "body.append({}, {});",
+                            string_literal(name),
+                            string_literal(text)
+                        ),
+                    });
+                }
+                lines.push(String::new());
+                options.push("body".to_owned());
+            }
+        }
+
+        lines.push(format!(
+            "const response = await fetch({}, {{\n  {},\n}});",
+            string_literal(&req.url),
+            options.join(",\n  ")
+        ));
+        lines.push("console.log(await response.text());".to_owned());
+
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::{formatdoc, indoc};
+
+    use crate::test_utils::root;
+
+    use super::*;
+
+    #[test]
+    fn should_export_requests() -> Result<()> {
+        let req = Request::basic("POST", "http://localhost/users")
+            .add_header("content-type", "application/json")
+            .body(r#"{"name": "jane"}"#);
+
+        assert_eq!(
+            FetchExporter.export(&req)?,
+            indoc!(
+                r#"
+                const response = await fetch("http://localhost/users", {
+                  method: "POST",
+                  headers: {
+                    "content-type": "application/json",
+                  },
+                  body: "{\"name\": \"jane\"}",
+                });
+                console.log(await response.text());"#
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_multipart_requests() -> Result<()> {
+        let image = root().join("resources/image.jpg");
+        let req = Request::basic("POST", "http://localhost/upload").multipart(&[
+            MultipartPart::File {
+                name: "image".to_owned(),
+                file_path: image.clone(),
+                mime_str: Some("image/jpeg".to_owned()),
+            },
+            MultipartPart::Text {
+                name: "metadata".to_owned(),
+                text: r#"{"a": 1}"#.to_owned(),
+                mime_str: Some("application/json".to_owned()),
+            },
+            MultipartPart::Text {
+                name: "title".to_owned(),
+                text: "holiday".to_owned(),
+                mime_str: None,
+            },
+        ]);
+
+        assert_eq!(
+            FetchExporter.export(&req)?,
+            formatdoc!(
+                r#"
+                import {{ openAsBlob }} from "node:fs";
+
+                const body = new FormData();
+                body.append("image", await openAsBlob("{image}", {{ type: "image/jpeg" }}), "image.jpg");
+                body.append("metadata", new Blob(["{{\"a\": 1}}"], {{ type: "application/json" }}));
+                body.append("title", "holiday");
+
+                const response = await fetch("http://localhost/upload", {{
+                  method: "POST",
+                  body,
+                }});
+                console.log(await response.text());"#,
+                image = image.to_str(),
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::Result;
+
+use crate::execution::export::{joined_headers, Exporter};
+use crate::parsers::Request;
+use crate::request::body::{Body, MultipartPart};
+use crate::shell::dollar_preserving_quote;
+
+pub struct HttpieExporter;
+
+impl Exporter for HttpieExporter {
+    fn name(&self) -> &'static str {
+        "httpie"
+    }
+
+    fn is_shell_command(&self) -> bool {
+        true
+    }
+
+    fn export(&self, req: &Request) -> Result<String> {
+        let mut parts = vec![];
+        if let Body::Multipart(_) = req.body {
+            parts.push("--multipart".to_owned());
+        }
+        parts.push(format!(
+            "{} {}",
+            req.method,
+            dollar_preserving_quote(&req.url)
+        ));
+
+        for (name, value) in joined_headers(req) {
+            // `name;` sends a header without value, `name:` would leave it out
+            parts.push(match value.is_empty() {
+                true => dollar_preserving_quote(&format!("{};", name)),
+                false => dollar_preserving_quote(&format!("{}:{}", name, value)),
+            });
+        }
+
+        match &req.body {
+            Body::Plain(body) if body.is_empty() => (),
+            Body::Plain(body) => parts.push(format!("--raw {}", dollar_preserving_quote(body))),
+            Body::Multipart(multiparts) => {
+                for part in multiparts {
+                    parts.push(match part {
+                        MultipartPart::File {
+                            name,
+                            file_path,
+                            mime_str,
+                        } => {
+                            let mime = match mime_str {
+                                Some(mime) => format!(";type={}", mime),
+                                None => String::new(),
+                            };
+                            dollar_preserving_quote(&format!(
+                                "{}@{}{}",
+                                name,
+                                file_path.to_str(),
+                                mime
+                            ))
+                        }
+                        // httpie can't set the content type of text fields
+                        MultipartPart::Text { name, text, .. } => {
+                            dollar_preserving_quote(&format!("{}={}", name, text))
+                        }
+                    });
+                }
+            }
+        }
+
+        Ok(format!("http --ignore-stdin {}", parts.join(" \\\n")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::{formatdoc, indoc};
+
+    use crate::test_utils::root;
+
+    use super::*;
+
+    #[test]
+    fn should_export_requests() -> Result<()> {
+        let req = Request::basic("POST", "http://localhost/users?q=\"x\"")
+            .add_header("content-type", "application/json")
+            .add_header("x-empty", "")
+            .body("{\n  \"name\": \"$(pass name)\"\n}");
+
+        assert_eq!(
+            HttpieExporter.export(&req)?,
+            indoc!(
+                r#"
+                http --ignore-stdin POST "http://localhost/users?q=\"x\"" \
+                "content-type:application/json" \
+                "x-empty;" \
+                --raw "{
+                  \"name\": \"$(pass name)\"
+                }""#
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_multipart_requests() -> Result<()> {
+        let image = root().join("resources/image.jpg");
+        let req = Request::basic("POST", "http://localhost/upload").multipart(&[
+            MultipartPart::File {
+                name: "image".to_owned(),
+                file_path: image.clone(),
+                mime_str: Some("image/jpeg".to_owned()),
+            },
+            MultipartPart::Text {
+                name: "title".to_owned(),
+                text: "holiday".to_owned(),
+                mime_str: None,
+            },
+        ]);
+
+        assert_eq!(
+            HttpieExporter.export(&req)?,
+            formatdoc!(
+                r#"
+                http --ignore-stdin --multipart \
+                POST "http://localhost/upload" \
+                "image@{image};type=image/jpeg" \
+                "title=holiday""#,
+                image = image.to_str(),
+            )
+        );
+
+        Ok(())
+    }
+}
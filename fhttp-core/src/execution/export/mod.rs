@@ -0,0 +1,127 @@
+use std::fmt::{self, Debug, Formatter};
+
+use anyhow::{anyhow, Result};
+
+use crate::execution::curl::Curl;
+use crate::parsers::Request;
+
+mod fetch;
+mod httpie;
+mod python;
+mod rust_reqwest;
+mod wget;
+
+/// Turns requests into snippets for other tools, e.g. `--export httpie`.
+pub trait Exporter: Sync {
+    /// The name to select the exporter with.
+    fn name(&self) -> &'static str;
+
+    /// Whether the snippets are shell commands, so secrets can be exported as evaluations like
+    /// `$(pass path)` instead of being resolved.
+    fn is_shell_command(&self) -> bool;
+
+    fn export(&self, req: &Request) -> Result<String>;
+}
+
+impl Debug for dyn Exporter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Exporter({})", self.name())
+    }
+}
+
+pub struct CurlExporter;
+
+impl Exporter for CurlExporter {
+    fn name(&self) -> &'static str {
+        "curl"
+    }
+
+    fn is_shell_command(&self) -> bool {
+        true
+    }
+
+    fn export(&self, req: &Request) -> Result<String> {
+        Ok(req.curl())
+    }
+}
+
+/// All exporters, in the order they're listed in errors and help texts.
+pub static EXPORTERS: [&dyn Exporter; 6] = [
+    &CurlExporter,
+    &httpie::HttpieExporter,
+    &wget::WgetExporter,
+    &python::PythonExporter,
+    &fetch::FetchExporter,
+    &rust_reqwest::ReqwestExporter,
+];
+
+pub fn find_exporter(name: &str) -> Result<&'static dyn Exporter> {
+    EXPORTERS
+        .iter()
+        .find(|it| it.name() == name)
+        .copied()
+        .ok_or_else(|| {
+            anyhow!(
+                "unknown export format '{}', expected one of {}",
+                name,
+                EXPORTERS.map(|it| it.name()).join(", ")
+            )
+        })
+}
+
+/// The headers of the request, values of repeated headers are joined with commas.
+pub(crate) fn joined_headers(req: &Request) -> Vec<(&str, String)> {
+    let mut ret: Vec<(&str, String)> = vec![];
+    for (name, value) in req.headers.iter() {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        match ret.iter_mut().find(|(it, _)| *it == name.as_str()) {
+            Some((_, existing)) => {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            }
+            None => ret.push((name.as_str(), value.into_owned())),
+        }
+    }
+
+    ret
+}
+
+/// A string literal for languages that accept json strings, like python and javascript.
+fn string_literal(text: &str) -> String {
+    serde_json::to_string(text).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_find_exporters_by_name() -> Result<()> {
+        for exporter in EXPORTERS {
+            assert_eq!(find_exporter(exporter.name())?.name(), exporter.name());
+        }
+        assert_eq!(
+            find_exporter("postman").unwrap_err().to_string(),
+            "unknown export format 'postman', expected one of curl, httpie, wget, python, fetch, reqwest"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_join_repeated_headers() {
+        let mut req = Request::basic("GET", "http://localhost")
+            .add_header("accept", "text/plain")
+            .add_header("x-id", "1");
+        req.headers
+            .append("accept", "application/json".parse().unwrap());
+
+        assert_eq!(
+            joined_headers(&req),
+            vec![
+                ("accept", "text/plain, application/json".to_owned()),
+                ("x-id", "1".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+
+use crate::execution::export::{joined_headers, string_literal, Exporter};
+use crate::parsers::Request;
+use crate::request::body::{Body, MultipartPart};
+
+pub struct PythonExporter;
+
+impl Exporter for PythonExporter {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn is_shell_command(&self) -> bool {
+        false
+    }
+
+    fn export(&self, req: &Request) -> Result<String> {
+        let mut arguments = vec![
+            string_literal(req.method.as_str()),
+            string_literal(&req.url),
+        ];
+
+        let headers = joined_headers(req);
+        if !headers.is_empty() {
+            let entries = headers
+                .iter()
+                .map(|(name, value)| {
+                    format!(
+                        "        {}: {},",
+                        string_literal(name),
+                        string_literal(value)
+                    )
+                })
+                .collect::<Vec<_>>();
+            arguments.push(format!("headers={{\n{}\n    }}", entries.join("\n")));
+        }
+
+        match &req.body {
+            Body::Plain(body) if body.is_empty() => (),
+            Body::Plain(body) => arguments.push(format!("data={}", string_literal(body))),
+            Body::Multipart(parts) => {
+                let entries = parts
+                    .iter()
+                    .map(|part| {
+                        let (name, value) = match part {
+                            MultipartPart::File {
+                                name,
+                                file_path,
+                                mime_str,
+                            } => {
+                                let mut tuple = vec![
+                                    string_literal(file_path.file_name()),
+                                    format!("open({}, \"rb\")", string_literal(file_path.to_str())),
+                                ];
+                                tuple.extend(mime_str.as_deref().map(string_literal));
+                                (name, tuple)
+                            }
+                            MultipartPart::Text {
+                                name,
+                                text,
+                                mime_str,
+                            } => {
+                                let mut tuple = vec!["None".to_owned(), string_literal(text)];
+                                tuple.extend(mime_str.as_deref().map(string_literal));
+                                (name, tuple)
+                            }
+                        };
+                        format!(
+                            "        ({}, ({})),",
+                            string_literal(name),
+                            value.join(", ")
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                // a list keeps the order of the parts and allows repeated names
+                arguments.push(format!("files=[\n{}\n    ]", entries.join("\n")));
+            }
+        }
+
+        Ok(format!(
+            "import requests\n\nresponse = requests.request(\n    {},\n)\nprint(response.text)",
+            arguments.join(",\n    ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::{formatdoc, indoc};
+
+    use crate::test_utils::root;
+
+    use super::*;
+
+    #[test]
+    fn should_export_requests() -> Result<()> {
+        let req = Request::basic("POST", "http://localhost/users")
+            .add_header("content-type", "application/json")
+            .body("{\n  \"name\": \"jäne\"\n}");
+
+        assert_eq!(
+            PythonExporter.export(&req)?,
+            indoc!(
+                r#"
+                import requests
+
+                response = requests.request(
+                    "POST",
+                    "http://localhost/users",
+                    headers={
+                        "content-type": "application/json",
+                    },
+                    data="{\n  \"name\": \"jäne\"\n}",
+                )
+                print(response.text)"#
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_multipart_requests() -> Result<()> {
+        let image = root().join("resources/image.jpg");
+        let req = Request::basic("POST", "http://localhost/upload").multipart(&[
+            MultipartPart::File {
+                name: "image".to_owned(),
+                file_path: image.clone(),
+                mime_str: Some("image/jpeg".to_owned()),
+            },
+            MultipartPart::Text {
+                name: "title".to_owned(),
+                text: "holiday".to_owned(),
+                mime_str: None,
+            },
+        ]);
+
+        assert_eq!(
+            PythonExporter.export(&req)?,
+            formatdoc!(
+                r#"
+                import requests
+
+                response = requests.request(
+                    "POST",
+                    "http://localhost/upload",
+                    files=[
+                        ("image", ("image.jpg", open("{image}", "rb"), "image/jpeg")),
+                        ("title", (None, "holiday")),
+                    ],
+                )
+                print(response.text)"#,
+                image = image.to_str(),
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use anyhow::Result;
+use reqwest::Method;
+
+use crate::execution::export::Exporter;
+use crate::parsers::Request;
+use crate::request::body::{Body, MultipartPart};
+
+/// Exports requests as rust programs using the blocking client of reqwest.
+pub struct ReqwestExporter;
+
+const STANDARD_METHODS: [Method; 9] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::DELETE,
+    Method::HEAD,
+    Method::OPTIONS,
+    Method::CONNECT,
+    Method::PATCH,
+    Method::TRACE,
+];
+
+impl Exporter for ReqwestExporter {
+    fn name(&self) -> &'static str {
+        "reqwest"
+    }
+
+    fn is_shell_command(&self) -> bool {
+        false
+    }
+
+    fn export(&self, req: &Request) -> Result<String> {
+        let mut lines = vec!["fn main() -> Result<(), Box<dyn std::error::Error>> {".to_owned()];
+
+        if let Body::Multipart(parts) = &req.body {
+            lines.push("    use reqwest::blocking::multipart::{Form, Part};\n".to_owned());
+            lines.push("    let form = Form::new()".to_owned());
+            for part in parts {
+                lines.push(match part {
+                    MultipartPart::File {
+                        name,
+                        file_path,
+                        mime_str: None,
+                    } => format!(
+                        "        .file({}, {})?",
+                        rust_string_literal(name),
+                        rust_string_literal(file_path.to_str())
+                    ),
+                    MultipartPart::File {
+                        name,
+                        file_path,
+                        mime_str: Some(mime),
+                    } => format!(
+                        "        .part({}, Part::file({})?.mime_str({})?)",
+                        rust_string_literal(name),
+                        rust_string_literal(file_path.to_str()),
+                        rust_string_literal(mime)
+                    ),
+                    MultipartPart::Text {
+                        name,
+                        text,
+                        mime_str: None,
+                    } => format!(
+                        "        .text({}, {})",
+                        rust_string_literal(name),
+                        rust_string_literal(text)
+                    ),
+                    MultipartPart::Text {
+                        name,
+                        text,
+                        mime_str: Some(mime),
+                    } => format!(
+                        "        .part({}, Part::text({}).mime_str({})?)",
+                        rust_string_literal(name),
+                        rust_string_literal(text),
+                        rust_string_literal(mime)
+                    ),
+                });
+            }
+            lines.last_mut().unwrap().push_str(";\n");
+        }
+
+        let method = match STANDARD_METHODS.contains(&req.method) {
+            true => format!("reqwest::Method::{}", req.method),
+            false => format!(
+                "reqwest::Method::from_bytes({})?",
+                rust_string_literal(req.method.as_str())
+            ),
+        };
+        lines.push("    let response = reqwest::blocking::Client::new()".to_owned());
+        lines.push(format!(
+            "        .request({}, {})",
+            method,
+            rust_string_literal(&req.url)
+        ));
+        for (name, value) in req.headers.iter() {
+            lines.push(format!(
+                "        .header({}, {})",
+                rust_string_literal(name.as_str()),
+                rust_string_literal(&String::from_utf8_lossy(value.as_bytes()))
+            ));
+        }
+        match &req.body {
+            Body::Plain(body) if body.is_empty() => (),
+            Body::Plain(body) => {
+                lines.push(format!("        .body({})", rust_string_literal(body)))
+            }
+            Body::Multipart(_) => lines.push("        .multipart(form)".to_owned()),
+        }
+        lines.push("        .send()?;".to_owned());
+        lines.push("    println!(\"{}\", response.text()?);\n".to_owned());
+        lines.push("    Ok(())".to_owned());
+        lines.push("}".to_owned());
+
+        Ok(lines.join("\n"))
+    }
+}
+
+/// A rust string literal. The json literals of `export::string_literal` escape control
+/// characters as `\uXXXX`, which rust doesn't understand.
+fn rust_string_literal(text: &str) -> String {
+    format!("{:?}", text)
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use indoc::{formatdoc, indoc};
+
+    use crate::test_utils::root;
+
+    use super::*;
+
+    #[test]
+    fn should_export_requests() -> Result<()> {
+        let req = Request::basic("POST", "http://localhost/users")
+            .add_header("content-type", "application/json")
+            .body("{\n  \"name\": \"jane\\u0000\"\n}");
+
+        assert_eq!(
+            ReqwestExporter.export(&req)?,
+            indoc!(
+                r#"
+                fn main() -> Result<(), Box<dyn std::error::Error>> {
+                    let response = reqwest::blocking::Client::new()
+                        .request(reqwest::Method::POST, "http://localhost/users")
+                        .header("content-type", "application/json")
+                        .body("{\n  \"name\": \"jane\\u0000\"\n}")
+                        .send()?;
+                    println!("{}", response.text()?);
+
+                    Ok(())
+                }"#
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_custom_methods() -> Result<()> {
+        let req = Request {
+            method: Method::from_str("PURGE")?,
+            ..Request::basic("GET", "http://localhost/cache")
+        };
+
+        assert!(ReqwestExporter.export(&req)?.contains(
+            r#".request(reqwest::Method::from_bytes("PURGE")?, "http://localhost/cache")"#
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_multipart_requests() -> Result<()> {
+        let image = root().join("resources/image.jpg");
+        let req = Request::basic("POST", "http://localhost/upload").multipart(&[
+            MultipartPart::File {
+                name: "image".to_owned(),
+                file_path: image.clone(),
+                mime_str: Some("image/jpeg".to_owned()),
+            },
+            MultipartPart::File {
+                name: "thumbnail".to_owned(),
+                file_path: image.clone(),
+                mime_str: None,
+            },
+            MultipartPart::Text {
+                name: "metadata".to_owned(),
+                text: r#"{"a": 1}"#.to_owned(),
+                mime_str: Some("application/json".to_owned()),
+            },
+            MultipartPart::Text {
+                name: "title".to_owned(),
+                text: "holiday".to_owned(),
+                mime_str: None,
+            },
+        ]);
+
+        assert_eq!(
+            ReqwestExporter.export(&req)?,
+            formatdoc!(
+                r#"
+                fn main() -> Result<(), Box<dyn std::error::Error>> {{
+                    use reqwest::blocking::multipart::{{Form, Part}};
+
+                    let form = Form::new()
+                        .part("image", Part::file("{image}")?.mime_str("image/jpeg")?)
+                        .file("thumbnail", "{image}")?
+                        .part("metadata", Part::text("{{\"a\": 1}}").mime_str("application/json")?)
+                        .text("title", "holiday");
+
+                    let response = reqwest::blocking::Client::new()
+                        .request(reqwest::Method::POST, "http://localhost/upload")
+                        .multipart(form)
+                        .send()?;
+                    println!("{{}}", response.text()?);
+
+                    Ok(())
+                }}"#,
+                image = image.to_str(),
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+
+use crate::execution::export::{joined_headers, Exporter};
+use crate::parsers::Request;
+use crate::request::body::Body;
+use crate::shell::dollar_preserving_quote;
+
+pub struct WgetExporter;
+
+impl Exporter for WgetExporter {
+    fn name(&self) -> &'static str {
+        "wget"
+    }
+
+    fn is_shell_command(&self) -> bool {
+        true
+    }
+
+    /// wget can't send multipart requests, they are exported as a comment saying so, so that
+    /// the other requests of a run are still exported.
+    fn export(&self, req: &Request) -> Result<String> {
+        let body = match &req.body {
+            Body::Plain(body) => body,
+            Body::Multipart(_) => {
+                return Ok(format!(
+                    "# wget can't send multipart requests, export {} {} with another format, e.g. --export curl",
+                    req.method, req.url
+                ))
+            }
+        };
+
+        let mut parts = vec![format!(
+            "wget --quiet --output-document=- --method={}",
+            req.method
+        )];
+
+        for (name, value) in joined_headers(req) {
+            parts.push(format!(
+                "--header={}",
+                dollar_preserving_quote(&format!("{}: {}", name, value))
+            ));
+        }
+
+        if !body.is_empty() {
+            parts.push(format!("--body-data={}", dollar_preserving_quote(body)));
+        }
+
+        parts.push(dollar_preserving_quote(&req.url));
+
+        Ok(parts.join(" \\\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::request::body::MultipartPart;
+    use crate::test_utils::root;
+
+    use super::*;
+
+    #[test]
+    fn should_export_requests() -> Result<()> {
+        let req = Request::basic("PUT", "http://localhost/users/1")
+            .add_header("authorization", "Bearer $(pass token)")
+            .body(r#"{"name": "jane"}"#);
+
+        assert_eq!(
+            WgetExporter.export(&req)?,
+            indoc!(
+                r#"
+                wget --quiet --output-document=- --method=PUT \
+                --header="authorization: Bearer $(pass token)" \
+                --body-data="{\"name\": \"jane\"}" \
+                "http://localhost/users/1""#
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_multipart_requests_as_comments() -> Result<()> {
+        let req =
+            Request::basic("POST", "http://localhost/upload").multipart(&[MultipartPart::File {
+                name: "image".to_owned(),
+                file_path: root().join("resources/image.jpg"),
+                mime_str: None,
+            }]);
+
+        assert_eq!(
+            WgetExporter.export(&req)?,
+            "# wget can't send multipart requests, export POST http://localhost/upload with another format, e.g. --export curl"
+        );
+
+        Ok(())
+    }
+}
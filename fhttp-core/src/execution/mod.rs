@@ -1,4 +1,9 @@
 pub mod execution_order;
 pub mod response_store;
 pub mod curl;
+pub mod curl_script;
+pub mod dry_run;
+pub mod export;
 pub mod client;
+pub mod cookie_jar;
+pub mod retry;
@@ -1,28 +1,140 @@
 use std::collections::HashMap;
-use crate::path_utils::CanonicalizedPathBuf;
+
+use reqwest::header::HeaderMap;
+
+use crate::execution::curl_script::shell_variable_name;
+use crate::postprocessing::response::header_value;
+use crate::request_sources::request_id::RequestId;
 
 #[derive(Debug)]
 pub struct ResponseStore {
-    response_data: HashMap<CanonicalizedPathBuf, String>,
+    response_data: HashMap<RequestId, String>,
+    response_headers: HashMap<RequestId, HeaderMap>,
+    placeholders: HashMap<RequestId, Placeholder>,
+}
+
+#[derive(Debug)]
+enum Placeholder {
+    Text,
+    /// the name of the shell variable
+    ShellVariable(String),
 }
 
 impl ResponseStore {
     pub fn new() -> Self {
-        ResponseStore { response_data: HashMap::new(), }
+        ResponseStore {
+            response_data: HashMap::new(),
+            response_headers: HashMap::new(),
+            placeholders: HashMap::new(),
+        }
     }
 
-    pub fn store<V: Into<String>>(
+    pub fn store<I: Into<RequestId>, V: Into<String>>(
         &mut self,
-        path: CanonicalizedPathBuf,
+        id: I,
         value: V
     ) {
-        self.response_data.insert(path, value.into());
+        let id = id.into();
+        self.placeholders.remove(&id);
+        self.response_data.insert(id, value.into());
     }
 
+    pub fn store_headers<I: Into<RequestId>>(
+        &mut self,
+        id: I,
+        headers: HeaderMap,
+    ) {
+        self.response_headers.insert(id.into(), headers);
+    }
+
+    /// Stores a placeholder like `<response of token.http>` instead of a response, e.g. for dry
+    /// runs. Every header of a placeholder is a placeholder as well.
+    pub fn store_placeholder<I: Into<RequestId>>(&mut self, id: I) {
+        let id = id.into();
+        self.response_data
+            .insert(id.clone(), format!("<response of {}>", placeholder_name(&id)));
+        self.placeholders.insert(id, Placeholder::Text);
+    }
+
+    /// Stores a reference to the shell variable holding the response, e.g. `${TOKEN}`, for
+    /// exporting requests as a shell script. Headers are read with `response_header`, see
+    /// `curl_script::SCRIPT_PREAMBLE`. Variables of requests with the same name, e.g.
+    /// `a/token.http` and `b/token.http`, are told apart by suffixes like `TOKEN_2`.
+    pub fn store_shell_variable<I: Into<RequestId>>(&mut self, id: I) {
+        let id = id.into();
+        let name = shell_variable_name(&id);
+        let taken = |candidate: &str| {
+            self.placeholders.iter().any(|(other, placeholder)| {
+                other != &id
+                    && matches!(placeholder, Placeholder::ShellVariable(it) if it == candidate)
+            })
+        };
+        let variable = (1..)
+            .map(|n| match n {
+                1 => name.clone(),
+                n => format!("{}_{}", name, n),
+            })
+            .find(|it| !taken(it))
+            .unwrap();
+
+        self.response_data.insert(id.clone(), format!("${{{}}}", variable));
+        self.placeholders.insert(id, Placeholder::ShellVariable(variable));
+    }
+
+    /// The shell variable stored with `store_shell_variable`, if any.
+    pub fn shell_variable(&self, id: &RequestId) -> Option<&str> {
+        match self.placeholders.get(id) {
+            Some(Placeholder::ShellVariable(variable)) => Some(variable),
+            _ => None,
+        }
+    }
+
+    /// # Panics
+    /// panics when key not found.
+    pub fn get(&self, id: &RequestId) -> String {
+        self.response_data[id].clone()
+    }
+
+    pub fn contains(&self, id: &RequestId) -> bool {
+        self.response_data.contains_key(id)
+    }
+
+    pub fn remove(&mut self, id: &RequestId) {
+        self.response_data.remove(id);
+        self.response_headers.remove(id);
+        self.placeholders.remove(id);
+    }
+
+    /// Removes all placeholders, keeping only real responses, e.g. between runs of the watch
+    /// mode, so dry runs and scripts don't take them for cached responses.
+    pub fn remove_placeholders(&mut self) {
+        for (id, _) in self.placeholders.drain() {
+            self.response_data.remove(&id);
+            self.response_headers.remove(&id);
+        }
+    }
+
+    /// Returns `None` if the response didn't contain the header.
     /// # Panics
     /// panics when key not found.
-    pub fn get(&self, path: &CanonicalizedPathBuf) -> String {
-        self.response_data[path].clone()
+    pub fn get_header(&self, id: &RequestId, name: &str) -> Option<String> {
+        match self.placeholders.get(id) {
+            Some(Placeholder::Text) => {
+                return Some(format!("<header {} of {}>", name, placeholder_name(id)))
+            }
+            Some(Placeholder::ShellVariable(variable)) => {
+                return Some(format!("$(response_header {} {})", variable, name))
+            }
+            None => (),
+        }
+        header_value(&self.response_headers[id], name)
+    }
+}
+
+fn placeholder_name(id: &RequestId) -> String {
+    match id.name {
+        Some(ref name) => format!("{}#{}", id.path.file_name(), name),
+        None => id.path.file_name().to_owned(),
     }
 }
 
@@ -0,0 +1,245 @@
+use std::time::{Duration, SystemTime};
+
+use rand::{rng, Rng};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, StatusCode};
+
+use crate::request::metadata::RequestMetadata;
+use crate::request::status_set::StatusSet;
+use crate::Config;
+
+const DEFAULT_RETRY_ON: [u16; 4] = [429, 502, 503, 504];
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Decides whether and when a failed attempt of a request is retried.
+/// Per-request settings take precedence over the ones from `Config`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    retries: u32,
+    retry_on: StatusSet,
+    retry_delay: Duration,
+    expect_status: Option<StatusSet>,
+}
+
+impl RetryPolicy {
+    pub fn new(config: &Config, method: &Method, metadata: &RequestMetadata) -> Self {
+        let retry_non_idempotent = metadata
+            .retry_non_idempotent
+            .unwrap_or_else(|| config.retry_non_idempotent());
+        let retries = match is_idempotent(method) || retry_non_idempotent {
+            true => metadata.retries.unwrap_or_else(|| config.retries()),
+            false => 0,
+        };
+
+        RetryPolicy {
+            retries,
+            retry_on: metadata
+                .retry_on
+                .or_else(|| config.retry_on())
+                .unwrap_or_else(|| StatusSet::try_from(DEFAULT_RETRY_ON.as_slice()).unwrap()),
+            retry_delay: config.retry_delay().unwrap_or(DEFAULT_RETRY_DELAY),
+            expect_status: metadata.expect_status,
+        }
+    }
+
+    /// The maximum number of attempts, including the first one.
+    pub fn attempts(&self) -> u32 {
+        self.retries + 1
+    }
+
+    /// Returns how long to wait before the next attempt, or `None` if the response
+    /// of the given (1-based) attempt should not be retried. Explicitly expected
+    /// statuses are never retried, neither are responses asking to wait longer than
+    /// `MAX_RETRY_DELAY` with `Retry-After`.
+    pub fn retry_response(
+        &self,
+        attempt: u32,
+        status: StatusCode,
+        headers: &HeaderMap,
+    ) -> Option<Duration> {
+        let expected = self.expect_status.is_some_and(|it| it.contains(status));
+        if attempt > self.retries || expected || !self.retry_on.contains(status) {
+            return None;
+        }
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+                match retry_after(headers) {
+                    Some(delay) if delay > MAX_RETRY_DELAY => None,
+                    Some(delay) => Some(delay),
+                    None => Some(self.backoff(attempt)),
+                }
+            }
+            _ => Some(self.backoff(attempt)),
+        }
+    }
+
+    /// Returns how long to wait before the next attempt, or `None` if the error
+    /// of the given (1-based) attempt should not be retried.
+    pub fn retry_error(&self, attempt: u32, error: &reqwest::Error) -> Option<Duration> {
+        let transient = error.is_connect() || error.is_timeout() || error.is_request();
+        match transient && attempt <= self.retries {
+            true => Some(self.backoff(attempt)),
+            false => None,
+        }
+    }
+
+    /// Exponential backoff with jitter: waits a random duration between half
+    /// and all of `retry_delay * 2^(attempt - 1)`, capped at `MAX_RETRY_DELAY`.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self
+            .retry_delay
+            .saturating_mul(factor)
+            .min(MAX_RETRY_DELAY)
+            .as_millis() as u64;
+
+        Duration::from_millis(rng().random_range(delay / 2..=delay))
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
+
+/// Parses the `Retry-After` header, which is either a number of seconds or an http date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            let date = httpdate::parse_http_date(value).ok()?;
+            Some(
+                date.duration_since(SystemTime::now())
+                    .unwrap_or(Duration::ZERO),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn config(retries: u32) -> Config {
+        Config::default()
+            .with_retries(retries)
+            .with_retry_delay_ms(Some(100))
+    }
+
+    #[test]
+    fn should_back_off_exponentially_with_jitter() {
+        let policy = RetryPolicy::new(&config(3), &Method::GET, &RequestMetadata::default());
+        let headers = HeaderMap::new();
+
+        for (attempt, max) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = policy
+                .retry_response(attempt, StatusCode::BAD_GATEWAY, &headers)
+                .unwrap();
+            assert!(delay >= Duration::from_millis(max / 2));
+            assert!(delay <= Duration::from_millis(max));
+        }
+        assert_eq!(
+            policy.retry_response(4, StatusCode::BAD_GATEWAY, &headers),
+            None
+        );
+    }
+
+    #[test]
+    fn should_only_retry_configured_statuses() -> anyhow::Result<()> {
+        let headers = HeaderMap::new();
+        let policy = RetryPolicy::new(&config(1), &Method::GET, &RequestMetadata::default());
+        assert!(policy
+            .retry_response(1, StatusCode::BAD_GATEWAY, &headers)
+            .is_some());
+        assert!(policy
+            .retry_response(1, StatusCode::INTERNAL_SERVER_ERROR, &headers)
+            .is_none());
+
+        let metadata = RequestMetadata {
+            retry_on: Some(StatusSet::from_str("500")?),
+            ..Default::default()
+        };
+        let policy = RetryPolicy::new(&config(1), &Method::GET, &metadata);
+        assert!(policy
+            .retry_response(1, StatusCode::BAD_GATEWAY, &headers)
+            .is_none());
+        assert!(policy
+            .retry_response(1, StatusCode::INTERNAL_SERVER_ERROR, &headers)
+            .is_some());
+
+        let metadata = RequestMetadata {
+            expect_status: Some(StatusSet::from_str("200,503")?),
+            ..Default::default()
+        };
+        let policy = RetryPolicy::new(&config(1), &Method::GET, &metadata);
+        assert!(policy
+            .retry_response(1, StatusCode::SERVICE_UNAVAILABLE, &headers)
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_retry_non_idempotent_methods_unless_allowed() {
+        let policy = RetryPolicy::new(&config(2), &Method::POST, &RequestMetadata::default());
+        assert_eq!(policy.attempts(), 1);
+
+        let policy = RetryPolicy::new(
+            &config(2).with_retry_non_idempotent(true),
+            &Method::POST,
+            &RequestMetadata::default(),
+        );
+        assert_eq!(policy.attempts(), 3);
+
+        let metadata = RequestMetadata {
+            retries: Some(1),
+            retry_non_idempotent: Some(true),
+            ..Default::default()
+        };
+        let policy = RetryPolicy::new(&config(2), &Method::PATCH, &metadata);
+        assert_eq!(policy.attempts(), 2);
+    }
+
+    #[test]
+    fn should_honour_retry_after() {
+        let policy = RetryPolicy::new(&config(1), &Method::GET, &RequestMetadata::default());
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+
+        assert_eq!(
+            policy.retry_response(1, StatusCode::TOO_MANY_REQUESTS, &headers),
+            Some(Duration::from_secs(7))
+        );
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(
+            policy.retry_response(1, StatusCode::SERVICE_UNAVAILABLE, &headers),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn should_give_up_when_retry_after_exceeds_the_maximum_delay() {
+        let policy = RetryPolicy::new(&config(1), &Method::GET, &RequestMetadata::default());
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("31"));
+
+        assert_eq!(
+            policy.retry_response(1, StatusCode::TOO_MANY_REQUESTS, &headers),
+            None
+        );
+    }
+}
@@ -13,6 +13,7 @@ extern crate wiremock_multipart;
 
 pub use config::Config;
 pub use execution::client::Client;
+pub use execution::cookie_jar::CookieJar;
 pub use execution::response_store::ResponseStore;
 pub use postprocessing::response::Response;
 pub use postprocessing::response_handler::ResponseHandler;
@@ -32,3 +33,5 @@ pub mod preprocessing;
 pub mod profiles;
 pub mod request;
 pub mod request_sources;
+pub mod secrets;
+pub mod shell;
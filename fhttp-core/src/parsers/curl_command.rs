@@ -0,0 +1,586 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::Method;
+
+use crate::parsers::Request;
+use crate::path_utils::{rebase, CanonicalizedPathBuf};
+use crate::request::body::{Body, MultipartPart};
+use crate::request::metadata::RequestMetadata;
+
+/// Options that take a value, by their short name.
+const SHORT_OPTIONS_WITH_VALUE: [(char, &str); 16] = [
+    ('X', "--request"),
+    ('H', "--header"),
+    ('d', "--data"),
+    ('F', "--form"),
+    ('u', "--user"),
+    ('A', "--user-agent"),
+    ('e', "--referer"),
+    ('b', "--cookie"),
+    ('o', "--output"),
+    ('m', "--max-time"),
+    ('w', "--write-out"),
+    ('x', "--proxy"),
+    ('c', "--cookie-jar"),
+    ('D', "--dump-header"),
+    ('r', "--range"),
+    ('E', "--cert"),
+];
+
+const SHORT_FLAGS: [(char, &str); 12] = [
+    ('s', "--silent"),
+    ('S', "--show-error"),
+    ('L', "--location"),
+    ('k', "--insecure"),
+    ('i', "--include"),
+    ('v', "--verbose"),
+    ('f', "--fail"),
+    ('N', "--no-buffer"),
+    ('g', "--globoff"),
+    ('G', "--get"),
+    ('I', "--head"),
+    ('#', "--progress-bar"),
+];
+
+/// Options that don't change the request, their values are skipped.
+const IGNORED_OPTIONS_WITH_VALUE: [&str; 14] = [
+    "--output",
+    "--max-time",
+    "--connect-timeout",
+    "--write-out",
+    "--retry",
+    "--proxy",
+    "--cookie-jar",
+    "--dump-header",
+    "--range",
+    "--cert",
+    "--key",
+    "--cacert",
+    "--resolve",
+    "--limit-rate",
+];
+
+const IGNORED_FLAGS: [&str; 16] = [
+    "--silent",
+    "--show-error",
+    "--location",
+    "--insecure",
+    "--include",
+    "--verbose",
+    "--fail",
+    "--fail-with-body",
+    "--no-buffer",
+    "--globoff",
+    "--progress-bar",
+    "--compressed",
+    "--http1.1",
+    "--http2",
+    "--no-progress-meter",
+    "--location-trusted",
+];
+
+/// Parses a curl command, e.g. copied from the developer tools of a browser, into a request.
+/// Relative paths of uploaded and included files are rewritten to be relative to `out_dir`,
+/// the directory of the request file that is generated, instead of the working directory.
+pub fn parse_curl_command<P: AsRef<Path>>(command: &str, out_dir: P) -> Result<Request> {
+    let out_dir = out_dir.as_ref();
+    let words = split_words(command)?;
+    let mut words = words.into_iter();
+    if words.next().as_deref() != Some("curl") {
+        return Err(anyhow!("expected a curl command"));
+    }
+
+    let mut command = CurlCommand::default();
+    while let Some(word) = words.next() {
+        match word.as_str() {
+            long if long.starts_with("--") => {
+                let value = match takes_value(long) {
+                    true => Some(
+                        words
+                            .next()
+                            .ok_or_else(|| anyhow!("curl option '{}' needs a value", long))?,
+                    ),
+                    false => None,
+                };
+                command.apply(long, value, out_dir)?;
+            }
+            short if short.starts_with('-') && short.len() > 1 => {
+                for (index, c) in short.char_indices().skip(1) {
+                    if let Some((_, long)) = SHORT_OPTIONS_WITH_VALUE.iter().find(|it| it.0 == c) {
+                        let attached = &short[index + c.len_utf8()..];
+                        let value = match attached.is_empty() {
+                            true => words
+                                .next()
+                                .ok_or_else(|| anyhow!("curl option '-{}' needs a value", c))?,
+                            false => attached.to_owned(),
+                        };
+                        command.apply(long, Some(value), out_dir)?;
+                        break;
+                    }
+                    match SHORT_FLAGS.iter().find(|it| it.0 == c) {
+                        Some((_, long)) => command.apply(long, None, out_dir)?,
+                        None => return Err(anyhow!("unsupported curl option '-{}'", c)),
+                    }
+                }
+            }
+            url => command.apply("--url", Some(url.to_owned()), out_dir)?,
+        }
+    }
+
+    command.into_request()
+}
+
+fn takes_value(long: &str) -> bool {
+    SHORT_OPTIONS_WITH_VALUE.iter().any(|it| it.1 == long)
+        || IGNORED_OPTIONS_WITH_VALUE.contains(&long)
+        || matches!(
+            long,
+            "--url"
+                | "--data-raw"
+                | "--data-binary"
+                | "--data-ascii"
+                | "--data-urlencode"
+                | "--form-string"
+        )
+}
+
+#[derive(Default)]
+struct CurlCommand {
+    method: Option<String>,
+    url: Option<String>,
+    headers: Vec<(String, String)>,
+    data: Vec<String>,
+    parts: Vec<MultipartPart>,
+    user: Option<String>,
+    get: bool,
+    head: bool,
+}
+
+impl CurlCommand {
+    fn apply(&mut self, option: &str, value: Option<String>, out_dir: &Path) -> Result<()> {
+        let value = value.unwrap_or_default();
+        match option {
+            "--request" => self.method = Some(value),
+            "--url" => match self.url {
+                None => self.url = Some(value),
+                Some(_) => return Err(anyhow!("only one url per curl command is supported")),
+            },
+            "--header" => match value.split_once(':') {
+                Some((name, value)) => {
+                    let value = value.trim();
+                    // `-H "name:"` removes a header curl would send
+                    if !value.is_empty() {
+                        self.headers
+                            .push((name.trim().to_owned(), value.to_owned()));
+                    }
+                }
+                None => match value.strip_suffix(';') {
+                    Some(name) => self.headers.push((name.trim().to_owned(), String::new())),
+                    None => return Err(anyhow!("invalid header '{}'", value)),
+                },
+            },
+            "--user-agent" => self.headers.push(("user-agent".to_owned(), value)),
+            "--referer" => self.headers.push(("referer".to_owned(), value)),
+            "--cookie" if value.contains('=') => self.headers.push(("cookie".to_owned(), value)),
+            "--cookie" => return Err(anyhow!("reading cookies from files isn't supported")),
+            "--data" | "--data-ascii" | "--data-binary" => match value.strip_prefix('@') {
+                Some(path) => self.data.push(format!(
+                    r#"${{include("{}")}}"#,
+                    rebase(path, out_dir)?.display()
+                )),
+                None => self.data.push(value),
+            },
+            "--data-raw" => self.data.push(value),
+            "--data-urlencode" => self.data.push(url_encode_data(&value)?),
+            "--form" => self.parts.push(parse_form(&value, out_dir)?),
+            "--form-string" => {
+                let (name, text) = split_form(&value)?;
+                self.parts.push(MultipartPart::Text {
+                    name: name.to_owned(),
+                    text: text.to_owned(),
+                    mime_str: None,
+                });
+            }
+            "--user" => self.user = Some(value),
+            "--get" => self.get = true,
+            "--head" => self.head = true,
+            ignored
+                if IGNORED_FLAGS.contains(&ignored)
+                    || IGNORED_OPTIONS_WITH_VALUE.contains(&ignored) => {}
+            unknown => return Err(anyhow!("unsupported curl option '{}'", unknown)),
+        }
+
+        Ok(())
+    }
+
+    fn into_request(self) -> Result<Request> {
+        let mut url = self
+            .url
+            .ok_or_else(|| anyhow!("the curl command has no url"))?;
+        if !self.data.is_empty() && !self.parts.is_empty() {
+            return Err(anyhow!(
+                "curl can't send data and form parts at the same time"
+            ));
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.headers {
+            headers.append(
+                HeaderName::from_str(&name)
+                    .with_context(|| format!("invalid header '{}'", name))?,
+                HeaderValue::from_str(&value)
+                    .with_context(|| format!("invalid value of header '{}'", name))?,
+            );
+        }
+        if let Some(user) = self.user {
+            if !user.contains(':') {
+                return Err(anyhow!("-u needs a password, e.g. '-u {}:password'", user));
+            }
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Basic {}", BASE64.encode(user)))?,
+            );
+        }
+
+        let (default_method, body) = match (self.get, self.data.is_empty(), self.parts.is_empty()) {
+            (true, false, _) => {
+                url.push(match url.contains('?') {
+                    true => '&',
+                    false => '?',
+                });
+                url.push_str(&self.data.join("&"));
+                ("GET", Body::Plain(String::new()))
+            }
+            (false, false, _) => {
+                if !headers.contains_key(CONTENT_TYPE) {
+                    headers.insert(
+                        CONTENT_TYPE,
+                        HeaderValue::from_static("application/x-www-form-urlencoded"),
+                    );
+                }
+                ("POST", Body::Plain(self.data.join("&")))
+            }
+            (_, true, false) => ("POST", Body::Multipart(self.parts)),
+            (_, true, true) => ("GET", Body::Plain(String::new())),
+        };
+        let method = match (self.method, self.head) {
+            (Some(method), _) => method,
+            (None, true) => "HEAD".to_owned(),
+            (None, false) => default_method.to_owned(),
+        };
+
+        Ok(Request {
+            method: Method::from_str(&method.to_uppercase())
+                .with_context(|| format!("invalid method '{}'", method))?,
+            url,
+            headers,
+            body,
+            response_handler: None,
+            assertions: vec![],
+            metadata: RequestMetadata::default(),
+        })
+    }
+}
+
+/// `--data-urlencode` values are `content`, `=content` or `name=content`.
+fn url_encode_data(value: &str) -> Result<String> {
+    let encode = |text: &str| form_urlencoded::byte_serialize(text.as_bytes()).collect::<String>();
+
+    match value.split_once('=') {
+        _ if value.contains('@') && !value.contains('=') => Err(anyhow!(
+            "reading --data-urlencode content from files isn't supported"
+        )),
+        Some(("", content)) => Ok(encode(content)),
+        Some((name, content)) => Ok(format!("{}={}", name, encode(content))),
+        None => Ok(encode(value)),
+    }
+}
+
+fn split_form(value: &str) -> Result<(&str, &str)> {
+    value
+        .split_once('=')
+        .ok_or_else(|| anyhow!("invalid form part '{}', expected NAME=CONTENT", value))
+}
+
+/// Parses `-F` parts like `name=text`, `name=@path;type=mime` or `name=<path`.
+fn parse_form(value: &str, out_dir: &Path) -> Result<MultipartPart> {
+    let (name, content) = split_form(value)?;
+    let mut params = content.split(';');
+    let content = params.next().unwrap_or_default();
+    let mime_str = params
+        .filter_map(|it| it.trim().strip_prefix("type="))
+        .map(str::to_owned)
+        .next();
+
+    if let Some(path) = content.strip_prefix('@') {
+        return Ok(MultipartPart::File {
+            name: name.to_owned(),
+            file_path: CanonicalizedPathBuf::verbatim(rebase(path, out_dir)?),
+            mime_str,
+        });
+    }
+    let text = match content.strip_prefix('<') {
+        Some(path) => {
+            fs::read_to_string(path).with_context(|| format!("error opening file {}", path))?
+        }
+        None => content.to_owned(),
+    };
+
+    Ok(MultipartPart::Text {
+        name: name.to_owned(),
+        text,
+        mime_str,
+    })
+}
+
+/// Splits a command into words like a posix shell, supporting single, double and `$'...'`
+/// quotes, backslash escapes and line continuations.
+fn split_words(command: &str) -> Result<Vec<String>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\\' => match chars.next() {
+                Some('\n') => (),
+                // windows line endings in continuations
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(escaped) => word.get_or_insert_with(String::new).push(escaped),
+                None => return Err(anyhow!("the command ends with a backslash")),
+            },
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(anyhow!("unterminated quote")),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => (),
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(anyhow!("unterminated quote")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(anyhow!("unterminated quote")),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => word.push(ansi_c_escape(&mut chars)?),
+                        Some(c) => word.push(c),
+                        None => return Err(anyhow!("unterminated quote")),
+                    }
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+fn ansi_c_escape<I: Iterator<Item = char>>(chars: &mut I) -> Result<char> {
+    Ok(match chars.next() {
+        Some('n') => '\n',
+        Some('t') => '\t',
+        Some('r') => '\r',
+        Some('0') => '\0',
+        Some('x') => hex_char(chars, 2)?,
+        Some('u') => hex_char(chars, 4)?,
+        Some('U') => hex_char(chars, 8)?,
+        Some(c) => c,
+        None => return Err(anyhow!("unterminated quote")),
+    })
+}
+
+fn hex_char<I: Iterator<Item = char>>(chars: &mut I, digits: usize) -> Result<char> {
+    let code = chars.take(digits).collect::<String>();
+    u32::from_str_radix(&code, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| anyhow!("invalid escape sequence '{}'", code))
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use indoc::indoc;
+    use rstest::rstest;
+
+    use crate::test_utils::root;
+
+    use super::*;
+
+    fn parse(command: &str) -> Result<Request> {
+        parse_curl_command(command, ".")
+    }
+
+    #[rstest]
+    #[case("curl http://localhost", &["curl", "http://localhost"])]
+    #[case(r#"curl -H 'a: "b"' -d "x \"y\" \$z \n""#, &["curl", "-H", r#"a: "b""#, "-d", r#"x "y" $z \n"#])]
+    #[case("curl \\\n  --url a\\ b", &["curl", "--url", "a b"])]
+    #[case(r#"curl --data-raw $'{\"a\":\n\x41ä}'"#, &["curl", "--data-raw", "{\"a\":\nAä}"])]
+    #[case("curl 'a'\"b\"c", &["curl", "abc"])]
+    fn should_split_words_like_a_shell(#[case] command: &str, #[case] expected: &[&str]) {
+        assert_eq!(split_words(command).unwrap(), expected);
+    }
+
+    #[test]
+    fn should_parse_commands_from_browsers() -> Result<()> {
+        let req = parse(indoc!(
+            r#"
+            curl 'https://api.example.com/users?page=1' \
+              -H 'accept: application/json' \
+              -H 'content-type: application/json' \
+              --data-raw $'{"name":"jane\'s"}' \
+              --compressed
+        "#
+        ))?;
+
+        assert_eq!(
+            req,
+            Request::basic("POST", "https://api.example.com/users?page=1")
+                .add_header("accept", "application/json")
+                .add_header("content-type", "application/json")
+                .body(r#"{"name":"jane's"}"#)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_short_options() -> Result<()> {
+        let req = parse("curl -sSL -XPUT -Hx-a:1 -H 'x-b;' -u jane:s3cret -A fhttp localhost")?;
+
+        let mut expected = Request::basic("PUT", "localhost")
+            .add_header("x-a", "1")
+            .add_header("x-b", "")
+            .add_header("user-agent", "fhttp");
+        expected.headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_static("Basic amFuZTpzM2NyZXQ="),
+        );
+        assert_eq!(req, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_form_data() -> Result<()> {
+        let req = parse("curl -d a=1 --data-urlencode 'b=x y&z' -d @body.json --url localhost")?;
+        assert_eq!(
+            req,
+            Request::basic("POST", "localhost")
+                .add_header("content-type", "application/x-www-form-urlencoded")
+                .body(r#"a=1&b=x+y%26z&${include("body.json")}"#)
+        );
+
+        let req = parse("curl -G -d a=1 -d b=2 'localhost?c=3'")?;
+        assert_eq!(req, Request::basic("GET", "localhost?c=3&a=1&b=2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_multipart_requests() -> Result<()> {
+        let image = root().join("resources/image.jpg");
+        let req = parse(&format!(
+            "curl -F 'image=@{};type=image/jpeg' -F 'meta={{}};type=application/json' --form-string 'title=@home' localhost",
+            image.to_str()
+        ))?;
+
+        assert_eq!(
+            req,
+            Request::basic("POST", "localhost").multipart(&[
+                MultipartPart::File {
+                    name: "image".into(),
+                    file_path: image,
+                    mime_str: Some("image/jpeg".into()),
+                },
+                MultipartPart::Text {
+                    name: "meta".into(),
+                    text: "{}".into(),
+                    mime_str: Some("application/json".into()),
+                },
+                MultipartPart::Text {
+                    name: "title".into(),
+                    text: "@home".into(),
+                    mime_str: None,
+                },
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_rebase_relative_paths_onto_the_output_directory() -> Result<()> {
+        let req = parse_curl_command("curl -d @body.json localhost", "requests")?;
+        assert_eq!(
+            req.body,
+            Body::Plain(r#"${include("../body.json")}"#.into())
+        );
+
+        let req = parse_curl_command("curl -F file=@./missing.bin localhost", "requests/users")?;
+        assert_eq!(
+            req.body,
+            Body::Multipart(vec![MultipartPart::File {
+                name: "file".into(),
+                file_path: CanonicalizedPathBuf::verbatim(PathBuf::from("../../missing.bin")),
+                mime_str: None,
+            }])
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("wget localhost", "expected a curl command")]
+    #[case("curl -X POST", "the curl command has no url")]
+    #[case("curl -T file localhost", "unsupported curl option '-T'")]
+    #[case(
+        "curl --upload-file file localhost",
+        "unsupported curl option '--upload-file'"
+    )]
+    #[case(
+        "curl -u jane localhost",
+        "-u needs a password, e.g. '-u jane:password'"
+    )]
+    #[case(
+        "curl -d a -F b=c localhost",
+        "curl can't send data and form parts at the same time"
+    )]
+    #[case("curl -H 'a: b localhost", "unterminated quote")]
+    fn should_report_invalid_commands(#[case] command: &str, #[case] message: &str) {
+        assert_eq!(parse(command).unwrap_err().to_string(), message);
+    }
+}
@@ -1,11 +1,14 @@
+pub mod curl_command;
 mod parsing;
 mod parsing_gql;
+mod request_blocks;
 
 pub use crate::request::Request;
 
 use lazy_regex::Regex;
 pub use parsing::parse_str;
 pub use parsing_gql::parse_gql_str;
+pub use request_blocks::{split_requests, RequestBlock};
 
 pub fn fileupload_regex() -> &'static Regex {
     regex!(r##"(?m)\$\{\s*file\s*\(\s*"([^}]+)"\s*,\s*"([^}]+)"\s*\)\s*\}"##)
@@ -22,3 +25,9 @@ pub mod gql_parser {
     #[grammar = "parsers/grammar/gql_request.pest"]
     pub struct RequestParser;
 }
+
+pub mod assertion_parser {
+    #[derive(Parser)]
+    #[grammar = "parsers/grammar/assertion.pest"]
+    pub struct AssertionParser;
+}
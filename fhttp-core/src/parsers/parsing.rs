@@ -10,8 +10,10 @@ use reqwest::Method;
 use crate::parsers::normal_parser::{RequestParser, Rule};
 use crate::parsers::{fileupload_regex, Request};
 use crate::path_utils::RelativePath;
+use crate::postprocessing::assertion::Assertion;
 use crate::postprocessing::response_handler::ResponseHandler;
 use crate::request::body::{Body, MultipartPart};
+use crate::request::metadata::RequestMetadata;
 
 pub fn parse_str<P: AsRef<Path>, T: AsRef<str>>(path: P, source: T) -> Result<Request> {
     let path = path.as_ref();
@@ -25,9 +27,12 @@ pub fn parse_str<P: AsRef<Path>, T: AsRef<str>>(path: P, source: T) -> Result<Re
     let mut headers = HeaderMap::new();
     let mut body = String::new();
     let mut response_handler: Option<ResponseHandler> = None;
+    let mut assertions = vec![];
+    let mut metadata = RequestMetadata::default();
 
     for element in file.into_inner() {
         match element.as_rule() {
+            Rule::directive => parse_directive(&mut metadata, element)?,
             Rule::first_line => parse_first_line(element, &mut method, &mut url)?,
             Rule::header_line => parse_header_line(&mut headers, element)?,
             Rule::body => body.push_str(element.as_str().trim()),
@@ -37,6 +42,7 @@ pub fn parse_str<P: AsRef<Path>, T: AsRef<str>>(path: P, source: T) -> Result<Re
             Rule::response_handler_deno => {
                 parse_deno_response_handler(&mut response_handler, element)
             }
+            Rule::assertions_exp => assertions = Assertion::parse_all(element.as_str())?,
             Rule::response_handler_rhai => {
                 parse_rhai_response_handler(&mut response_handler, element)
             }
@@ -50,9 +56,26 @@ pub fn parse_str<P: AsRef<Path>, T: AsRef<str>>(path: P, source: T) -> Result<Re
         headers,
         body: plain_body_or_files(path, body)?,
         response_handler,
+        assertions,
+        metadata,
     })
 }
 
+fn parse_directive(metadata: &mut RequestMetadata, element: Pair<Rule>) -> Result<()> {
+    let mut name = "";
+    let mut value = "";
+
+    for part in element.into_inner() {
+        match part.as_rule() {
+            Rule::directive_name => name = part.as_str(),
+            Rule::directive_value => value = part.as_str().trim(),
+            _ => unreachable!(),
+        }
+    }
+
+    metadata.apply_directive(name, value)
+}
+
 fn parse_first_line(element: Pair<Rule>, method: &mut Method, url: &mut String) -> Result<()> {
     for field in element.into_inner() {
         match field.as_rule() {
@@ -159,6 +182,7 @@ fn plain_body_or_files(source_path: &Path, body: String) -> Result<Body> {
 mod parse_normal_requests {
     use std::env::current_dir;
 
+    use crate::request::status_set::StatusSet;
     use crate::test_utils::root;
     use indoc::indoc;
 
@@ -598,4 +622,141 @@ mod parse_normal_requests {
 
         Ok(())
     }
+
+    #[test]
+    fn should_parse_directives() -> Result<()> {
+        let result = parse_str(
+            current_dir().unwrap(),
+            indoc!(
+                r##"
+            # some comment
+            # @retries 3
+            #@retry-on 502, 503
+            # @retry-non-idempotent
+            POST http://localhost:9000/foo
+            # @retries 5
+            accept: application/xml
+        "##
+            ),
+        )?;
+
+        assert_eq!(
+            result,
+            Request::basic("POST", "http://localhost:9000/foo")
+                .add_header("accept", "application/xml")
+                .metadata(RequestMetadata {
+                    retries: Some(5),
+                    retry_on: Some(StatusSet::from_str("502,503")?),
+                    retry_non_idempotent: Some(true),
+                    ..Default::default()
+                })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_treat_unknown_directives_as_comments() -> Result<()> {
+        let result = parse_str(
+            current_dir().unwrap(),
+            indoc!(
+                r##"
+            # @author jane
+            # @names are no directive either
+            GET http://localhost:9000/foo
+            # @see https://example.com
+            accept: application/json
+        "##
+            ),
+        )?;
+
+        assert_eq!(
+            result,
+            Request::basic("GET", "http://localhost:9000/foo")
+                .add_header("accept", "application/json")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_metadata_directives() -> Result<()> {
+        let result = parse_str(
+            current_dir().unwrap(),
+            indoc!(
+                r##"
+            # @name  create user
+            # @timeout 5000
+            # @no-redirect
+            POST http://localhost:9000/foo
+        "##
+            ),
+        )?;
+
+        assert_eq!(
+            result,
+            Request::basic("POST", "http://localhost:9000/foo").metadata(RequestMetadata {
+                name: Some("create user".to_string()),
+                timeout_ms: Some(5000),
+                no_redirect: Some(true),
+                ..Default::default()
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_assertions_after_the_response_handler() -> Result<()> {
+        let result = parse_str(
+            current_dir().unwrap(),
+            indoc!(
+                r##"
+            POST http://localhost:9000/foo
+
+            body
+
+            > {%
+                json $.data
+            %}
+
+            > {% assert
+                status == 201
+                jsonpath $.data exists; header location exists
+            %}
+        "##
+            ),
+        )?;
+
+        assert_eq!(
+            result,
+            Request::basic("POST", "http://localhost:9000/foo")
+                .body("body")
+                .response_handler_json("$.data")
+                .assertions("status == 201; jsonpath $.data exists; header location exists")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_assertions_without_response_handler() -> Result<()> {
+        let result = parse_str(
+            current_dir().unwrap(),
+            indoc!(
+                r##"
+            GET http://localhost:9000/foo
+
+            > {% assert status == 200 %}
+        "##
+            ),
+        )?;
+
+        assert_eq!(
+            result,
+            Request::basic("GET", "http://localhost:9000/foo").assertions("status == 200")
+        );
+
+        Ok(())
+    }
 }
@@ -10,8 +10,10 @@ use serde_json::Value;
 
 use crate::parsers::gql_parser::{RequestParser, Rule};
 use crate::parsers::{fileupload_regex, Request};
+use crate::postprocessing::assertion::Assertion;
 use crate::postprocessing::response_handler::ResponseHandler;
 use crate::request::body::Body;
+use crate::request::metadata::RequestMetadata;
 
 pub fn parse_gql_str<T: AsRef<str>>(source: T) -> Result<Request> {
     let file = RequestParser::parse(Rule::file, source.as_ref())
@@ -24,10 +26,13 @@ pub fn parse_gql_str<T: AsRef<str>>(source: T) -> Result<Request> {
     let mut headers = HeaderMap::new();
     let mut query = String::new();
     let mut response_handler: Option<ResponseHandler> = None;
+    let mut assertions = vec![];
+    let mut metadata = RequestMetadata::default();
     let mut variables: Option<String> = None;
 
     for element in file.into_inner() {
         match element.as_rule() {
+            Rule::directive => parse_directive(&mut metadata, element)?,
             Rule::first_line => parse_first_line(element, &mut method, &mut url)?,
             Rule::header_line => parse_header_line(&mut headers, element)?,
             Rule::query => query.push_str(element.as_str().trim()),
@@ -38,6 +43,7 @@ pub fn parse_gql_str<T: AsRef<str>>(source: T) -> Result<Request> {
             Rule::response_handler_deno => {
                 parse_deno_response_handler(&mut response_handler, element)
             }
+            Rule::assertions_exp => assertions = Assertion::parse_all(element.as_str())?,
             _ => (),
         }
     }
@@ -64,9 +70,26 @@ pub fn parse_gql_str<T: AsRef<str>>(source: T) -> Result<Request> {
         headers: ensure_content_type_json(headers),
         body,
         response_handler,
+        assertions,
+        metadata,
     })
 }
 
+fn parse_directive(metadata: &mut RequestMetadata, element: Pair<Rule>) -> Result<()> {
+    let mut name = "";
+    let mut value = "";
+
+    for part in element.into_inner() {
+        match part.as_rule() {
+            Rule::directive_name => name = part.as_str(),
+            Rule::directive_value => value = part.as_str().trim(),
+            _ => unreachable!(),
+        }
+    }
+
+    metadata.apply_directive(name, value)
+}
+
 fn parse_first_line(element: Pair<Rule>, method: &mut Method, url: &mut String) -> Result<()> {
     for field in element.into_inner() {
         match field.as_rule() {
@@ -492,4 +515,60 @@ mod parse_gql_requests {
 
         Ok(())
     }
+
+    #[test]
+    fn should_parse_directives() -> Result<()> {
+        let result = parse_gql_str(indoc!(
+            r##"
+            # @retries 2
+            POST http://localhost:9000/foo
+
+            query
+        "##
+        ))?;
+
+        assert_eq!(
+            result,
+            Request::basic("POST", "http://localhost:9000/foo")
+                .add_header("content-type", "application/json")
+                .gql_body(json!({
+                    "query": "query",
+                    "variables": {}
+                }))
+                .metadata(RequestMetadata {
+                    retries: Some(2),
+                    ..Default::default()
+                })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_assertions() -> Result<()> {
+        let result = parse_gql_str(indoc!(
+            r##"
+            POST http://localhost:9000/foo
+
+            query
+
+            {}
+
+            > {% assert jsonpath $.data.items.length > 0 %}
+        "##
+        ))?;
+
+        assert_eq!(
+            result,
+            Request::basic("POST", "http://localhost:9000/foo")
+                .add_header("content-type", "application/json")
+                .gql_body(json!({
+                    "query": "query",
+                    "variables": {}
+                }))
+                .assertions("jsonpath $.data.items.length > 0")
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Context, Result};
+use pest::Parser;
+
+use crate::parsers::normal_parser::{RequestParser, Rule};
+
+/// A single request of a file that contains several `###`-separated requests.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RequestBlock {
+    pub name: String,
+    pub text: String,
+}
+
+/// Splits the contents of a file at its `###` separator lines. Returns `None` if there are no
+/// separators, i.e. the file contains a single request.
+///
+/// A request is named after its `# @name` directive, the title of its separator line or
+/// its 1-based position in the file, in that order. Blocks without any request lines,
+/// e.g. a comment above the first separator, are skipped.
+pub fn split_requests(source: &str) -> Result<Option<Vec<RequestBlock>>> {
+    let requests = RequestParser::parse(Rule::requests, source)
+        .context("failed to split requests")?
+        .next()
+        .unwrap(); // get and unwrap the `requests` rule; never fails
+
+    let mut separators = 0;
+    let mut title = None;
+    let mut blocks = vec![];
+    for element in requests.into_inner() {
+        match element.as_rule() {
+            Rule::request_separator => {
+                separators += 1;
+                title = element
+                    .into_inner()
+                    .map(|it| it.as_str().trim())
+                    .find(|it| !it.is_empty())
+                    .map(str::to_owned);
+            }
+            Rule::request_block => {
+                let text = element.as_str();
+                if contains_request(text) {
+                    let name = name_directive(text)
+                        .or(title.take())
+                        .unwrap_or_else(|| (blocks.len() + 1).to_string());
+                    blocks.push(RequestBlock {
+                        name,
+                        text: text.to_owned(),
+                    });
+                }
+            }
+            _ => (),
+        }
+    }
+
+    if separators == 0 {
+        return Ok(None);
+    }
+
+    let mut names = HashSet::new();
+    for block in &blocks {
+        if !names.insert(&block.name) {
+            return Err(anyhow!("duplicate request name '{}'", block.name));
+        }
+    }
+
+    Ok(Some(blocks))
+}
+
+fn contains_request(text: &str) -> bool {
+    text.lines()
+        .map(str::trim)
+        .any(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn name_directive(text: &str) -> Option<String> {
+    regex!(r"(?m)^#\s*@name[ \t]+(.*\S)")
+        .captures(text)
+        .map(|captures| captures.get(1).unwrap().as_str().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn should_not_split_files_without_separators() -> Result<()> {
+        let result = split_requests(indoc!(
+            r##"
+            # some comment
+            GET http://localhost/foo
+        "##
+        ))?;
+
+        assert_eq!(result, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_split_files_at_separators() -> Result<()> {
+        let result = split_requests(indoc!(
+            r##"
+            # requests for the users api
+
+            ### login
+            POST http://localhost/login
+
+            ###
+            # @name create-user
+            POST http://localhost/users
+
+            {}
+            ###
+            GET http://localhost/users
+        "##
+        ))?;
+
+        assert_eq!(
+            result,
+            Some(vec![
+                RequestBlock {
+                    name: "login".to_string(),
+                    text: "POST http://localhost/login\n".to_string(),
+                },
+                RequestBlock {
+                    name: "create-user".to_string(),
+                    text: "# @name create-user\nPOST http://localhost/users\n\n{}".to_string(),
+                },
+                RequestBlock {
+                    name: "3".to_string(),
+                    text: "GET http://localhost/users\n".to_string(),
+                },
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_duplicate_names() {
+        let result = split_requests(indoc!(
+            r##"
+            ### login
+            POST http://localhost/login
+            ### login
+            POST http://localhost/login
+        "##
+        ));
+
+        assert_err!(result, "duplicate request name 'login'");
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::Display;
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::{env, fs};
 
 use anyhow::{Context, Result};
 
@@ -14,6 +14,12 @@ pub fn canonicalize(p: &Path) -> Result<CanonicalizedPathBuf> {
 pub struct CanonicalizedPathBuf(PathBuf);
 
 impl CanonicalizedPathBuf {
+    /// Keeps a path as given, for paths that are only written into generated request files
+    /// and may not exist yet.
+    pub(crate) fn verbatim(path: PathBuf) -> Self {
+        CanonicalizedPathBuf(path)
+    }
+
     pub fn to_str(&self) -> &str {
         self.0
             .to_str()
@@ -83,3 +89,47 @@ impl<T: AsRef<Path>> RelativePath for T {
         get_dependency_path(self.as_ref(), path)
     }
 }
+
+/// Rewrites `path`, relative to the working directory, to be relative to `dir` instead.
+/// Works on the path names alone, so neither has to exist. Absolute paths stay as they are.
+pub fn rebase<P: AsRef<Path>, D: AsRef<Path>>(path: P, dir: D) -> Result<PathBuf> {
+    let path = path.as_ref();
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+
+    let cwd = env::current_dir()?;
+    let path = normalize(&cwd.join(path));
+    let dir = normalize(&cwd.join(dir));
+    let common = path
+        .components()
+        .zip(dir.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut ret = PathBuf::new();
+    for _ in dir.components().skip(common) {
+        ret.push("..");
+    }
+    for component in path.components().skip(common) {
+        ret.push(component);
+    }
+
+    Ok(ret)
+}
+
+/// Resolves `.` and `..` without looking at the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut ret = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                ret.pop();
+            }
+            other => ret.push(other),
+        }
+    }
+
+    ret
+}
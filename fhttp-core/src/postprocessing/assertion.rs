@@ -0,0 +1,363 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use pest::iterators::Pair;
+use pest::Parser;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::parsers::assertion_parser::{AssertionParser, Rule};
+use crate::postprocessing::response::header_value;
+use crate::postprocessing::response_handler::ResponseHandlerInput;
+
+/// A declarative check on a response, e.g. `status == 200` or `jsonpath $.items.length > 0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assertion {
+    source: String,
+    subject: Subject,
+    predicate: Predicate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Subject {
+    Status,
+    Body,
+    Header(String),
+    JsonPath(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Predicate {
+    Exists,
+    Compare(Operator, Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    Matches,
+}
+
+impl Assertion {
+    /// Parses the content of an `assert` block, assertions are separated by newlines or `;`.
+    pub fn parse_all(text: &str) -> Result<Vec<Assertion>> {
+        let assertions = AssertionParser::parse(Rule::assertions, text.trim())
+            .with_context(|| format!("failed to parse assertions '{}'", text.trim()))?
+            .next()
+            .unwrap();
+
+        assertions
+            .into_inner()
+            .filter(|pair| pair.as_rule() == Rule::assertion)
+            .map(parse_assertion)
+            .collect()
+    }
+
+    /// Checks all assertions, the error lists every failed one.
+    pub fn verify_all(assertions: &[Assertion], response: &ResponseHandlerInput) -> Result<()> {
+        let failures = assertions
+            .iter()
+            .filter_map(|assertion| assertion.verify(response).err())
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+
+        match failures.is_empty() {
+            true => Ok(()),
+            false => Err(anyhow!("{}", failures.join("\n"))),
+        }
+    }
+
+    pub fn verify(&self, response: &ResponseHandlerInput) -> Result<()> {
+        let fail = |e: anyhow::Error| anyhow!("assertion '{}' failed, {}", self, e);
+        let actual = self.subject.value(response).map_err(fail)?;
+
+        let passed = match (&self.predicate, &actual) {
+            (Predicate::Exists, actual) => actual.is_some(),
+            (Predicate::Compare(_, _), None) => false,
+            (Predicate::Compare(operator, expected), Some(actual)) => {
+                compare(*operator, actual, expected).map_err(fail)?
+            }
+        };
+
+        match (passed, actual) {
+            (true, _) => Ok(()),
+            (false, Some(actual)) => Err(anyhow!(
+                "assertion '{}' failed, actual value: {}",
+                self,
+                text(&actual)
+            )),
+            (false, None) => Err(anyhow!("assertion '{}' failed, no value found", self)),
+        }
+    }
+}
+
+impl Display for Assertion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn parse_assertion(pair: Pair<Rule>) -> Result<Assertion> {
+    let source = pair.as_str().trim().to_owned();
+    let mut subject = Subject::Status;
+    let mut operator = None;
+    let mut predicate = Predicate::Exists;
+
+    for part in pair.into_inner() {
+        match part.as_rule() {
+            Rule::status => subject = Subject::Status,
+            Rule::body => subject = Subject::Body,
+            Rule::header => subject = Subject::Header(inner_str(part)),
+            Rule::jsonpath => subject = Subject::JsonPath(inner_str(part)),
+            Rule::exists => predicate = Predicate::Exists,
+            Rule::operator => operator = Some(parse_operator(part.as_str())),
+            Rule::value => {
+                let operator = operator.unwrap();
+                let value = parse_value(part.as_str().trim())?;
+                if operator == Operator::Matches {
+                    Regex::new(&text(&value))
+                        .with_context(|| format!("invalid regex in assertion '{}'", source))?;
+                }
+                predicate = Predicate::Compare(operator, value);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(Assertion {
+        source,
+        subject,
+        predicate,
+    })
+}
+
+fn inner_str(pair: Pair<Rule>) -> String {
+    pair.into_inner().next().unwrap().as_str().to_owned()
+}
+
+fn parse_operator(operator: &str) -> Operator {
+    match operator {
+        "==" => Operator::Eq,
+        "!=" => Operator::Ne,
+        "<" => Operator::Lt,
+        "<=" => Operator::Le,
+        ">" => Operator::Gt,
+        ">=" => Operator::Ge,
+        "contains" => Operator::Contains,
+        "matches" => Operator::Matches,
+        _ => unreachable!(),
+    }
+}
+
+/// Values are json literals, anything else is taken as a plain string.
+fn parse_value(value: &str) -> Result<Value> {
+    match value.starts_with('"') {
+        true => serde_json::from_str(value).with_context(|| format!("invalid string {}", value)),
+        false => Ok(serde_json::from_str(value).unwrap_or_else(|_| Value::from(value))),
+    }
+}
+
+impl Subject {
+    fn value(&self, response: &ResponseHandlerInput) -> Result<Option<Value>> {
+        match self {
+            Subject::Status => Ok(Some(Value::from(response.status_code))),
+            Subject::Body => Ok(Some(Value::from(response.body.as_str()))),
+            Subject::Header(name) => Ok(header_value(&response.headers, name).map(Value::from)),
+            Subject::JsonPath(path) => select_json_path(path, &response.body),
+        }
+    }
+}
+
+/// Selects the first match of the path. Paths ending in `.length` evaluate to the size of
+/// the array, string or object they point to, unless there's an actual `length` property.
+fn select_json_path(path: &str, body: &str) -> Result<Option<Value>> {
+    let value: Value = serde_json::from_str(body)
+        .with_context(|| format!("failed to parse response body as json\nBody was '{}'", body))?;
+
+    if let Some(found) = select_first(path, &value)? {
+        return Ok(Some(found));
+    }
+
+    match path.strip_suffix(".length") {
+        Some(parent) => Ok(select_first(parent, &value)?.and_then(|it| match it {
+            Value::Array(array) => Some(Value::from(array.len())),
+            Value::String(string) => Some(Value::from(string.chars().count())),
+            Value::Object(object) => Some(Value::from(object.len())),
+            _ => None,
+        })),
+        None => Ok(None),
+    }
+}
+
+fn select_first(path: &str, value: &Value) -> Result<Option<Value>> {
+    let mut selector = jsonpath_lib::Selector::new();
+    let results = selector
+        .str_path(path)
+        .map_err(|e| anyhow!("invalid json path '{}': {:?}", path, e))?
+        .value(value)
+        .select()
+        .map_err(|e| anyhow!("invalid json path '{}': {:?}", path, e))?;
+
+    Ok(results.first().map(|it| (*it).clone()))
+}
+
+fn compare(operator: Operator, actual: &Value, expected: &Value) -> Result<bool> {
+    let ret = match operator {
+        Operator::Eq => equals(actual, expected),
+        Operator::Ne => !equals(actual, expected),
+        Operator::Lt => number(actual)? < number(expected)?,
+        Operator::Le => number(actual)? <= number(expected)?,
+        Operator::Gt => number(actual)? > number(expected)?,
+        Operator::Ge => number(actual)? >= number(expected)?,
+        Operator::Contains => match actual {
+            Value::Array(items) => items.iter().any(|item| equals(item, expected)),
+            _ => text(actual).contains(&text(expected)),
+        },
+        Operator::Matches => Regex::new(&text(expected))?.is_match(&text(actual)),
+    };
+
+    Ok(ret)
+}
+
+/// Numbers are compared numerically, so `header content-length == 42` works on the string
+/// value of the header. Everything else is compared by its text.
+fn equals(actual: &Value, expected: &Value) -> bool {
+    match (expected.is_number(), number(actual), number(expected)) {
+        (true, Ok(actual), Ok(expected)) => actual == expected,
+        _ => text(actual) == text(expected),
+    }
+}
+
+fn number(value: &Value) -> Result<f64> {
+    let ret = match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => f64::from_str(string.trim()).ok(),
+        _ => None,
+    };
+
+    ret.ok_or_else(|| anyhow!("'{}' is not a number", text(value)))
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderMap;
+
+    use super::*;
+    use crate::test_utils::errmsg;
+
+    fn response(status_code: u16, body: &str) -> ResponseHandlerInput {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "application/json".parse().unwrap());
+        headers.insert("content-length", body.len().to_string().parse().unwrap());
+
+        ResponseHandlerInput {
+            status_code,
+            headers,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn should_parse_assertions() -> Result<()> {
+        let assertions = Assertion::parse_all(
+            r#"
+            status == 200; header content-type contains "json"
+            jsonpath $.items[?(@.price > 10)].name exists
+            body matches ^\{
+            "#,
+        )?;
+
+        assert_eq!(
+            assertions,
+            vec![
+                Assertion {
+                    source: "status == 200".to_string(),
+                    subject: Subject::Status,
+                    predicate: Predicate::Compare(Operator::Eq, Value::from(200)),
+                },
+                Assertion {
+                    source: r#"header content-type contains "json""#.to_string(),
+                    subject: Subject::Header("content-type".to_string()),
+                    predicate: Predicate::Compare(Operator::Contains, Value::from("json")),
+                },
+                Assertion {
+                    source: "jsonpath $.items[?(@.price > 10)].name exists".to_string(),
+                    subject: Subject::JsonPath("$.items[?(@.price > 10)].name".to_string()),
+                    predicate: Predicate::Exists,
+                },
+                Assertion {
+                    source: r"body matches ^\{".to_string(),
+                    subject: Subject::Body,
+                    predicate: Predicate::Compare(Operator::Matches, Value::from(r"^\{")),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_assertions() {
+        assert!(Assertion::parse_all("status is 200").is_err());
+        assert_eq!(
+            errmsg(Assertion::parse_all("body matches (")),
+            "invalid regex in assertion 'body matches ('"
+        );
+    }
+
+    #[test]
+    fn should_verify_assertions() -> Result<()> {
+        let body = r#"{ "items": [1, 2], "name": "fhttp", "ok": true }"#;
+        let assertions = Assertion::parse_all(&format!(
+            r#"
+            status == 200
+            status != 404
+            header content-length == {}
+            jsonpath $.items.length > 0
+            jsonpath $.items contains 2
+            jsonpath $.name == fhttp
+            jsonpath $.name.length == 5
+            jsonpath $.ok == true
+            body contains "fhttp"
+            "#,
+            body.len()
+        ))?;
+
+        Assertion::verify_all(&assertions, &response(200, body))
+    }
+
+    #[test]
+    fn should_list_all_failed_assertions() -> Result<()> {
+        let response = response(404, r#"{ "items": [] }"#);
+        let assertions = Assertion::parse_all(
+            "status == 200; jsonpath $.items.length >= 1; header location exists; jsonpath $.id == 1",
+        )?;
+
+        assert_eq!(
+            errmsg(Assertion::verify_all(&assertions, &response)),
+            [
+                "assertion 'status == 200' failed, actual value: 404",
+                "assertion 'jsonpath $.items.length >= 1' failed, actual value: 0",
+                "assertion 'header location exists' failed, no value found",
+                "assertion 'jsonpath $.id == 1' failed, no value found",
+            ]
+            .join("\n")
+        );
+
+        Ok(())
+    }
+}
@@ -1,2 +1,3 @@
+pub mod assertion;
 pub mod response_handler;
 pub mod response;
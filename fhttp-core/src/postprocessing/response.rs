@@ -1,18 +1,22 @@
+use reqwest::header::HeaderMap;
 use reqwest::StatusCode;
 
 #[derive(Debug)]
 pub struct Response {
     status: StatusCode,
+    headers: HeaderMap,
     body: String
 }
 
 impl Response {
     pub fn new <S: Into<String>> (
         status: StatusCode,
+        headers: HeaderMap,
         body: S
     ) -> Self {
         Response {
             status,
+            headers,
             body: body.into()
         }
     }
@@ -21,7 +25,25 @@ impl Response {
         &self.status
     }
 
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
     pub fn body(&self) -> &str {
         &self.body
     }
 }
+
+/// Returns all values of the given header joined by ", ", or `None` if the header is absent.
+pub fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    let values = headers
+        .get_all(name)
+        .iter()
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+        .collect::<Vec<_>>();
+
+    match values.is_empty() {
+        true => None,
+        false => Some(values.join(", ")),
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt::Debug;
 use anyhow::{bail, format_err, Context, Result};
-use rhai::{Engine, EvalAltResult, Scope};
+use reqwest::header::HeaderMap;
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope};
+
+use crate::postprocessing::response::header_value;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum ResponseHandler {
@@ -12,6 +15,7 @@ pub enum ResponseHandler {
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ResponseHandlerInput {
     pub status_code: u16,
+    pub headers: HeaderMap,
     pub body: String,
 }
 
@@ -53,11 +57,12 @@ fn process_body_json(json_path: &str, body: &str) -> Result<String> {
 }
 
 fn process_response_rhai(program: &String, response: ResponseHandlerInput) -> Result<String> {
-    let ResponseHandlerInput { status_code, body } = response;
+    let ResponseHandlerInput { status_code, headers, body } = response;
     let engine = Engine::new();
     let mut scope = Scope::new();
 
     scope.push("status", status_code as i64); // use i64 for seamless comparisons in-script
+    scope.push("headers", headers_to_rhai_map(&headers));
     scope.push("body", body.clone());
 
     match engine.eval_with_scope::<String>(&mut scope, program) {
@@ -74,6 +79,17 @@ fn process_response_rhai(program: &String, response: ResponseHandlerInput) -> Re
     }
 }
 
+/// header names are lowercase, multiple values for the same header are joined by ", "
+fn headers_to_rhai_map(headers: &HeaderMap) -> Map {
+    headers
+        .keys()
+        .map(|name| {
+            let value = header_value(headers, name.as_str()).unwrap_or_default();
+            (name.as_str().into(), Dynamic::from(value))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod json_tests {
     use indoc::indoc;
@@ -97,7 +113,7 @@ mod json_tests {
         let handler = ResponseHandler::Json {
             json_path: "$.a.b.c".into(),
         };
-        let result = handler.process_body(ResponseHandlerInput{ body, status_code: 200 });
+        let result = handler.process_body(ResponseHandlerInput { body, status_code: 200, headers: HeaderMap::new() });
 
         assert_ok!(result, String::from("success"));
     }
@@ -118,7 +134,7 @@ mod json_tests {
         let handler = ResponseHandler::Json {
             json_path: "$.a.b.c".into(),
         };
-        let result = handler.process_body(ResponseHandlerInput { body, status_code: 200 });
+        let result = handler.process_body(ResponseHandlerInput { body, status_code: 200, headers: HeaderMap::new() });
 
         assert_ok!(result, String::from("3.141"));
     }
@@ -132,7 +148,7 @@ mod deno_tests {
     fn should_not_support_deno_anymore() {
         let body = "this is the response body".to_string();
         let handler = ResponseHandler::Deno { program: "".into() };
-        let result = handler.process_body(ResponseHandlerInput { body, status_code: 200 });
+        let result = handler.process_body(ResponseHandlerInput { body, status_code: 200, headers: HeaderMap::new() });
 
         assert_err!(result, "deno response handlers are no longer supported.");
     }
@@ -149,6 +165,7 @@ mod rhai_tests {
         let input = ResponseHandlerInput {
             body: "".to_string(),
             status_code: 200,
+            headers: HeaderMap::new(),
         };
         let handler = ResponseHandler::Rhai {
             program: indoc! ("
@@ -168,6 +185,7 @@ mod rhai_tests {
         let input = ResponseHandlerInput {
             body: "hello".to_string(),
             status_code: 200,
+            headers: HeaderMap::new(),
         };
         let handler = ResponseHandler::Rhai {
             program: indoc! ("
@@ -183,6 +201,7 @@ mod rhai_tests {
         let input = ResponseHandlerInput {
             body: "hello".to_string(),
             status_code: 500,
+            headers: HeaderMap::new(),
         };
         let handler = ResponseHandler::Rhai {
             program: indoc! ("
@@ -199,11 +218,32 @@ mod rhai_tests {
         "#);
     }
 
+    #[test]
+    fn should_pass_in_the_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append("location", "/resources/123".parse().unwrap());
+        headers.append("x-tag", "a".parse().unwrap());
+        headers.append("x-tag", "b".parse().unwrap());
+        let input = ResponseHandlerInput {
+            body: "".to_string(),
+            status_code: 201,
+            headers,
+        };
+        let handler = ResponseHandler::Rhai {
+            program: indoc! ("
+                headers[\"location\"] + \" \" + headers[\"x-tag\"]
+            ").to_string(),
+        };
+        let result = handler.process_body(input).expect("failed to invoke handler");
+        assert_debug_snapshot!(result, @r#""/resources/123 a, b""#);
+    }
+
     #[test]
     fn should_allow_parsing_json() {
         let input = ResponseHandlerInput {
             body: r#"{ "foo": [1, 2, 3] }"#.to_string(),
             status_code: 200,
+            headers: HeaderMap::new(),
         };
         let handler = ResponseHandler::Rhai {
             program: indoc! ("
@@ -220,6 +260,7 @@ mod rhai_tests {
         let input = ResponseHandlerInput {
             body: "body".to_string(),
             status_code: 200,
+            headers: HeaderMap::new(),
         };
         let handler = ResponseHandler::Rhai {
             program: "let x = 2 + 2;".to_string(),
@@ -233,6 +274,7 @@ mod rhai_tests {
         let input = ResponseHandlerInput {
             body: "body".to_string(),
             status_code: 200,
+            headers: HeaderMap::new(),
         };
         let handler = ResponseHandler::Rhai {
             program: "2 + 2".to_string(),
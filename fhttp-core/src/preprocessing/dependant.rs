@@ -15,8 +15,11 @@ pub trait Dependant {
     }
 }
 
+/// Finds `${request("PATH")}` and `${request_header("PATH", "NAME")}` expressions.
 pub fn request_dependencies(text: &str) -> Result<Vec<RequestDependencyEval>> {
-    let re_request = regex!(r#"(?m)(\\*)(\$\{request\("([^"]+)"\)})"#);
+    let re_request = regex!(
+        r#"(?m)(\\*)(\$\{(?:request\("([^"]+)"\)|request_header\("([^"]+)"\s*,\s*"([^"]+)"\))})"#
+    );
 
     let deps = re_request
         .captures_iter(text)
@@ -26,9 +29,10 @@ pub fn request_dependencies(text: &str) -> Result<Vec<RequestDependencyEval>> {
         .map(|capture: Captures| {
             let backslashes = capture.get(1).unwrap().range();
             let group = capture.get(2).unwrap();
-            let path = capture.get(3).unwrap().as_str();
+            let path = capture.get(3).or_else(|| capture.get(4)).unwrap().as_str();
+            let header = capture.get(5).map(|it| it.as_str());
 
-            RequestDependencyEval::new(path, group.range(), backslashes)
+            RequestDependencyEval::new(path, header, group.range(), backslashes)
         })
         .collect::<Vec<_>>();
 
@@ -1,50 +1,363 @@
-use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, RwLock};
+use std::thread;
+
+use anyhow::{anyhow, Result};
 use linked_hash_set::LinkedHashSet;
 
-use crate::execution::execution_order::plan_request_order;
-use crate::path_utils::CanonicalizedPathBuf;
+use crate::execution::execution_order::{plan_dependency_graph, plan_request_order};
+use crate::preprocessing::evaluation::Evaluation;
+use crate::profiles::VariableOrigin;
+use crate::request_sources::file_includes::included_files;
+use crate::request_sources::request_id::RequestId;
+use crate::request_sources::variable_support::get_env_vars;
 use crate::request_sources::Preprocessed;
+use crate::Client;
 use crate::Config;
 use crate::Profile;
 use crate::RequestSource;
+use crate::Response;
 use crate::ResponseStore;
 
+type ExecutionResult<T> = Result<(Option<Response>, T)>;
+
 // #[derive(Debug)]
 pub struct Requestpreprocessor {
     profile: Profile,
     config: Config,
+    /// the http client of the run, used for variables looked up over http, e.g. vault secrets
+    client: Client,
     requests: LinkedHashSet<RequestSource>,
-    response_data: ResponseStore,
+    dependencies: HashMap<RequestId, Vec<RequestId>>,
+    response_data: Arc<RwLock<ResponseStore>>,
 }
 
 impl Requestpreprocessor {
-    pub fn new(profile: Profile, requests: Vec<RequestSource>, config: Config) -> Result<Self> {
+    pub fn new(
+        profile: Profile,
+        requests: Vec<RequestSource>,
+        config: Config,
+        client: Client,
+    ) -> Result<Self> {
         let requests_in_order = plan_request_order(requests, &profile)?;
+        let dependencies = plan_dependency_graph(&requests_in_order, &profile)?;
 
         Ok(Requestpreprocessor {
             profile,
             config,
+            client,
             requests: requests_in_order,
-            response_data: ResponseStore::new(),
+            dependencies,
+            response_data: Arc::new(RwLock::new(ResponseStore::new())),
         })
     }
 
+    /// Uses the given store for the responses of this run, e.g. to keep them between runs.
+    /// Dependencies that already have a response in the store are not executed again.
+    pub fn with_response_store(mut self, store: Arc<RwLock<ResponseStore>>) -> Self {
+        let cached = {
+            let responses = store.read().unwrap();
+            self.requests
+                .iter()
+                .filter(|req| req.dependency && responses.contains(&req.id()))
+                .map(RequestSource::id)
+                .collect::<HashSet<_>>()
+        };
+
+        self.requests = mem::take(&mut self.requests)
+            .into_iter()
+            .filter(|req| !cached.contains(&req.id()))
+            .collect();
+        self.dependencies.retain(|id, _| !cached.contains(id));
+        for dependencies in self.dependencies.values_mut() {
+            dependencies.retain(|dep| !cached.contains(dep));
+        }
+        self.response_data = store;
+
+        self
+    }
+
+    /// Stores placeholders as the responses of the planned dependencies that have no response
+    /// yet and skips them, so the remaining requests can be resolved without executing anything.
+    pub fn with_placeholder_dependencies(self) -> Self {
+        {
+            let mut store = self.response_data.write().unwrap();
+            for req in self.requests.iter().filter(|req| req.dependency) {
+                if !store.contains(&req.id()) {
+                    store.store_placeholder(req.id());
+                }
+            }
+        }
+        let store = self.response_data.clone();
+
+        self.with_response_store(store)
+    }
+
+    /// Stores shell variable references like `${TOKEN}` as the responses of all planned
+    /// dependencies, for exporting the requests as a shell script. The dependencies stay planned.
+    pub fn with_shell_variable_dependencies(self) -> Self {
+        {
+            let mut store = self.response_data.write().unwrap();
+            for req in self.requests.iter().filter(|req| req.dependency) {
+                store.store_shell_variable(req.id());
+            }
+        }
+
+        self
+    }
+
+    pub fn response_store(&self) -> Arc<RwLock<ResponseStore>> {
+        self.response_data.clone()
+    }
+
+    /// The files each planned request is made of: its request file, the files it includes
+    /// and the files of the requests it depends on.
+    pub fn source_files(&self) -> HashMap<RequestId, HashSet<PathBuf>> {
+        let mut ret: HashMap<RequestId, HashSet<PathBuf>> = HashMap::new();
+
+        // dependencies are planned before the requests depending on them
+        for req in &self.requests {
+            let id = req.id();
+            let mut files = included_files(&req.source_path)
+                .into_iter()
+                .collect::<HashSet<_>>();
+            files.insert(req.source_path.clone().into());
+            for dep in &self.dependencies[&id] {
+                files.extend(ret.get(dep).into_iter().flatten().cloned());
+            }
+            ret.insert(id, files);
+        }
+
+        ret
+    }
+
+    /// The `${env(...)}` variables the planned requests reference, sorted by name, with where
+    /// they would be resolved from. Nothing is resolved or executed.
+    pub fn variables(&self) -> Vec<VariableUsage> {
+        let mut usages: BTreeMap<&str, (Option<&str>, bool, Vec<RequestId>)> = BTreeMap::new();
+
+        for req in &self.requests {
+            let id = req.id();
+            // occurrences come last to first
+            for occurrence in get_env_vars(&req.text).into_iter().rev() {
+                if occurrence.is_escaped() {
+                    continue;
+                }
+                let (default, always_defaulted, requests) = usages
+                    .entry(occurrence.name)
+                    .or_insert((None, true, vec![]));
+                match occurrence.default {
+                    Some(_) if default.is_none() => *default = occurrence.default,
+                    Some(_) => (),
+                    None => *always_defaulted = false,
+                }
+                if !requests.contains(&id) {
+                    requests.push(id.clone());
+                }
+            }
+        }
+
+        usages
+            .into_iter()
+            .map(
+                |(name, (default, always_defaulted, requests))| VariableUsage {
+                    name: name.to_owned(),
+                    // a default only helps if every occurrence has one
+                    origin: self
+                        .profile
+                        .origin(name, default.filter(|_| always_defaulted)),
+                    requests,
+                },
+            )
+            .collect()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.requests.is_empty()
     }
 
-    pub fn notify_response(&mut self, path: &CanonicalizedPathBuf, response: &str) {
-        self.response_data.store(path.clone(), response);
+    pub fn notify_response(&mut self, id: &RequestId, response: &Response) {
+        store_response(&mut self.response_data.write().unwrap(), id, response);
+    }
+
+    /// Runs all remaining requests with up to `parallelism` of them in flight at once. A request is
+    /// started as soon as all requests it depends on have finished successfully.
+    ///
+    /// `execute` gets the preprocessed request and returns its response, which is made available
+    /// to dependent requests, along with an arbitrary value. After the first failure no further
+    /// requests are started. The results are returned in the planned execution order, requests
+    /// that never ran are left out.
+    pub fn execute_parallel<T, F>(self, parallelism: usize, execute: F) -> Vec<Result<T>>
+    where
+        T: Send,
+        F: Fn(RequestSource<Preprocessed>) -> ExecutionResult<T> + Sync,
+    {
+        self.run(parallelism, false, execute)
+            .into_iter()
+            .filter_map(|outcome| match outcome.result {
+                Outcome::Done(result) => Some(result),
+                Outcome::Skipped(_) => None,
+            })
+            .collect()
+    }
+
+    /// Like `execute_parallel`, but keeps going after failures: only the requests that depend
+    /// on a failed request are skipped. Returns the outcomes of all requests in the planned
+    /// execution order.
+    pub fn execute_all<T, F>(self, parallelism: usize, execute: F) -> Vec<RequestOutcome<T>>
+    where
+        T: Send,
+        F: Fn(RequestSource<Preprocessed>) -> ExecutionResult<T> + Sync,
+    {
+        self.run(parallelism, true, execute)
+    }
+
+    fn run<T, F>(self, parallelism: usize, keep_going: bool, execute: F) -> Vec<RequestOutcome<T>>
+    where
+        T: Send,
+        F: Fn(RequestSource<Preprocessed>) -> ExecutionResult<T> + Sync,
+    {
+        let Requestpreprocessor {
+            profile,
+            config,
+            client,
+            requests,
+            dependencies,
+            response_data,
+        } = self;
+        let (profile, config, client, response_data, execute) =
+            (&profile, &config, &client, &*response_data, &execute);
+
+        let order = requests
+            .iter()
+            .map(|req| (req.id(), req.dependency))
+            .collect::<Vec<_>>();
+        let mut pending = requests.into_iter().collect::<Vec<_>>();
+        let mut completed = HashSet::new();
+        let mut failed = HashSet::new();
+        let mut results = HashMap::new();
+        let mut in_flight = 0;
+
+        thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel::<(RequestId, ExecutionResult<T>)>();
+
+            loop {
+                let mut index = 0;
+                while (keep_going || failed.is_empty())
+                    && in_flight < parallelism.max(1)
+                    && index < pending.len()
+                {
+                    let id = pending[index].id();
+                    if let Some(dep) = dependencies[&id].iter().find(|dep| failed.contains(*dep)) {
+                        pending.remove(index);
+                        results.insert(id.clone(), Outcome::Skipped(dep.clone()));
+                        failed.insert(id);
+                        continue;
+                    }
+                    let ready = dependencies[&id].iter().all(|dep| completed.contains(dep));
+                    if !ready {
+                        index += 1;
+                        continue;
+                    }
+
+                    let req = pending.remove(index);
+                    let sender = sender.clone();
+                    in_flight += 1;
+                    scope.spawn(move || {
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                            let req = {
+                                let response_data = response_data.read().unwrap();
+                                req.replace_variables(profile, config, client, &response_data)?
+                            };
+                            execute(req)
+                        }));
+
+                        match result {
+                            Ok(result) => {
+                                let _ = sender.send((id, result));
+                            }
+                            Err(payload) => {
+                                let _ = sender.send((id, Err(anyhow!("request panicked"))));
+                                panic::resume_unwind(payload);
+                            }
+                        }
+                    });
+                }
+
+                if in_flight == 0 {
+                    break;
+                }
+
+                let (id, result) = receiver.recv().unwrap();
+                in_flight -= 1;
+                match result {
+                    Ok((response, value)) => {
+                        if let Some(response) = response {
+                            store_response(&mut response_data.write().unwrap(), &id, &response);
+                        }
+                        completed.insert(id.clone());
+                        results.insert(id, Outcome::Done(Ok(value)));
+                    }
+                    Err(e) => {
+                        failed.insert(id.clone());
+                        results.insert(id, Outcome::Done(Err(e)));
+                    }
+                }
+            }
+        });
+
+        order
+            .into_iter()
+            .filter_map(|(id, dependency)| {
+                results.remove(&id).map(|result| RequestOutcome {
+                    id,
+                    dependency,
+                    result,
+                })
+            })
+            .collect()
     }
 }
 
+/// A variable referenced by planned requests, see `Requestpreprocessor::variables`.
+#[derive(Debug, Eq, PartialEq)]
+pub struct VariableUsage {
+    pub name: String,
+    pub origin: VariableOrigin,
+    /// the requests referencing the variable, in execution order
+    pub requests: Vec<RequestId>,
+}
+
+/// What became of a request run by `Requestpreprocessor::execute_all`.
+pub struct RequestOutcome<T> {
+    pub id: RequestId,
+    /// whether the request only ran as a dependency of another one
+    pub dependency: bool,
+    pub result: Outcome<T>,
+}
+
+pub enum Outcome<T> {
+    Done(Result<T>),
+    /// the request didn't run because the given dependency failed or was skipped
+    Skipped(RequestId),
+}
+
+fn store_response(response_data: &mut ResponseStore, id: &RequestId, response: &Response) {
+    response_data.store(id.clone(), response.body());
+    response_data.store_headers(id.clone(), response.headers().clone());
+}
+
 impl Iterator for Requestpreprocessor {
     type Item = Result<RequestSource<Preprocessed>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.requests
-            .pop_front()
-            .map(|req| req.replace_variables(&self.profile, &self.config, &self.response_data))
+        let response_data = self.response_data.read().unwrap();
+        self.requests.pop_front().map(|req| {
+            req.replace_variables(&self.profile, &self.config, &self.client, &response_data)
+        })
     }
 }
 
@@ -52,7 +365,10 @@ impl Iterator for Requestpreprocessor {
 mod dependencies {
     use std::env;
 
-    use crate::test_utils::root;
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+
+    use crate::test_utils::{client, root};
     use crate::RequestSource;
 
     use super::*;
@@ -69,10 +385,14 @@ mod dependencies {
             Profile::empty(env::current_dir().unwrap()),
             vec![init_request],
             Config::default(),
+            client(),
         )?;
 
         preprocessor.next();
-        preprocessor.notify_response(&dep_path, "dependency");
+        preprocessor.notify_response(
+            &RequestId::from(dep_path),
+            &Response::new(StatusCode::OK, HeaderMap::new(), "dependency"),
+        );
         let result = preprocessor.next().unwrap().unwrap();
         let req = result.parse()?;
         assert_eq!(req.request.url, "dependency");
@@ -80,3 +400,325 @@ mod dependencies {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::env;
+    use std::path::PathBuf;
+    use std::sync::{Arc, RwLock};
+
+    use anyhow::{anyhow, Result};
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+    use temp_dir::TempDir;
+
+    use crate::path_utils::CanonicalizedPathBuf;
+    use crate::preprocessing::request_preprocessor::{Outcome, VariableUsage};
+    use crate::profiles::VariableOrigin;
+    use crate::request_sources::request_id::RequestId;
+    use crate::test_utils::{client, write_test_file};
+    use crate::{Config, Profile, RequestSource, Requestpreprocessor, Response, ResponseStore};
+
+    #[test]
+    fn execute_parallel_should_provide_dependency_responses() -> Result<()> {
+        let workdir = TempDir::new()?;
+        write_test_file(&workdir, "token.http", "GET http://localhost/token")?;
+        let a = write_test_file(
+            &workdir,
+            "a.http",
+            r#"GET http://localhost/a/${request("token.http")}"#,
+        )?;
+        let b = write_test_file(&workdir, "b.http", "GET http://localhost/b")?;
+
+        let preprocessor = Requestpreprocessor::new(
+            Profile::empty(env::current_dir()?),
+            vec![
+                RequestSource::from_file(&a, false)?,
+                RequestSource::from_file(&b, false)?,
+            ],
+            Config::default(),
+            client(),
+        )?;
+
+        let results = preprocessor.execute_parallel(4, |req| {
+            let url = req.parse()?.request.url;
+            let body = url.rsplit('/').next().unwrap().to_uppercase();
+            Ok((
+                Some(Response::new(StatusCode::OK, HeaderMap::new(), body)),
+                url,
+            ))
+        });
+        let results = results.into_iter().collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(
+            results,
+            vec![
+                "http://localhost/token".to_string(),
+                "http://localhost/a/TOKEN".to_string(),
+                "http://localhost/b".to_string(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn execute_parallel_should_not_start_dependents_of_failed_requests() -> Result<()> {
+        let workdir = TempDir::new()?;
+        write_test_file(&workdir, "token.http", "GET http://localhost/token")?;
+        let a = write_test_file(
+            &workdir,
+            "a.http",
+            r#"GET http://localhost/a/${request("token.http")}"#,
+        )?;
+
+        let preprocessor = Requestpreprocessor::new(
+            Profile::empty(env::current_dir()?),
+            vec![RequestSource::from_file(&a, false)?],
+            Config::default(),
+            client(),
+        )?;
+
+        let results = preprocessor.execute_parallel(4, |req| -> Result<(Option<Response>, ())> {
+            Err(anyhow!("{} failed", req.parse()?.request.url))
+        });
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].as_ref().unwrap_err().to_string(),
+            "http://localhost/token failed"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_all_should_only_skip_dependents_of_failed_requests() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let token = write_test_file(&workdir, "token.http", "GET http://localhost/token")?;
+        let a = write_test_file(
+            &workdir,
+            "a.http",
+            r#"GET http://localhost/a/${request("token.http")}"#,
+        )?;
+        let b = write_test_file(&workdir, "b.http", "GET http://localhost/b")?;
+
+        let preprocessor = Requestpreprocessor::new(
+            Profile::empty(env::current_dir()?),
+            vec![
+                RequestSource::from_file(&a, false)?,
+                RequestSource::from_file(&b, false)?,
+            ],
+            Config::default(),
+            client(),
+        )?;
+
+        let outcomes = preprocessor.execute_all(1, |req| {
+            let url = req.parse()?.request.url;
+            match url.ends_with("token") {
+                true => Err(anyhow!("{} failed", url)),
+                false => Ok((None, url)),
+            }
+        });
+        let outcomes = outcomes
+            .into_iter()
+            .map(|outcome| {
+                let result = match outcome.result {
+                    Outcome::Done(Ok(url)) => url,
+                    Outcome::Done(Err(e)) => e.to_string(),
+                    Outcome::Skipped(dep) => format!("skipped, {} failed", dep),
+                };
+                (outcome.id, outcome.dependency, result)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            outcomes,
+            vec![
+                (
+                    RequestId::from(token.clone()),
+                    true,
+                    "http://localhost/token failed".to_string()
+                ),
+                (
+                    RequestId::from(a),
+                    false,
+                    format!("skipped, {} failed", token.to_str())
+                ),
+                (RequestId::from(b), false, "http://localhost/b".to_string()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reuse_stored_dependency_responses() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let token = write_test_file(&workdir, "token.http", "GET http://localhost/token")?;
+        let a = write_test_file(
+            &workdir,
+            "a.http",
+            r#"GET http://localhost/a/${request("token.http")}"#,
+        )?;
+
+        let mut store = ResponseStore::new();
+        store.store(token.clone(), "CACHED");
+        store.store_headers(token, HeaderMap::new());
+        let store = Arc::new(RwLock::new(store));
+
+        let preprocessor = Requestpreprocessor::new(
+            Profile::empty(env::current_dir()?),
+            vec![RequestSource::from_file(&a, false)?],
+            Config::default(),
+            client(),
+        )?
+        .with_response_store(store.clone());
+
+        let results = preprocessor.execute_parallel(1, |req| {
+            let url = req.parse()?.request.url;
+            Ok((
+                Some(Response::new(StatusCode::OK, HeaderMap::new(), "A")),
+                url,
+            ))
+        });
+        let results = results.into_iter().collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(results, vec!["http://localhost/a/CACHED".to_string()]);
+        assert_eq!(store.read().unwrap().get(&RequestId::from(a)), "A");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_replace_dependencies_with_placeholders() -> Result<()> {
+        let workdir = TempDir::new()?;
+        write_test_file(&workdir, "token.http", "GET http://localhost/token")?;
+        let id = write_test_file(&workdir, "id.http", "GET http://localhost/id")?;
+        let a = write_test_file(
+            &workdir,
+            "a.http",
+            r#"GET http://localhost/a/${request("token.http")}/${request("id.http")}
+location: ${request_header("token.http", "location")}"#,
+        )?;
+
+        let mut store = ResponseStore::new();
+        store.store(id, "42");
+        let store = Arc::new(RwLock::new(store));
+
+        let mut preprocessor = Requestpreprocessor::new(
+            Profile::empty(env::current_dir()?),
+            vec![RequestSource::from_file(&a, false)?],
+            Config::default(),
+            client(),
+        )?
+        .with_response_store(store)
+        .with_placeholder_dependencies();
+
+        let req = preprocessor.next().unwrap()?.parse()?.request;
+        assert_eq!(req.url, "http://localhost/a/<response of token.http>/42");
+        assert_eq!(req.headers["location"], "<header location of token.http>");
+        assert!(preprocessor.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_list_source_files_including_those_of_dependencies() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let body = write_test_file(&workdir, "body.txt", "secret")?;
+        let token = write_test_file(
+            &workdir,
+            "token.http",
+            r#"POST http://localhost/token
+
+${include("body.txt")}"#,
+        )?;
+        let a = write_test_file(
+            &workdir,
+            "a.http",
+            r#"GET http://localhost/a/${request("token.http")}"#,
+        )?;
+
+        let preprocessor = Requestpreprocessor::new(
+            Profile::empty(env::current_dir()?),
+            vec![RequestSource::from_file(&a, false)?],
+            Config::default(),
+            client(),
+        )?;
+        let files = preprocessor.source_files();
+
+        let paths = |paths: &[&CanonicalizedPathBuf]| {
+            paths
+                .iter()
+                .map(|it| PathBuf::from(it.to_str()))
+                .collect::<HashSet<_>>()
+        };
+        assert_eq!(
+            files[&RequestId::from(token.clone())],
+            paths(&[&token, &body])
+        );
+        assert_eq!(
+            files[&RequestId::from(a.clone())],
+            paths(&[&a, &token, &body])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_list_variables_of_requests_and_their_dependencies() -> Result<()> {
+        let workdir = TempDir::new()?;
+        write_test_file(&workdir, "body.txt", r#"${env(FHTTP_VARS_DEFAULTED, "d")}"#)?;
+        let token = write_test_file(
+            &workdir,
+            "token.http",
+            r#"POST http://localhost/token/${env(FHTTP_VARS_SHARED)}
+
+${include("body.txt")}"#,
+        )?;
+        let a = write_test_file(
+            &workdir,
+            "a.http",
+            r#"GET http://localhost/${env(FHTTP_VARS_SHARED)}/${request("token.http")}
+x: ${env(FHTTP_VARS_PARTIALLY_DEFAULTED, "d")}
+y: ${env(FHTTP_VARS_PARTIALLY_DEFAULTED)}
+z: \${env(FHTTP_VARS_ESCAPED)}"#,
+        )?;
+
+        let mut profile = Profile::empty(env::current_dir()?);
+        profile.override_with_values(
+            vec![("FHTTP_VARS_SHARED".to_owned(), "x".to_owned())],
+            "--var",
+        );
+        let preprocessor = Requestpreprocessor::new(
+            profile,
+            vec![RequestSource::from_file(&a, false)?],
+            Config::default(),
+            client(),
+        )?;
+
+        assert_eq!(
+            preprocessor.variables(),
+            vec![
+                VariableUsage {
+                    name: "FHTTP_VARS_DEFAULTED".into(),
+                    origin: VariableOrigin::Default("d".into()),
+                    requests: vec![RequestId::from(token.clone())],
+                },
+                VariableUsage {
+                    name: "FHTTP_VARS_PARTIALLY_DEFAULTED".into(),
+                    origin: VariableOrigin::Unresolved,
+                    requests: vec![RequestId::from(a.clone())],
+                },
+                VariableUsage {
+                    name: "FHTTP_VARS_SHARED".into(),
+                    origin: VariableOrigin::Profile(Some("--var".into())),
+                    requests: vec![RequestId::from(token), RequestId::from(a)],
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
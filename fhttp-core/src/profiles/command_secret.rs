@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::shell::single_quote;
+
+/// How long a secret command may run if the profile doesn't say otherwise.
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runs a program with the given arguments and environment variables and returns its trimmed
+/// stdout. Fails if the program can't be started, exits with a non-zero status or doesn't finish
+/// within the timeout.
+pub fn resolve_command(
+    command: &[String],
+    env: &BTreeMap<String, String>,
+    timeout: Duration,
+) -> Result<String> {
+    run_command(command, env, None, timeout)
+}
+
+/// Like [resolve_command], but writes `input` to the program's stdin, e.g. keys that shouldn't
+/// show up in its arguments.
+pub fn resolve_command_with_input(
+    command: &[String],
+    env: &BTreeMap<String, String>,
+    input: &str,
+    timeout: Duration,
+) -> Result<String> {
+    run_command(command, env, Some(input), timeout)
+}
+
+fn run_command(
+    command: &[String],
+    env: &BTreeMap<String, String>,
+    input: Option<&str>,
+    timeout: Duration,
+) -> Result<String> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| anyhow!("command secrets need a program to run"))?;
+    let command_line = render_command(command, &BTreeMap::new());
+
+    let mut child = Command::new(program)
+        .args(args)
+        .envs(env)
+        .stdin(match input {
+            Some(_) => Stdio::piped(),
+            None => Stdio::inherit(),
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("error running '{}'", command_line))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // the program may exit without reading its input, it fails on its own then
+        let _ = stdin.write_all(input.as_bytes());
+    }
+    let stdout = read_in_background(child.stdout.take().unwrap());
+    let stderr = read_in_background(child.stderr.take().unwrap());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!(
+                "'{}' timed out after {}ms",
+                command_line,
+                timeout.as_millis()
+            ));
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let stdout = stdout.join().unwrap();
+    let stderr = stderr.join().unwrap();
+    match status.success() {
+        true => Ok(String::from_utf8(stdout)
+            .with_context(|| format!("'{}' printed invalid utf-8", command_line))?
+            .trim()
+            .to_owned()),
+        false => Err(anyhow!(
+            "'{}' failed with {}: '{}'",
+            command_line,
+            status,
+            String::from_utf8_lossy(&stderr).trim()
+        )),
+    }
+}
+
+/// Renders the command as it would be typed into a shell, e.g. for curl exports.
+pub fn render_command(command: &[String], env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(key, value)| format!("{}={}", key, single_quote(value)))
+        .chain(command.iter().map(|it| single_quote(it)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn read_in_background<R: Read + Send + 'static>(mut input: R) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut ret = vec![];
+        let _ = input.read_to_end(&mut ret);
+        ret
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::btreemap;
+
+    use super::*;
+    use crate::test_utils::errmsg;
+
+    fn command(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|it| it.to_string()).collect()
+    }
+
+    #[test]
+    fn should_return_trimmed_stdout() -> Result<()> {
+        let result = resolve_command(
+            &command(&["sh", "-c", "echo \"  $GREETING, $NAME  \""]),
+            &btreemap! {
+                "GREETING".to_string() => "hello".to_string(),
+                "NAME".to_string() => "world".to_string(),
+            },
+            DEFAULT_COMMAND_TIMEOUT,
+        )?;
+
+        assert_eq!(result, "hello, world");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_write_the_input_to_stdin() -> Result<()> {
+        let result = resolve_command_with_input(
+            &command(&["sh", "-c", "tr a-z A-Z"]),
+            &BTreeMap::new(),
+            "age-secret-key",
+            DEFAULT_COMMAND_TIMEOUT,
+        )?;
+
+        assert_eq!(result, "AGE-SECRET-KEY");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_failing_commands() {
+        assert_eq!(
+            errmsg(resolve_command(
+                &command(&["sh", "-c", "echo 'no such secret' >&2; exit 3"]),
+                &BTreeMap::new(),
+                DEFAULT_COMMAND_TIMEOUT,
+            )),
+            "'sh -c 'echo '\\''no such secret'\\'' >&2; exit 3'' failed with exit status: 3: 'no such secret'"
+        );
+        assert!(errmsg(resolve_command(
+            &command(&["fhttp-no-such-program"]),
+            &BTreeMap::new(),
+            DEFAULT_COMMAND_TIMEOUT,
+        ))
+        .starts_with("error running 'fhttp-no-such-program'"));
+        assert_eq!(
+            errmsg(resolve_command(
+                &[],
+                &BTreeMap::new(),
+                DEFAULT_COMMAND_TIMEOUT
+            )),
+            "command secrets need a program to run"
+        );
+    }
+
+    #[test]
+    fn should_kill_commands_that_time_out() {
+        let start = Instant::now();
+
+        assert_eq!(
+            errmsg(resolve_command(
+                &command(&["sleep", "5"]),
+                &BTreeMap::new(),
+                Duration::from_millis(100),
+            )),
+            "'sleep 5' timed out after 100ms"
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn should_render_commands_for_shells() {
+        assert_eq!(
+            render_command(
+                &command(&["gopass", "show", "team/api key", "it's"]),
+                &btreemap! { "PASSWORD_STORE_DIR".to_string() => "~/store".to_string() },
+            ),
+            r"PASSWORD_STORE_DIR='~/store' gopass show 'team/api key' 'it'\''s'"
+        );
+    }
+}
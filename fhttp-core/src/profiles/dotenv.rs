@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::env;
+
+use anyhow::{anyhow, Result};
+
+/// Parses the content of a `.env` file.
+///
+/// * empty lines and lines starting with `#` are ignored, as are `export` prefixes
+/// * unquoted values end at a ` #` comment and are trimmed
+/// * single quoted values are taken literally
+/// * double quoted values may span several lines and support the escapes `\n`, `\t`, `\"`,
+///   `\\` and `\$`
+/// * `${NAME}` in unquoted and double quoted values is replaced with the value of the
+///   environment variable NAME, or of a variable defined earlier in the file, or nothing
+pub fn parse_dotenv(text: &str) -> Result<HashMap<String, String>> {
+    let mut ret = HashMap::new();
+    let mut lines = text.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+        let error = |msg: &str| anyhow!("line {}: {}", index + 1, msg);
+
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected NAME=value"))?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(error("invalid variable name"));
+        }
+
+        let value = value.trim_start();
+        let value = if let Some(value) = value.strip_prefix('\'') {
+            let (value, _) = value
+                .split_once('\'')
+                .ok_or_else(|| error("unterminated quote"))?;
+            value.to_owned()
+        } else if let Some(value) = value.strip_prefix('"') {
+            let mut value = value.to_owned();
+            while !has_closing_quote(&value) {
+                let (_, next) = lines.next().ok_or_else(|| error("unterminated quote"))?;
+                value.push('\n');
+                value.push_str(next);
+            }
+            unescape_and_interpolate(&value, &ret)
+        } else {
+            let value = match value.find(" #") {
+                Some(comment) => &value[..comment],
+                None => value,
+            };
+            interpolate(value.trim_end(), &ret)
+        };
+
+        ret.insert(name.to_owned(), value);
+    }
+
+    Ok(ret)
+}
+
+fn has_closing_quote(value: &str) -> bool {
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return true,
+            _ => escaped = false,
+        }
+    }
+
+    false
+}
+
+/// Handles the content of a double quoted value up to its closing quote. Escapes and
+/// interpolations are resolved in one pass, so `\\${X}` is a backslash followed by the value of X.
+fn unescape_and_interpolate(value: &str, variables: &HashMap<String, String>) -> String {
+    let mut ret = String::new();
+    let mut chars = value.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => match chars.next().map(|(_, it)| it) {
+                Some('n') => ret.push('\n'),
+                Some('t') => ret.push('\t'),
+                Some(other) => ret.push(other),
+                None => ret.push('\\'),
+            },
+            '$' => match regex!(r"^\$\{([A-Za-z_][A-Za-z0-9_]*)\}").captures(&value[index..]) {
+                Some(captures) => {
+                    ret.push_str(&variable_value(&captures[1], variables));
+                    // skip the rest of the (ascii) placeholder after the `$`
+                    chars.nth(captures[0].len() - 2);
+                }
+                None => ret.push(c),
+            },
+            _ => ret.push(c),
+        }
+    }
+
+    ret
+}
+
+fn interpolate(value: &str, variables: &HashMap<String, String>) -> String {
+    regex!(r"(\\?)\$\{([A-Za-z_][A-Za-z0-9_]*)\}")
+        .replace_all(value, |captures: &regex::Captures| {
+            match captures[1].is_empty() {
+                false => captures[0].to_owned(),
+                true => variable_value(&captures[2], variables),
+            }
+        })
+        .into_owned()
+}
+
+fn variable_value(name: &str, variables: &HashMap<String, String>) -> String {
+    env::var(name)
+        .ok()
+        .or_else(|| variables.get(name).cloned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use maplit::hashmap;
+
+    use super::*;
+    use crate::test_utils::errmsg;
+
+    #[test]
+    fn should_parse_dotenv_files() -> Result<()> {
+        env::set_var("FHTTP_DOTENV_TEST_HOST", "example.com");
+
+        let result = parse_dotenv(indoc!(
+            r#"
+            # local defaults
+            export BASE_URL = https://${FHTTP_DOTENV_TEST_HOST}/api # the api
+            USER_ID=42
+            EMPTY=
+            LITERAL='${USER_ID} is #1'
+            QUOTED="user ${USER_ID}:\t\"quoted\" \${NOT_INTERPOLATED}"
+            MULTILINE="first
+            second"
+            ESCAPED_BACKSLASH="a\\${USER_ID}"
+            PATH_WITH_HASH=a#b
+            "#
+        ))?;
+
+        assert_eq!(
+            result,
+            hashmap! {
+                "BASE_URL".to_string() => "https://example.com/api".to_string(),
+                "USER_ID".to_string() => "42".to_string(),
+                "EMPTY".to_string() => "".to_string(),
+                "LITERAL".to_string() => "${USER_ID} is #1".to_string(),
+                "QUOTED".to_string() => "user 42:\t\"quoted\" ${NOT_INTERPOLATED}".to_string(),
+                "MULTILINE".to_string() => "first\nsecond".to_string(),
+                "ESCAPED_BACKSLASH".to_string() => "a\\42".to_string(),
+                "PATH_WITH_HASH".to_string() => "a#b".to_string(),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_prefer_the_environment_when_interpolating() -> Result<()> {
+        env::set_var("FHTTP_DOTENV_TEST_USER", "env");
+
+        let result = parse_dotenv(
+            "FHTTP_DOTENV_TEST_USER=file\nGREETING=hi ${FHTTP_DOTENV_TEST_USER} ${MISSING}",
+        )?;
+
+        assert_eq!(result["GREETING"], "hi env ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_invalid_lines() {
+        assert_eq!(
+            errmsg(parse_dotenv("A=1\nB")),
+            "line 2: expected NAME=value"
+        );
+        assert_eq!(
+            errmsg(parse_dotenv("A B=1")),
+            "line 1: invalid variable name"
+        );
+        assert_eq!(
+            errmsg(parse_dotenv("A=\"1\nB=2")),
+            "line 1: unterminated quote"
+        );
+    }
+}
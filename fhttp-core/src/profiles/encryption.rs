@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+use std::env;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::profiles::command_secret::{
+    resolve_command, resolve_command_with_input, DEFAULT_COMMAND_TIMEOUT,
+};
+
+/// Decrypts profile files using the `sops` and `age` command line tools, which read their keys
+/// from the usual environment variables and key files.
+///
+/// * files ending with `.age` are decrypted with age, their format is chosen by the extension
+///   before `.age`, e.g. `fhttp-config.yaml.age` is a YAML file
+/// * JSON and YAML files with `sops` metadata are decrypted with sops
+/// * other files are returned as they are
+///
+/// Returns the content, the path whose extension determines the file format and which of its
+/// values were encrypted.
+pub fn decrypt_profile_file(
+    path: &Path,
+    content: Vec<u8>,
+) -> Result<(String, PathBuf, EncryptedValues)> {
+    if has_extension(path, "age") {
+        let (keys, identity_file) = age_identities()?;
+        let mut command = vec!["age".to_string(), "--decrypt".to_string()];
+        if let Some(identity_file) = identity_file {
+            command.push("--identity".to_string());
+            command.push(identity_file.to_string_lossy().into_owned());
+        }
+        if keys.is_some() {
+            // keep the keys out of the arguments, age reads them from stdin
+            command.push("--identity".to_string());
+            command.push("-".to_string());
+        }
+        command.push(path.to_string_lossy().into_owned());
+
+        let content = match keys {
+            Some(keys) => resolve_command_with_input(
+                &command,
+                &Default::default(),
+                &keys,
+                DEFAULT_COMMAND_TIMEOUT,
+            )?,
+            None => resolve_command(&command, &Default::default(), DEFAULT_COMMAND_TIMEOUT)?,
+        };
+        return Ok((content, path.with_extension(""), EncryptedValues::All));
+    }
+
+    let content = String::from_utf8(content).context("profile file is not valid utf-8")?;
+    match is_sops_encrypted(path, &content) {
+        true => {
+            let encrypted = sops_encrypted_variables(&content);
+            let content = resolve_command(
+                &[
+                    "sops".to_string(),
+                    "--decrypt".to_string(),
+                    path.to_string_lossy().into_owned(),
+                ],
+                &Default::default(),
+                DEFAULT_COMMAND_TIMEOUT,
+            )?;
+            Ok((
+                content,
+                path.to_path_buf(),
+                EncryptedValues::Some(encrypted),
+            ))
+        }
+        false => Ok((content, path.to_path_buf(), EncryptedValues::None)),
+    }
+}
+
+/// The values of a profile file that were encrypted.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EncryptedValues {
+    None,
+    /// age encrypts whole files
+    All,
+    /// sops encrypts single values, all of them unless configured otherwise, e.g. with
+    /// `--encrypted-regex`. Holds the names of profiles and their encrypted variables.
+    Some(HashSet<(String, String)>),
+}
+
+impl EncryptedValues {
+    pub fn contains(&self, profile: &str, variable: &str) -> bool {
+        match self {
+            EncryptedValues::None => false,
+            EncryptedValues::All => true,
+            EncryptedValues::Some(variables) => {
+                variables.contains(&(profile.to_owned(), variable.to_owned()))
+            }
+        }
+    }
+}
+
+/// The variables whose values sops encrypted, which look like `ENC[AES256_GCM,data:...]`.
+fn sops_encrypted_variables(content: &str) -> HashSet<(String, String)> {
+    let Ok(serde_yaml::Value::Mapping(profiles)) = serde_yaml::from_str(content) else {
+        return HashSet::new();
+    };
+
+    let mut ret = HashSet::new();
+    for (profile, value) in &profiles {
+        let Some(variables) = value.get("variables").and_then(|it| it.as_mapping()) else {
+            continue;
+        };
+        for (name, value) in variables {
+            if let (Some(profile), Some(name), Some(value)) =
+                (profile.as_str(), name.as_str(), value.as_str())
+            {
+                if value.starts_with("ENC[") {
+                    ret.insert((profile.to_owned(), name.to_owned()));
+                }
+            }
+        }
+    }
+
+    ret
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|it| it.eq_ignore_ascii_case(extension))
+}
+
+/// sops adds a top level `sops` object with the encryption metadata to the files it encrypts.
+fn is_sops_encrypted(path: &Path, content: &str) -> bool {
+    if has_extension(path, "toml") {
+        return false;
+    }
+
+    serde_yaml::from_str::<serde_yaml::Value>(content)
+        .ok()
+        .and_then(|it| it.get("sops").cloned())
+        .is_some_and(|it| it.get("mac").is_some())
+}
+
+/// The age identities, as configured for sops: the keys in `SOPS_AGE_KEY` and the key file
+/// `SOPS_AGE_KEY_FILE`, or `sops/age/keys.txt` in the user's config directory.
+fn age_identities() -> Result<(Option<String>, Option<PathBuf>)> {
+    let keys = env::var("SOPS_AGE_KEY")
+        .ok()
+        .filter(|it| !it.trim().is_empty());
+    let file = match env::var_os("SOPS_AGE_KEY_FILE") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|config| config.join("sops/age/keys.txt"))
+            .filter(|path| path.exists()),
+    };
+
+    match (keys, file) {
+        (None, None) => Err(anyhow!(
+            "no age identity found, set SOPS_AGE_KEY or SOPS_AGE_KEY_FILE or create ~/.config/sops/age/keys.txt"
+        )),
+        identities => Ok(identities),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn should_detect_sops_encrypted_files() {
+        let encrypted = indoc!(
+            r#"
+            default:
+              variables:
+                TOKEN: ENC[AES256_GCM,data:abc=,iv:def=,tag:ghi=,type:str]
+            sops:
+              age:
+                - recipient: age1abc
+              mac: ENC[AES256_GCM,data:jkl=,iv:mno=,tag:pqr=,type:str]
+              version: 3.8.1
+            "#
+        );
+
+        assert!(is_sops_encrypted(Path::new("fhttp-config.yaml"), encrypted));
+        assert!(is_sops_encrypted(
+            Path::new("fhttp-config.json"),
+            r#"{ "default": {}, "sops": { "mac": "ENC[...]" } }"#
+        ));
+        assert!(!is_sops_encrypted(
+            Path::new("fhttp-config.json"),
+            r#"{ "sops": { "variables": {} } }"#
+        ));
+        assert!(!is_sops_encrypted(
+            Path::new("fhttp-config.toml"),
+            "[sops]\nmac = 'x'"
+        ));
+    }
+
+    #[test]
+    fn should_find_variables_encrypted_by_sops() {
+        let encrypted = indoc!(
+            r#"
+            default:
+              variables:
+                TOKEN: ENC[AES256_GCM,data:abc=,iv:def=,tag:ghi=,type:str]
+                PORT: 8080
+            staging:
+              variables:
+                URL: https://staging.example.com
+            sops:
+              mac: ENC[AES256_GCM,data:jkl=,iv:mno=,tag:pqr=,type:str]
+            "#
+        );
+
+        assert_eq!(
+            sops_encrypted_variables(encrypted),
+            HashSet::from([("default".to_string(), "TOKEN".to_string())])
+        );
+    }
+
+    #[test]
+    fn should_return_unencrypted_files_as_they_are() -> Result<()> {
+        let path = Path::new("fhttp-config.json");
+        let content = r#"{ "default": { "variables": {} } }"#;
+
+        assert_eq!(
+            decrypt_profile_file(path, content.as_bytes().to_vec())?,
+            (
+                content.to_string(),
+                path.to_path_buf(),
+                EncryptedValues::None
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -1,50 +1,198 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env::{self, VarError};
+use std::fmt::{self, Display, Formatter};
 use std::iter::Iterator;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Mutex;
 
 use anyhow::{anyhow, Context, Result};
 use promptly::prompt;
 use serde::{Deserialize, Serialize};
 
-pub use profile_variable::ProfileVariable;
+pub use dotenv::parse_dotenv;
+pub use encryption::{decrypt_profile_file, EncryptedValues};
+pub use profile_variable::{ProfileVariable, SecretCache};
+pub use variable_file::load_variable_file;
 
-use crate::path_utils::RelativePath;
-use crate::{Config, ResponseStore};
+use crate::request_sources::request_id::RequestId;
+use crate::{secrets, Client, Config, ResponseStore};
 
+mod command_secret;
+mod dotenv;
+mod encryption;
 mod profile_variable;
+mod variable_file;
+mod vault;
+
+/// Encrypted values shorter than this, e.g. ports or flags, aren't masked, since masking them
+/// would garble every log line, url and error containing them.
+pub const MIN_MASKED_LENGTH: usize = 8;
+
+/// The profile files that are used if none is given, in order of precedence.
+pub const DEFAULT_PROFILE_FILES: [&str; 4] = [
+    "fhttp-config.json",
+    "fhttp-config.yaml",
+    "fhttp-config.yml",
+    "fhttp-config.toml",
+];
 
 pub struct Profiles;
 
 impl Profiles {
+    /// Parses a profile file. The format is chosen by the file extension, `.yaml`/`.yml` for
+    /// YAML, `.toml` for TOML and JSON for everything else. Files encrypted with sops or age are
+    /// decrypted first, see [decrypt_profile_file]. Their encrypted values are registered as
+    /// secrets, unless they are shorter than [MIN_MASKED_LENGTH].
     pub fn parse<P: AsRef<Path>>(path: P) -> Result<HashMap<String, Profile>> {
         let path = path.as_ref();
-        let content = std::fs::read_to_string(path)
+        let content = std::fs::read(path)
             .with_context(|| format!("Error opening file {}", path.to_str().unwrap()))?;
-        let profiles = serde_json::from_str::<HashMap<String, _Profile>>(&content)
+        let (content, format, encrypted) = decrypt_profile_file(path, content)
+            .with_context(|| format!("error decrypting {}", path.to_str().unwrap()))?;
+        let profiles = parse_profiles(&format, &content)
             .with_context(|| format!("error reading profile from {}", path.to_str().unwrap()))?;
-        let ret = profiles
+
+        let mut resolved = HashMap::new();
+        for name in profiles.keys() {
+            resolve_variables(name, &profiles, &mut resolved, &mut vec![]).with_context(|| {
+                format!("error reading profile from {}", path.to_str().unwrap())
+            })?;
+        }
+        let ret = resolved
             .into_iter()
-            .map(|(key, value)| {
-                let profile = Profile::new(path, value.variables);
+            .map(|(key, variables)| {
+                let mut profile = Profile::empty(path);
+                for (name, (origin, variable)) in variables {
+                    if let ProfileVariable::StringValue(value) = &variable {
+                        if encrypted.contains(&origin, &name) && value.len() >= MIN_MASKED_LENGTH
+                        {
+                            secrets::register_secret(value);
+                        }
+                    }
+                    profile
+                        .sources
+                        .insert(name.clone(), format!("profile '{}'", origin));
+                    profile.variables.insert(name, variable);
+                }
                 (key, profile)
             })
             .collect::<HashMap<String, Profile>>();
 
         Ok(ret)
     }
+
+    /// The first of the [DEFAULT_PROFILE_FILES] that exists in the current directory.
+    pub fn find_default() -> Option<PathBuf> {
+        DEFAULT_PROFILE_FILES
+            .iter()
+            .map(PathBuf::from)
+            .find(|it| it.exists())
+    }
+}
+
+fn parse_profiles(path: &Path, content: &str) -> Result<HashMap<String, _Profile>> {
+    let extension = path
+        .extension()
+        .map(|it| it.to_string_lossy().to_lowercase());
+
+    let profiles = match extension.as_deref() {
+        Some("yaml") | Some("yml") => serde_yaml::from_str(content)?,
+        Some("toml") => toml::from_str(content)?,
+        _ => serde_json::from_str(content)?,
+    };
+
+    Ok(profiles)
+}
+
+/// The variables of a profile by name, with the name of the profile that defines them.
+type ResolvedVariables = HashMap<String, (String, ProfileVariable)>;
+
+/// Merges the variables of the profiles that a profile extends, in the given order, with its
+/// own variables. `chain` holds the profiles currently being resolved, to detect cycles.
+fn resolve_variables<'a>(
+    name: &'a str,
+    profiles: &'a HashMap<String, _Profile>,
+    resolved: &mut HashMap<String, ResolvedVariables>,
+    chain: &mut Vec<&'a str>,
+) -> Result<ResolvedVariables> {
+    if let Some(variables) = resolved.get(name) {
+        return Ok(variables.clone());
+    }
+    if let Some(start) = chain.iter().position(|it| *it == name) {
+        let cycle = chain[start..].iter().chain([&name]).copied();
+        return Err(anyhow!(
+            "profiles extend each other in a cycle: {}",
+            cycle.collect::<Vec<_>>().join(" -> ")
+        ));
+    }
+
+    let profile = &profiles[name];
+    let mut variables = HashMap::new();
+    chain.push(name);
+    for parent in profile.extends.names() {
+        if !profiles.contains_key(parent) {
+            return Err(anyhow!(
+                "profile '{}' extends unknown profile '{}'",
+                name,
+                parent
+            ));
+        }
+        variables.extend(resolve_variables(parent, profiles, resolved, chain)?);
+    }
+    chain.pop();
+    variables.extend(
+        profile
+            .variables
+            .iter()
+            .map(|(key, value)| (key.clone(), (name.to_owned(), value.clone()))),
+    );
+
+    resolved.insert(name.to_owned(), variables.clone());
+    Ok(variables)
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 struct _Profile {
+    #[serde(default)]
+    pub extends: Extends,
+    #[serde(default)]
     pub variables: HashMap<String, ProfileVariable>,
 }
 
+/// The profiles a profile extends, either one name or a list of names.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Extends {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Extends {
+    fn names(&self) -> &[String] {
+        match self {
+            Extends::One(name) => std::slice::from_ref(name),
+            Extends::Many(names) => names,
+        }
+    }
+}
+
+impl Default for Extends {
+    fn default() -> Self {
+        Extends::Many(vec![])
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Profile {
     source_path: PathBuf,
     variables: HashMap<String, ProfileVariable>,
+    /// variables of a `.env` file, used for names that aren't set in the environment
+    #[serde(skip)]
+    dotenv: HashMap<String, String>,
+    /// where variables were defined, e.g. `profile 'staging'`
+    #[serde(skip)]
+    sources: HashMap<String, String>,
 }
 
 impl Profile {
@@ -52,6 +200,8 @@ impl Profile {
         Profile {
             source_path: source_path.into(),
             variables: HashMap::new(),
+            dotenv: HashMap::new(),
+            sources: HashMap::new(),
         }
     }
 
@@ -62,6 +212,8 @@ impl Profile {
         Profile {
             source_path: source_path.into(),
             variables,
+            dotenv: HashMap::new(),
+            sources: HashMap::new(),
         }
     }
 
@@ -83,6 +235,7 @@ impl Profile {
         &self,
         key: K,
         config: &Config,
+        client: &Client,
         response_store: &ResponseStore,
         default: Option<&'a str>,
         for_dependency: bool,
@@ -91,10 +244,26 @@ impl Profile {
 
         match self.variables.get(key) {
             Some(ProfileVariable::Request { request }) => {
-                Ok(response_store.get(&self.get_dependency_path(request)?))
+                Ok(response_store.get(&RequestId::resolve(&self.source_path, request)?))
             }
-            Some(var) => var.get(config, for_dependency),
-            None => get_from_environment(key, config, default),
+            Some(var) => var.get(config, client, for_dependency),
+            None => get_from_environment(key, config, &self.dotenv, default),
+        }
+    }
+
+    /// Where the variable `key` would be resolved from by [Profile::get], without resolving it.
+    pub fn origin(&self, key: &str, default: Option<&str>) -> VariableOrigin {
+        if self.variables.contains_key(key) {
+            return VariableOrigin::Profile(self.sources.get(key).cloned());
+        }
+
+        match env::var_os(key) {
+            Some(_) => VariableOrigin::Environment,
+            None if self.dotenv.contains_key(key) => VariableOrigin::DotEnv,
+            None => match default {
+                Some(default) => VariableOrigin::Default(default.to_owned()),
+                None => VariableOrigin::Unresolved,
+            },
         }
     }
 
@@ -106,11 +275,54 @@ impl Profile {
         self.variables.values().collect()
     }
 
-    pub fn override_with(&mut self, other: Profile) {
+    /// The variables sorted by name, with where they were defined, if known.
+    pub fn annotated_variables(&self) -> Vec<(&str, &ProfileVariable, Option<&str>)> {
+        let mut ret = self
+            .variables
+            .iter()
+            .map(|(key, value)| {
+                (
+                    key.as_str(),
+                    value,
+                    self.sources.get(key).map(String::as_str),
+                )
+            })
+            .collect::<Vec<_>>();
+        ret.sort_by_key(|(key, _, _)| *key);
+
+        ret
+    }
+
+    pub fn override_with(&mut self, mut other: Profile) {
         for (key, value) in other.variables {
+            match other.sources.remove(&key) {
+                Some(source) => self.sources.insert(key.clone(), source),
+                None => self.sources.remove(&key),
+            };
             self.variables.insert(key, value);
         }
     }
+
+    /// Sets the variables of a `.env` file. They are used for variables that neither the profile
+    /// nor the environment defines.
+    pub fn set_dotenv_variables(&mut self, variables: HashMap<String, String>) {
+        self.dotenv = variables;
+    }
+
+    /// Sets variables to fixed values, e.g. from the command line. They replace profile
+    /// variables of the same name, including secrets and request variables. `source` describes
+    /// where the values come from.
+    pub fn override_with_values<I: IntoIterator<Item = (String, String)>>(
+        &mut self,
+        values: I,
+        source: &str,
+    ) {
+        for (key, value) in values {
+            self.sources.insert(key.clone(), source.to_owned());
+            self.variables
+                .insert(key, ProfileVariable::StringValue(value));
+        }
+    }
 }
 
 impl AsRef<Path> for Profile {
@@ -119,22 +331,65 @@ impl AsRef<Path> for Profile {
     }
 }
 
-fn get_from_environment(key: &str, config: &Config, default: Option<&str>) -> Result<String> {
+/// Where the value of a variable comes from, see [Profile::origin].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum VariableOrigin {
+    /// a profile variable, with where it was defined, if known
+    Profile(Option<String>),
+    Environment,
+    DotEnv,
+    /// the default given in the request, e.g. `${env(NAME, "default")}`
+    Default(String),
+    /// fhttp would prompt for the variable or fail with `--no-prompt`
+    Unresolved,
+}
+
+impl Display for VariableOrigin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableOrigin::Profile(Some(source)) => write!(f, "{}", source),
+            VariableOrigin::Profile(None) => write!(f, "profile"),
+            VariableOrigin::Environment => write!(f, "environment"),
+            VariableOrigin::DotEnv => write!(f, ".env file"),
+            VariableOrigin::Default(default) => write!(f, "default \"{}\"", default),
+            VariableOrigin::Unresolved => write!(f, "unresolved"),
+        }
+    }
+}
+
+/// Values entered at prompts, kept here instead of in the process environment, which must not
+/// be modified while requests are preprocessed on several threads. The lock also ensures that
+/// only one thread prompts at a time.
+static PROMPTED_VARIABLES: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Environment variables take precedence over `.env` variables, which take precedence over the
+/// default.
+fn get_from_environment(
+    key: &str,
+    config: &Config,
+    dotenv: &HashMap<String, String>,
+    default: Option<&str>,
+) -> Result<String> {
     match env::var(key) {
         Ok(value) => Ok(value),
         Err(VarError::NotUnicode(_)) => {
             Err(anyhow!("environment variable {} is not unicode!", key))
         }
+        Err(VarError::NotPresent) if dotenv.contains_key(key) => Ok(dotenv[key].clone()),
         Err(VarError::NotPresent) => match default {
             Some(default) => Ok(default.to_owned()),
-            None => match config.prompt_missing_env_vars() {
-                true => {
-                    let value = prompt::<String, _>(&key).unwrap();
-                    env::set_var(key, &value);
-                    Ok(value)
+            None => {
+                let mut prompted = PROMPTED_VARIABLES.lock().unwrap();
+                match prompted.get(key) {
+                    Some(value) => Ok(value.clone()),
+                    None if config.prompt_missing_env_vars() => {
+                        let value = prompt::<String, _>(&key).unwrap();
+                        prompted.insert(key.to_owned(), value.clone());
+                        Ok(value)
+                    }
+                    None => Err(anyhow!(format!("missing environment variable {}", key))),
                 }
-                false => Err(anyhow!(format!("missing environment variable {}", key))),
-            },
+            }
         },
     }
 }
@@ -143,10 +398,13 @@ fn get_from_environment(key: &str, config: &Config, default: Option<&str>) -> Re
 mod test {
     use std::env;
 
+    use indoc::indoc;
     use maplit::hashmap;
+    use rstest::rstest;
+    use temp_dir::TempDir;
 
     use crate::profiles::ProfileVariable;
-    use crate::test_utils::root;
+    use crate::test_utils::{client, root, write_test_file};
 
     use super::*;
 
@@ -160,12 +418,18 @@ mod test {
                 "development".into() => Profile {
                     source_path: root().join("resources/test/profiles/profile1.json").path_buf(),
                     variables: hashmap!{},
+                    dotenv: HashMap::new(),
+                    sources: HashMap::new(),
                 },
                 "testing".into() => Profile {
                     source_path: root().join("resources/test/profiles/profile1.json").path_buf(),
                     variables: hashmap!{
                         "var1".into() => ProfileVariable::StringValue("value1".into())
                     },
+                    dotenv: HashMap::new(),
+                    sources: hashmap!{
+                        "var1".into() => "profile 'testing'".into()
+                    },
                 }
             }
         );
@@ -173,6 +437,129 @@ mod test {
         Ok(())
     }
 
+    #[rstest]
+    #[case("all-variables.json")]
+    #[case("all-variables.yaml")]
+    #[case("all-variables.toml")]
+    fn should_load_profiles_by_file_extension(#[case] file: &str) -> Result<()> {
+        let path = root().join("resources/test/profiles").join(file).path_buf();
+        let mut profiles = Profiles::parse(&path)?;
+        let profile = profiles.remove("default").unwrap();
+
+        assert!(profiles.is_empty());
+        assert_eq!(profile.source_path(), path.as_path());
+        assert_eq!(
+            profile.variables,
+            hashmap! {
+                "url".into() => ProfileVariable::StringValue("http://localhost:8080".into()),
+                "password".into() => ProfileVariable::PassSecret {
+                    pass: "path/to/password".into(),
+                    cache: SecretCache::default(),
+                },
+                "api_key".into() => ProfileVariable::OnePasswordSecret {
+                    onepassword: "op://vault/item/field".into(),
+                    cache: SecretCache::default(),
+                },
+                "token".into() => ProfileVariable::Request {
+                    request: "token.http".into(),
+                },
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_resolve_extended_profiles() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let path = write_test_file(
+            &workdir,
+            "profiles.yaml",
+            indoc!(
+                r#"
+                staging:
+                  variables:
+                    URL: https://staging.example.com
+                    REGION: none
+                    token:
+                      request: token.http
+                eu:
+                  variables:
+                    REGION: eu
+                    LANGUAGE: de
+                staging-eu:
+                  extends: [staging, eu]
+                  variables:
+                    LANGUAGE: fr
+                staging-eu-debug:
+                  extends: staging-eu
+                "#
+            ),
+        )?;
+
+        let profiles = Profiles::parse(&path)?;
+
+        let profile = &profiles["staging-eu-debug"];
+        assert_eq!(profile.source_path(), path.as_ref());
+        assert_eq!(
+            profile.variables,
+            hashmap! {
+                "URL".into() => ProfileVariable::StringValue("https://staging.example.com".into()),
+                "REGION".into() => ProfileVariable::StringValue("eu".into()),
+                "LANGUAGE".into() => ProfileVariable::StringValue("fr".into()),
+                "token".into() => ProfileVariable::Request { request: "token.http".into() },
+            }
+        );
+        assert_eq!(
+            profile.sources,
+            hashmap! {
+                "URL".into() => "profile 'staging'".into(),
+                "REGION".into() => "profile 'eu'".into(),
+                "LANGUAGE".into() => "profile 'staging-eu'".into(),
+                "token".into() => "profile 'staging'".into(),
+            }
+        );
+        assert_eq!(profiles["staging-eu"].variables, profile.variables);
+        assert_eq!(profiles["eu"].variables.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_invalid_extends_chains() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let cycle = write_test_file(
+            &workdir,
+            "cycle.json",
+            r#"{
+                "default": { "variables": {} },
+                "a": { "extends": "b" },
+                "b": { "extends": ["default", "c"] },
+                "c": { "extends": "a" }
+            }"#,
+        )?;
+        let unknown = write_test_file(&workdir, "unknown.json", r#"{ "a": { "extends": "b" } }"#)?;
+
+        let message = format!("{:#}", Profiles::parse(&cycle).unwrap_err());
+        assert!(
+            ["a -> b -> c -> a", "b -> c -> a -> b", "c -> a -> b -> c"]
+                .iter()
+                .any(|it| message
+                    .ends_with(&format!("profiles extend each other in a cycle: {}", it))),
+            "{}",
+            message
+        );
+        assert_eq!(
+            format!("{:#}", Profiles::parse(&unknown).unwrap_err()),
+            format!(
+                "error reading profile from {}: profile 'a' extends unknown profile 'b'",
+                unknown.to_str()
+            )
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn get_should_get_variables() -> Result<()> {
         let profile = Profile {
@@ -180,10 +567,12 @@ mod test {
             variables: hashmap! {
                 "a".into() => ProfileVariable::StringValue("b".into())
             },
+            dotenv: HashMap::new(),
+            sources: HashMap::new(),
         };
 
         assert_eq!(
-            profile.get("a", &Config::default(), &ResponseStore::new(), None, true)?,
+            profile.get("a", &Config::default(), &client(), &ResponseStore::new(), None, true)?,
             String::from("b")
         );
 
@@ -197,16 +586,81 @@ mod test {
         let profile = Profile {
             source_path: env::current_dir().unwrap(),
             variables: HashMap::new(),
+            dotenv: HashMap::new(),
+            sources: HashMap::new(),
         };
 
         assert_eq!(
-            profile.get("a", &Config::default(), &ResponseStore::new(), None, true)?,
+            profile.get("a", &Config::default(), &client(), &ResponseStore::new(), None, true)?,
             String::from("A")
         );
 
         Ok(())
     }
 
+    #[test]
+    fn get_should_prefer_env_vars_over_dotenv_variables() -> Result<()> {
+        env::set_var("FHTTP_PROFILE_TEST_ENV", "env");
+
+        let mut profile = Profile::empty(env::current_dir().unwrap());
+        profile.set_dotenv_variables(hashmap! {
+            "FHTTP_PROFILE_TEST_ENV".into() => "dotenv".into(),
+            "FHTTP_PROFILE_TEST_DOTENV".into() => "dotenv".into(),
+        });
+        let get = |key| {
+            profile.get(
+                key,
+                &Config::default(),
+                &client(),
+                &ResponseStore::new(),
+                Some("default"),
+                true,
+            )
+        };
+
+        assert_eq!(get("FHTTP_PROFILE_TEST_ENV")?, "env");
+        assert_eq!(get("FHTTP_PROFILE_TEST_DOTENV")?, "dotenv");
+        assert_eq!(get("FHTTP_PROFILE_TEST_MISSING")?, "default");
+
+        Ok(())
+    }
+
+    #[test]
+    fn origin_should_follow_the_resolution_order() {
+        env::set_var("FHTTP_PROFILE_ORIGIN_ENV", "env");
+
+        let mut profile = Profile::empty(env::current_dir().unwrap());
+        profile.override_with_values(
+            vec![("FHTTP_PROFILE_ORIGIN_ENV".into(), "var".into())],
+            "--var",
+        );
+        profile.set_dotenv_variables(hashmap! {
+            "FHTTP_PROFILE_ORIGIN_DOTENV".into() => "dotenv".into(),
+        });
+
+        assert_eq!(
+            profile.origin("FHTTP_PROFILE_ORIGIN_ENV", None),
+            VariableOrigin::Profile(Some("--var".into()))
+        );
+        profile.variables.clear();
+        assert_eq!(
+            profile.origin("FHTTP_PROFILE_ORIGIN_ENV", None),
+            VariableOrigin::Environment
+        );
+        assert_eq!(
+            profile.origin("FHTTP_PROFILE_ORIGIN_DOTENV", Some("default")),
+            VariableOrigin::DotEnv
+        );
+        assert_eq!(
+            profile.origin("FHTTP_PROFILE_ORIGIN_MISSING", Some("default")),
+            VariableOrigin::Default("default".into())
+        );
+        assert_eq!(
+            profile.origin("FHTTP_PROFILE_ORIGIN_MISSING", None),
+            VariableOrigin::Unresolved
+        );
+    }
+
     #[test]
     fn override_with_should_merge() -> Result<()> {
         let config = Config::default();
@@ -228,16 +682,77 @@ mod test {
         );
 
         default.override_with(local);
-        assert_eq!(default.get("a", &config, &response_store, None, true)?, "A");
+        assert_eq!(default.get("a", &config, &client(), &response_store, None, true)?, "A");
         assert_eq!(
-            default.get("b", &config, &response_store, None, true)?,
+            default.get("b", &config, &client(), &response_store, None, true)?,
             "BBB"
         );
         assert_eq!(
-            default.get("c", &config, &response_store, None, true)?,
+            default.get("c", &config, &client(), &response_store, None, true)?,
             "CCC"
         );
 
         Ok(())
     }
+
+    #[test]
+    fn override_with_values_should_replace_all_kinds_of_variables() -> Result<()> {
+        let config = Config::default();
+        let response_store = ResponseStore::new();
+
+        let mut profile = Profile::new(
+            env::current_dir().unwrap(),
+            hashmap! {
+                String::from("token") => ProfileVariable::Request { request: String::from("token.http") },
+                String::from("password") => ProfileVariable::PassSecret {
+                    pass: String::from("app/password"),
+                    cache: SecretCache::default(),
+                },
+            },
+        );
+        profile.override_with_values(
+            hashmap! {
+                String::from("token") => String::from("TOKEN"),
+                String::from("password") => String::from("PASSWORD"),
+            },
+            "--var",
+        );
+
+        assert_eq!(profile.defined_through_request("token"), None);
+        assert_eq!(
+            profile.annotated_variables()[1],
+            (
+                "token",
+                &ProfileVariable::StringValue("TOKEN".into()),
+                Some("--var")
+            )
+        );
+        assert_eq!(
+            profile.get("token", &config, &client(), &response_store, None, true)?,
+            "TOKEN"
+        );
+        assert_eq!(
+            profile.get("password", &config, &client(), &response_store, None, true)?,
+            "PASSWORD"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reuse_prompted_values_without_changing_the_environment() -> Result<()> {
+        PROMPTED_VARIABLES
+            .lock()
+            .unwrap()
+            .insert("FHTTP_PROMPTED_VAR".to_owned(), "prompted".to_owned());
+        let config = Config::new(true, 0, false, false, None, false);
+
+        assert_eq!(
+            get_from_environment("FHTTP_PROMPTED_VAR", &config, &HashMap::new(), None)?,
+            "prompted"
+        );
+        assert!(env::var_os("FHTTP_PROMPTED_VAR").is_none());
+
+        Ok(())
+    }
 }
@@ -1,9 +1,48 @@
+#[cfg(test)]
 use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
-use crate::Config;
+use crate::profiles::command_secret::{render_command, resolve_command, DEFAULT_COMMAND_TIMEOUT};
+use crate::profiles::vault::{render_vault_command, resolve_vault, VaultSettings};
+use crate::secrets::{self, MASK};
+use crate::{Client, Config};
+
+/// Caches the resolved value of a secret. Safe to share between threads, concurrent lookups
+/// of the same secret wait for the first one instead of resolving the secret again.
+#[derive(Debug, Default)]
+pub struct SecretCache(Mutex<Option<String>>);
+
+impl SecretCache {
+    fn get_or_resolve<F: FnOnce() -> Result<String>>(&self, resolve: F) -> Result<String> {
+        let mut cache = self.0.lock().unwrap();
+        if cache.is_none() {
+            let value = resolve()?;
+            secrets::register_secret(&value);
+            cache.replace(value);
+        }
+
+        Ok(cache.as_ref().unwrap().clone())
+    }
+}
+
+impl Clone for SecretCache {
+    fn clone(&self) -> Self {
+        SecretCache(Mutex::new(self.0.lock().unwrap().clone()))
+    }
+}
+
+impl PartialEq for SecretCache {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other) || *self.0.lock().unwrap() == *other.0.lock().unwrap()
+    }
+}
+
+impl Eq for SecretCache {}
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -12,12 +51,28 @@ pub enum ProfileVariable {
     PassSecret {
         pass: String,
         #[serde(skip)]
-        cache: RefCell<Option<String>>,
+        cache: SecretCache,
     },
     OnePasswordSecret {
         onepassword: String,
         #[serde(skip)]
-        cache: RefCell<Option<String>>,
+        cache: SecretCache,
+    },
+    CommandSecret {
+        command: Vec<String>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        env: BTreeMap<String, String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+        #[serde(skip)]
+        cache: SecretCache,
+    },
+    VaultSecret {
+        vault: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        namespace: Option<String>,
+        #[serde(skip)]
+        cache: SecretCache,
     },
     Request {
         request: String,
@@ -25,35 +80,74 @@ pub enum ProfileVariable {
 }
 
 impl ProfileVariable {
-    pub fn get(&self, config: &Config, for_dependency: bool) -> Result<String> {
+    pub fn get(&self, config: &Config, client: &Client, for_dependency: bool) -> Result<String> {
         match self {
             ProfileVariable::StringValue(ref value) => Ok(value.to_owned()),
             ProfileVariable::PassSecret { pass: path, cache } => {
-                if config.curl() && !for_dependency {
+                if config.secret_commands(for_dependency) {
                     Ok(format!("$(pass {})", path))
                 } else {
-                    if cache.borrow().is_none() {
+                    cache.get_or_resolve(|| {
                         config.log(2, format!("resolving pass secret '{}'... ", &path));
                         let value = resolve_pass(path)?.trim().to_owned();
                         config.logln(2, "done");
-                        cache.borrow_mut().replace(value);
-                    }
-
-                    Ok(cache.borrow().as_ref().unwrap().clone())
+                        Ok(value)
+                    })
                 }
             }
             ProfileVariable::OnePasswordSecret { onepassword, cache } => {
-                if config.curl() && !for_dependency {
+                if config.secret_commands(for_dependency) {
                     Ok(format!("$(op read {})", onepassword))
                 } else {
-                    if cache.borrow().is_none() {
+                    cache.get_or_resolve(|| {
                         config.log(2, format!("resolving onepassword secret '{}'... ", &onepassword));
                         let value = resolve_onepassword(onepassword)?.trim().to_owned();
                         config.logln(2, "done");
-                        cache.borrow_mut().replace(value);
-                    }
-
-                    Ok(cache.borrow().as_ref().unwrap().clone())
+                        Ok(value)
+                    })
+                }
+            }
+            ProfileVariable::CommandSecret {
+                command,
+                env,
+                timeout_ms,
+                cache,
+            } => {
+                if config.secret_commands(for_dependency) {
+                    Ok(format!("$({})", render_command(command, env)))
+                } else {
+                    cache.get_or_resolve(|| {
+                        config.log(
+                            2,
+                            format!("resolving command secret '{}'... ", command.join(" ")),
+                        );
+                        let timeout = timeout_ms
+                            .map(Duration::from_millis)
+                            .unwrap_or(DEFAULT_COMMAND_TIMEOUT);
+                        let value = resolve_command(command, env, timeout)?;
+                        config.logln(2, "done");
+                        Ok(value)
+                    })
+                }
+            }
+            ProfileVariable::VaultSecret {
+                vault,
+                namespace,
+                cache,
+            } => {
+                if config.secret_commands(for_dependency) {
+                    Ok(format!(
+                        "$({})",
+                        render_vault_command(vault, namespace.as_deref())
+                    ))
+                } else {
+                    cache.get_or_resolve(|| {
+                        config.log(2, format!("resolving vault secret '{}'... ", &vault));
+                        let settings = VaultSettings::from_env()?;
+                        let value = resolve_vault(vault, namespace.as_deref(), &settings, client)?;
+                        config.logln(2, "done");
+                        Ok(value)
+                    })
                 }
             }
             ProfileVariable::Request { request: _ } => {
@@ -61,6 +155,25 @@ impl ProfileVariable {
             }
         }
     }
+
+    /// Describes the variable without resolving it, secrets are masked.
+    pub fn describe(&self) -> String {
+        match self {
+            ProfileVariable::StringValue(value) if secrets::is_secret(value) => {
+                format!("{} (encrypted profile file)", MASK)
+            }
+            ProfileVariable::StringValue(value) => value.to_owned(),
+            ProfileVariable::PassSecret { pass, .. } => format!("{} (pass {})", MASK, pass),
+            ProfileVariable::OnePasswordSecret { onepassword, .. } => {
+                format!("{} (onepassword {})", MASK, onepassword)
+            }
+            ProfileVariable::CommandSecret { command, .. } => {
+                format!("{} (command {})", MASK, command.join(" "))
+            }
+            ProfileVariable::VaultSecret { vault, .. } => format!("{} (vault {})", MASK, vault),
+            ProfileVariable::Request { request } => format!("response of {}", request),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -144,7 +257,7 @@ mod test {
             result,
             ProfileVariable::PassSecret {
                 pass: "foo/bar".into(),
-                cache: RefCell::new(None)
+                cache: SecretCache::default()
             }
         );
     }
@@ -163,7 +276,51 @@ mod test {
             result,
             ProfileVariable::OnePasswordSecret {
                 onepassword: "op://pass/word".into(),
-                cache: RefCell::new(None)
+                cache: SecretCache::default()
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_command_secret() {
+        let input = indoc!(
+            r##"
+            {
+                "command": ["gopass", "show", "x"],
+                "env": { "PASSWORD_STORE_DIR": "/store" },
+                "timeout_ms": 5000
+            }
+        "##
+        );
+        let result = serde_json::from_str::<ProfileVariable>(input).unwrap();
+        assert_eq!(
+            result,
+            ProfileVariable::CommandSecret {
+                command: vec!["gopass".into(), "show".into(), "x".into()],
+                env: BTreeMap::from([("PASSWORD_STORE_DIR".into(), "/store".into())]),
+                timeout_ms: Some(5000),
+                cache: SecretCache::default()
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_vault_secret() {
+        let input = indoc!(
+            r##"
+            {
+                "vault": "secret/data/app#client_secret",
+                "namespace": "team"
+            }
+        "##
+        );
+        let result = serde_json::from_str::<ProfileVariable>(input).unwrap();
+        assert_eq!(
+            result,
+            ProfileVariable::VaultSecret {
+                vault: "secret/data/app#client_secret".into(),
+                namespace: Some("team".into()),
+                cache: SecretCache::default()
             }
         );
     }
@@ -174,6 +331,8 @@ mod curl {
     use super::*;
     use rstest::{fixture, rstest};
 
+    use crate::test_utils::client;
+
     #[fixture]
     fn program() -> Config {
         Config::new(false, 0, false, false, None, true)
@@ -182,7 +341,7 @@ mod curl {
     #[rstest]
     fn string_value_should_return_normally(program: Config) {
         let var = ProfileVariable::StringValue(String::from("value"));
-        let result = var.get(&program, false);
+        let result = var.get(&program, &client(), false);
 
         assert_ok!(result, String::from("value"));
     }
@@ -193,9 +352,9 @@ mod curl {
 
         let var = ProfileVariable::PassSecret {
             pass: "path/to/secret".to_string(),
-            cache: RefCell::new(None),
+            cache: SecretCache::default(),
         };
-        let result = var.get(&program, false);
+        let result = var.get(&program, &client(), false);
 
         assert_ok!(result, String::from("$(pass path/to/secret)"));
 
@@ -208,9 +367,9 @@ mod curl {
 
         let var = ProfileVariable::PassSecret {
             pass: "path/to/secret".to_string(),
-            cache: RefCell::new(None),
+            cache: SecretCache::default(),
         };
-        let result = var.get(&program, true);
+        let result = var.get(&program, &client(), true);
 
         assert_ok!(result, String::from("pass_secret"));
 
@@ -219,4 +378,54 @@ mod curl {
             assert_eq!(&invocations, &["path/to/secret".to_string()]);
         });
     }
+
+    #[rstest]
+    fn pass_should_only_be_invoked_once_per_secret(program: Config) {
+        PASS_INVOCATIONS.with(|it| it.borrow_mut().clear());
+
+        let var = ProfileVariable::PassSecret {
+            pass: "path/to/secret".to_string(),
+            cache: SecretCache::default(),
+        };
+        assert_ok!(var.get(&program, &client(), true), String::from("pass_secret"));
+        assert_ok!(var.get(&program, &client(), true), String::from("pass_secret"));
+
+        PASS_INVOCATIONS.with(|it| assert_eq!(it.borrow().len(), 1));
+    }
+
+    #[rstest]
+    fn command_should_return_the_command_line_for_non_dependencies(program: Config) {
+        let var = ProfileVariable::CommandSecret {
+            command: vec!["gopass".into(), "show".into(), "team/api key".into()],
+            env: BTreeMap::from([("PASSWORD_STORE_DIR".into(), "/store".into())]),
+            timeout_ms: None,
+            cache: SecretCache::default(),
+        };
+
+        assert_ok!(
+            var.get(&program, &client(), false),
+            String::from("$(PASSWORD_STORE_DIR=/store gopass show 'team/api key')")
+        );
+    }
+
+    #[rstest]
+    fn command_should_only_be_invoked_once_per_secret(program: Config) {
+        let workdir = temp_dir::TempDir::new().unwrap();
+        let log = workdir.child("invocations");
+        let var = ProfileVariable::CommandSecret {
+            command: vec![
+                "sh".into(),
+                "-c".into(),
+                "echo invoked >> \"$LOG\"; echo secret".into(),
+            ],
+            env: BTreeMap::from([("LOG".into(), log.to_str().unwrap().into())]),
+            timeout_ms: None,
+            cache: SecretCache::default(),
+        };
+
+        assert_ok!(var.get(&program, &client(), true), String::from("secret"));
+        assert_ok!(var.get(&program, &client(), true), String::from("secret"));
+
+        assert_eq!(std::fs::read_to_string(log).unwrap(), "invoked\n");
+    }
 }
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+use crate::profiles::dotenv::parse_dotenv;
+
+/// Reads variables from a `.json` file containing an object, or from a file in `.env` format.
+pub fn load_variable_file<P: AsRef<Path>>(path: P) -> Result<HashMap<String, String>> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Error opening file {}", path.to_string_lossy()))?;
+
+    let ret = match path.to_string_lossy().to_lowercase().ends_with(".json") {
+        true => parse_json_variables(&content),
+        false => parse_dotenv(&content),
+    };
+
+    ret.with_context(|| format!("error reading variables from {}", path.to_string_lossy()))
+}
+
+fn parse_json_variables(text: &str) -> Result<HashMap<String, String>> {
+    let variables: HashMap<String, Value> = serde_json::from_str(text)?;
+
+    variables
+        .into_iter()
+        .map(|(name, value)| match value {
+            Value::String(value) => Ok((name, value)),
+            Value::Number(_) | Value::Bool(_) => Ok((name, value.to_string())),
+            _ => Err(anyhow!(
+                "variable '{}' must be a string, number or boolean",
+                name
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use maplit::hashmap;
+    use temp_dir::TempDir;
+
+    use super::*;
+    use crate::test_utils::{errmsg, write_test_file};
+
+    #[test]
+    fn should_load_env_files() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let file = write_test_file(
+            &workdir,
+            "vars.env",
+            indoc!(
+                r#"
+                # local overrides
+                BASE_URL=http://localhost:8080
+                USER = "john doe"
+                TOKEN='a=b'
+            "#
+            ),
+        )?;
+
+        assert_eq!(
+            load_variable_file(&file)?,
+            hashmap! {
+                "BASE_URL".to_string() => "http://localhost:8080".to_string(),
+                "USER".to_string() => "john doe".to_string(),
+                "TOKEN".to_string() => "a=b".to_string(),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_load_json_files() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let file = write_test_file(
+            &workdir,
+            "vars.json",
+            r#"{ "BASE_URL": "http://localhost", "PORT": 8080, "DEBUG": true }"#,
+        )?;
+
+        assert_eq!(
+            load_variable_file(&file)?,
+            hashmap! {
+                "BASE_URL".to_string() => "http://localhost".to_string(),
+                "PORT".to_string() => "8080".to_string(),
+                "DEBUG".to_string() => "true".to_string(),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_lines() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let file = write_test_file(&workdir, "vars.env", "A=1\nB\n")?;
+
+        assert_eq!(
+            format!("{:#}", load_variable_file(&file).unwrap_err()),
+            format!(
+                "error reading variables from {}: line 2: expected NAME=value",
+                file.to_str()
+            )
+        );
+        let file = write_test_file(&workdir, "vars.json", r#"{ "A": [1] }"#)?;
+        assert!(errmsg(load_variable_file(&file)).starts_with("error reading variables from"));
+
+        Ok(())
+    }
+}
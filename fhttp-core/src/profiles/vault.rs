@@ -0,0 +1,284 @@
+use std::env;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+use crate::Client;
+
+/// Where to find Vault and how to authenticate, usually taken from the environment.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VaultSettings {
+    pub address: String,
+    pub token: String,
+    pub namespace: Option<String>,
+}
+
+impl VaultSettings {
+    /// Reads `VAULT_ADDR`, `VAULT_NAMESPACE` and `VAULT_TOKEN`, falling back to the token the
+    /// vault CLI stores in `~/.vault-token`.
+    pub fn from_env() -> Result<Self> {
+        let address = env::var("VAULT_ADDR")
+            .map_err(|_| anyhow!("missing environment variable VAULT_ADDR for vault secrets"))?;
+        let token = match env::var("VAULT_TOKEN") {
+            Ok(token) => token,
+            Err(_) => token_file()
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .map(|it| it.trim().to_owned())
+                .ok_or_else(|| {
+                    anyhow!("vault secrets need a token in VAULT_TOKEN or ~/.vault-token")
+                })?,
+        };
+
+        Ok(VaultSettings {
+            address,
+            token,
+            namespace: env::var("VAULT_NAMESPACE").ok(),
+        })
+    }
+}
+
+fn token_file() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".vault-token"))
+}
+
+/// Splits a secret reference like `secret/data/app#client_secret` into path and field.
+fn split_secret(secret: &str) -> Result<(&str, &str)> {
+    match secret.rsplit_once('#') {
+        Some((path, field)) if !path.is_empty() && !field.is_empty() => {
+            Ok((path.trim_matches('/'), field))
+        }
+        _ => Err(anyhow!(
+            "invalid vault secret '{}', expected 'path#field'",
+            secret
+        )),
+    }
+}
+
+/// Reads a field of a secret from the KV secrets engine. Works with KV v1 paths like
+/// `secret/app` as well as KV v2 paths like `secret/data/app`. The profile's namespace takes
+/// precedence over the one of the settings. Uses the http client of the run, so the lookup
+/// honours its timeout.
+pub fn resolve_vault(
+    secret: &str,
+    namespace: Option<&str>,
+    settings: &VaultSettings,
+    client: &Client,
+) -> Result<String> {
+    let (path, field) = split_secret(secret)?;
+    let url = format!("{}/v1/{}", settings.address.trim_end_matches('/'), path);
+
+    let mut request = client
+        .http_client()
+        .get(&url)
+        .header("X-Vault-Token", &settings.token);
+    if let Some(namespace) = namespace.or(settings.namespace.as_deref()) {
+        request = request.header("X-Vault-Namespace", namespace);
+    }
+    let response = request
+        .send()
+        .with_context(|| format!("error reading vault secret '{}'", path))?;
+
+    let status = response.status();
+    let body = response
+        .text()
+        .ok()
+        .and_then(|it| serde_json::from_str::<Value>(&it).ok())
+        .unwrap_or_default();
+    if !status.is_success() {
+        let errors = match body.get("errors").and_then(Value::as_array) {
+            Some(errors) if !errors.is_empty() => errors
+                .iter()
+                .map(|it| it.as_str().map(str::to_owned).unwrap_or(it.to_string()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => status.to_string(),
+        };
+        return Err(anyhow!(
+            "vault returned an error for '{}': {}",
+            path,
+            errors
+        ));
+    }
+
+    let data = &body["data"];
+    // KV v2 wraps the secret's fields in another data object, next to its metadata
+    let fields = match data.get("metadata").is_some() && data["data"].is_object() {
+        true => &data["data"],
+        false => data,
+    };
+    match fields.get(field) {
+        Some(Value::String(value)) => Ok(value.to_owned()),
+        Some(Value::Null) | None => {
+            Err(anyhow!("vault secret '{}' has no field '{}'", path, field))
+        }
+        Some(value) => Ok(value.to_string()),
+    }
+}
+
+/// Renders the vault CLI command that reads the secret, e.g. for curl exports. `vault kv get`
+/// detects the KV version itself and expects KV v2 paths without their `data` segment.
+pub fn render_vault_command(secret: &str, namespace: Option<&str>) -> String {
+    let (path, field) = split_secret(secret).unwrap_or((secret, ""));
+    let path = match path.split_once('/') {
+        Some((mount, rest)) => match rest.strip_prefix("data/") {
+            Some(rest) => format!("{}/{}", mount, rest),
+            None => path.to_owned(),
+        },
+        None => path.to_owned(),
+    };
+
+    match namespace {
+        Some(namespace) => format!(
+            "vault kv get -namespace={} -field={} {}",
+            namespace, field, path
+        ),
+        None => format!("vault kv get -field={} {}", field, path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use rstest::rstest;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::test_utils::{client, errmsg};
+    use crate::{Config, CookieJar};
+
+    fn settings(server: &MockServer) -> VaultSettings {
+        VaultSettings {
+            address: server.uri(),
+            token: "vault-token".to_string(),
+            namespace: Some("default-namespace".to_string()),
+        }
+    }
+
+    #[rstest]
+    async fn should_read_kv_v1_and_v2_secrets() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/app"))
+            .and(header("X-Vault-Token", "vault-token"))
+            .and(header("X-Vault-Namespace", "team"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {
+                    "data": { "client_secret": "v2-secret", "port": 8080 },
+                    "metadata": { "version": 3 }
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/kv/app"))
+            .and(header("X-Vault-Namespace", "default-namespace"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "client_secret": "v1-secret" }
+            })))
+            .mount(&server)
+            .await;
+
+        let settings = settings(&server);
+        let client = client();
+        assert_eq!(
+            resolve_vault(
+                "secret/data/app#client_secret",
+                Some("team"),
+                &settings,
+                &client
+            )?,
+            "v2-secret"
+        );
+        assert_eq!(
+            resolve_vault("secret/data/app#port", Some("team"), &settings, &client)?,
+            "8080"
+        );
+        assert_eq!(
+            resolve_vault("kv/app#client_secret", None, &settings, &client)?,
+            "v1-secret"
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    async fn should_report_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/app"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "data": {}, "metadata": {} }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/forbidden"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .set_body_json(json!({ "errors": ["permission denied"] })),
+            )
+            .mount(&server)
+            .await;
+
+        let settings = settings(&server);
+        let client = client();
+        assert_eq!(
+            errmsg(resolve_vault(
+                "secret/data/app#missing",
+                None,
+                &settings,
+                &client
+            )),
+            "vault secret 'secret/data/app' has no field 'missing'"
+        );
+        assert_eq!(
+            errmsg(resolve_vault(
+                "secret/data/forbidden#x",
+                None,
+                &settings,
+                &client
+            )),
+            "vault returned an error for 'secret/data/forbidden': permission denied"
+        );
+        assert_eq!(
+            errmsg(resolve_vault("secret/data/app", None, &settings, &client)),
+            "invalid vault secret 'secret/data/app', expected 'path#field'"
+        );
+    }
+
+    #[rstest]
+    async fn should_use_the_timeout_of_the_client() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "data": { "field": "value" } }))
+                    .set_delay(Duration::from_millis(1_000)),
+            )
+            .mount(&server)
+            .await;
+
+        let config = Config::new(false, 0, false, false, Some(100), false);
+        let client = Client::new(&config, Arc::new(CookieJar::new())).unwrap();
+        let result = resolve_vault("secret/app#field", None, &settings(&server), &client);
+
+        assert!(errmsg(result).starts_with("error reading vault secret 'secret/app'"));
+    }
+
+    #[test]
+    fn should_render_vault_commands() {
+        assert_eq!(
+            render_vault_command("secret/data/app#client_secret", None),
+            "vault kv get -field=client_secret secret/app"
+        );
+        assert_eq!(
+            render_vault_command("kv/app#client_secret", Some("team")),
+            "vault kv get -namespace=team -field=client_secret kv/app"
+        );
+    }
+}
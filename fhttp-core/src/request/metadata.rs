@@ -0,0 +1,163 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::StatusCode;
+
+use crate::request::status_set::StatusSet;
+
+/// Per-request settings that override the corresponding values from `Config`.
+/// Set through `# @name value` directives in request files or top-level keys
+/// in structured (yaml/json) request files.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RequestMetadata {
+    pub name: Option<String>,
+    pub timeout_ms: Option<u64>,
+    pub no_redirect: Option<bool>,
+    pub retries: Option<u32>,
+    pub retry_on: Option<StatusSet>,
+    pub retry_non_idempotent: Option<bool>,
+    pub expect_status: Option<StatusSet>,
+    /// used to select requests to run, see `RequestFilter`
+    pub tags: Vec<String>,
+}
+
+impl RequestMetadata {
+    /// Applies a `# @name value` line. Lines with unknown names, e.g. `# @author jane`,
+    /// are comments and ignored.
+    pub fn apply_directive(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "name" if !value.is_empty() => self.name = Some(value.to_owned()),
+            "timeout" => self.timeout_ms = Some(parse_value(name, value)?),
+            "no-redirect" => self.no_redirect = Some(parse_flag(name, value)?),
+            "retries" => self.retries = Some(parse_value(name, value)?),
+            "retry-on" => self.retry_on = Some(parse_value(name, value)?),
+            "retry-non-idempotent" => self.retry_non_idempotent = Some(parse_flag(name, value)?),
+            "expect-status" => self.expect_status = Some(parse_value(name, value)?),
+            "tags" => self.tags = parse_tags(value),
+            "name" => return Err(anyhow!("directive '@name' needs a value")),
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn no_redirect(&self) -> bool {
+        self.no_redirect.unwrap_or(false)
+    }
+
+    /// Whether the request succeeded with the given status. Without an `expect-status`
+    /// declaration, all 2xx statuses are accepted.
+    pub fn is_expected_status(&self, status: StatusCode) -> bool {
+        match self.expect_status {
+            Some(expected) => expected.contains(status),
+            None => status.is_success(),
+        }
+    }
+}
+
+fn parse_value<T>(name: &str, value: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Into<anyhow::Error>,
+{
+    T::from_str(value)
+        .map_err(Into::into)
+        .with_context(|| format!("invalid value '{}' for directive '@{}'", value, name))
+}
+
+/// Tags are separated by commas or whitespace.
+pub fn parse_tags(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Flags may be given without a value, which means `true`.
+fn parse_flag(name: &str, value: &str) -> Result<bool> {
+    match value {
+        "" => Ok(true),
+        _ => parse_value(name, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::errmsg;
+
+    #[test]
+    fn should_apply_directives() -> Result<()> {
+        let mut metadata = RequestMetadata::default();
+        metadata.apply_directive("name", "create user")?;
+        metadata.apply_directive("timeout", "5000")?;
+        metadata.apply_directive("no-redirect", "true")?;
+        metadata.apply_directive("retries", "3")?;
+        metadata.apply_directive("retry-on", "500-504")?;
+        metadata.apply_directive("retry-non-idempotent", "")?;
+        metadata.apply_directive("expect-status", "200,404")?;
+        metadata.apply_directive("tags", "smoke, users slow")?;
+
+        assert_eq!(
+            metadata,
+            RequestMetadata {
+                name: Some("create user".to_string()),
+                timeout_ms: Some(5000),
+                no_redirect: Some(true),
+                retries: Some(3),
+                retry_on: Some(StatusSet::from_str("500,501,502,503,504")?),
+                retry_non_idempotent: Some(true),
+                expect_status: Some(StatusSet::from_str("200,404")?),
+                tags: vec!["smoke".to_string(), "users".to_string(), "slow".to_string()],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_ignore_unknown_directives() -> Result<()> {
+        let mut metadata = RequestMetadata::default();
+        metadata.apply_directive("author", "jane")?;
+
+        assert_eq!(metadata, RequestMetadata::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_values() {
+        let mut metadata = RequestMetadata::default();
+
+        assert_eq!(
+            errmsg(metadata.apply_directive("retries", "many")),
+            "invalid value 'many' for directive '@retries'"
+        );
+        assert_eq!(
+            errmsg(metadata.apply_directive("name", "")),
+            "directive '@name' needs a value"
+        );
+    }
+
+    #[test]
+    fn should_check_expected_statuses() -> Result<()> {
+        let metadata = RequestMetadata::default();
+        assert!(metadata.is_expected_status(StatusCode::NO_CONTENT));
+        assert!(!metadata.is_expected_status(StatusCode::NOT_FOUND));
+
+        let metadata = RequestMetadata {
+            expect_status: Some(StatusSet::from_str("204,404")?),
+            ..Default::default()
+        };
+        assert!(metadata.is_expected_status(StatusCode::NOT_FOUND));
+        assert!(!metadata.is_expected_status(StatusCode::OK));
+
+        Ok(())
+    }
+}
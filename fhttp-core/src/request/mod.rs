@@ -4,9 +4,13 @@ use reqwest::Method;
 #[cfg(test)] use body::MultipartPart;
 
 use body::Body;
+use metadata::RequestMetadata;
+use crate::postprocessing::assertion::Assertion;
 use crate::postprocessing::response_handler::ResponseHandler;
 
 pub mod body;
+pub mod metadata;
+pub mod status_set;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Request {
@@ -15,6 +19,8 @@ pub struct Request {
     pub headers: HeaderMap,
     pub body: Body,
     pub response_handler: Option<ResponseHandler>,
+    pub assertions: Vec<Assertion>,
+    pub metadata: RequestMetadata,
 }
 
 #[cfg(test)]
@@ -31,6 +37,8 @@ impl Request {
             headers: HeaderMap::new(),
             body: Body::Plain(String::new()),
             response_handler: None,
+            assertions: vec![],
+            metadata: RequestMetadata::default(),
         }
     }
 
@@ -97,6 +105,24 @@ impl Request {
         self
     }
 
+    pub fn metadata(
+        mut self,
+        metadata: RequestMetadata,
+    ) -> Self {
+        self.metadata = metadata;
+
+        self
+    }
+
+    pub fn assertions(
+        mut self,
+        assertions: &'static str,
+    ) -> Self {
+        self.assertions = Assertion::parse_all(assertions).unwrap();
+
+        self
+    }
+
     pub fn response_handler_rhai(
         mut self,
         handler: &'static str,
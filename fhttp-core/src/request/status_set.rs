@@ -0,0 +1,130 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::StatusCode;
+
+const MIN: u16 = 100;
+const MAX: u16 = 599;
+
+/// A set of http status codes, e.g. the statuses a request should be retried on.
+/// Stored as a bitset so it's `Copy` and can live in `Config`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StatusSet([u64; 8]);
+
+impl StatusSet {
+    pub fn insert(&mut self, status: u16) {
+        if (MIN..=MAX).contains(&status) {
+            let index = (status - MIN) as usize;
+            self.0[index / 64] |= 1 << (index % 64);
+        }
+    }
+
+    pub fn contains(&self, status: StatusCode) -> bool {
+        let status = status.as_u16();
+        if !(MIN..=MAX).contains(&status) {
+            return false;
+        }
+
+        let index = (status - MIN) as usize;
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u16> + '_ {
+        (MIN..=MAX).filter(|status| self.contains(StatusCode::from_u16(*status).unwrap()))
+    }
+}
+
+/// Rejects codes outside of 100-599, like `FromStr`.
+impl TryFrom<&[u16]> for StatusSet {
+    type Error = anyhow::Error;
+
+    fn try_from(statuses: &[u16]) -> Result<Self> {
+        let mut ret = StatusSet::default();
+        for status in statuses {
+            ret.insert(check_status(*status)?);
+        }
+
+        Ok(ret)
+    }
+}
+
+/// Parses comma separated status codes and ranges, e.g. `429,500-504`.
+impl FromStr for StatusSet {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut ret = StatusSet::default();
+
+        for part in s.split(',').map(str::trim).filter(|it| !it.is_empty()) {
+            let (from, to) = match part.split_once('-') {
+                Some((from, to)) => (parse_status(from)?, parse_status(to)?),
+                None => (parse_status(part)?, parse_status(part)?),
+            };
+            if from > to {
+                return Err(anyhow!("invalid status range '{}'", part));
+            }
+            (from..=to).for_each(|status| ret.insert(status));
+        }
+
+        Ok(ret)
+    }
+}
+
+fn parse_status(s: &str) -> Result<u16> {
+    let status = s
+        .trim()
+        .parse::<u16>()
+        .with_context(|| format!("invalid status code '{}'", s.trim()))?;
+    check_status(status)
+}
+
+fn check_status(status: u16) -> Result<u16> {
+    match (MIN..=MAX).contains(&status) {
+        true => Ok(status),
+        false => Err(anyhow!("invalid status code '{}'", status)),
+    }
+}
+
+impl fmt::Debug for StatusSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::errmsg;
+
+    #[test]
+    fn should_parse_codes_and_ranges() -> Result<()> {
+        let set = StatusSet::from_str("429, 502-504")?;
+
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![429, 502, 503, 504]);
+        assert!(set.contains(StatusCode::BAD_GATEWAY));
+        assert!(!set.contains(StatusCode::INTERNAL_SERVER_ERROR));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_statuses() {
+        assert_eq!(
+            errmsg(StatusSet::from_str("200,abc")),
+            "invalid status code 'abc'"
+        );
+        assert_eq!(
+            errmsg(StatusSet::from_str("700")),
+            "invalid status code '700'"
+        );
+        assert_eq!(
+            errmsg(StatusSet::from_str("504-500")),
+            "invalid status range '504-500'"
+        );
+        assert_eq!(
+            errmsg(StatusSet::try_from([200, 99].as_slice())),
+            "invalid status code '99'"
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use glob::{MatchOptions, Pattern};
+
+use crate::profiles::DEFAULT_PROFILE_FILES;
+use crate::RequestSource;
+
+const REQUEST_EXTENSIONS: [&str; 4] = [".http", ".yaml", ".yml", ".json"];
+
+/// Selects which requests to run. Path patterns apply to files found in directories or
+/// through glob patterns, tags apply to all requests.
+#[derive(Debug, Default, Clone)]
+pub struct RequestFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    tags: Vec<String>,
+    exclude_tags: Vec<String>,
+}
+
+impl RequestFilter {
+    pub fn new<S: AsRef<str>>(
+        include: &[S],
+        exclude: &[S],
+        tags: &[S],
+        exclude_tags: &[S],
+    ) -> Result<Self> {
+        let patterns = |patterns: &[S]| {
+            patterns
+                .iter()
+                .map(|it| {
+                    Pattern::new(it.as_ref())
+                        .with_context(|| format!("invalid pattern '{}'", it.as_ref()))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        let strings = |strings: &[S]| {
+            strings
+                .iter()
+                .map(|it| it.as_ref().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        Ok(RequestFilter {
+            include: patterns(include)?,
+            exclude: patterns(exclude)?,
+            tags: strings(tags),
+            exclude_tags: strings(exclude_tags),
+        })
+    }
+
+    /// Whether a discovered file should be run. Patterns are matched against the path
+    /// relative to the searched directory as well as against the file name.
+    pub fn matches_path(&self, relative_path: &Path) -> bool {
+        let matches = |pattern: &Pattern| {
+            pattern.matches_path(relative_path)
+                || relative_path
+                    .file_name()
+                    .is_some_and(|name| pattern.matches_path(Path::new(name)))
+        };
+
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+
+    /// Whether a request should be run, given its tags.
+    pub fn matches_tags(&self, tags: &[String]) -> bool {
+        (self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag)))
+            && !self.exclude_tags.iter().any(|tag| tags.contains(tag))
+    }
+
+    pub fn filter_requests(&self, requests: Vec<RequestSource>) -> Vec<RequestSource> {
+        requests
+            .into_iter()
+            .filter(|req| self.matches_tags(&req.tags()))
+            .collect()
+    }
+}
+
+pub fn is_glob_pattern(arg: &str) -> bool {
+    arg.contains(['*', '?', '['])
+}
+
+/// Finds all request files in the given directory and its subdirectories, skipping hidden
+/// files and directories and the default profile file.
+pub fn discover_in_directory(dir: &Path, filter: &RequestFilter) -> Result<Vec<PathBuf>> {
+    let mut ret = vec![];
+    walk(dir, &mut ret)?;
+
+    let mut ret = ret
+        .into_iter()
+        .filter(|path| filter.matches_path(path.strip_prefix(dir).unwrap_or(path)))
+        .collect::<Vec<_>>();
+    ret.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+
+    Ok(ret)
+}
+
+/// Finds all request files matching the glob pattern, directories among the matches are
+/// searched for request files. Like in shells, wildcards don't match hidden files.
+pub fn discover_by_pattern(pattern: &str, filter: &RequestFilter) -> Result<Vec<PathBuf>> {
+    let options = MatchOptions {
+        require_literal_leading_dot: true,
+        ..Default::default()
+    };
+    let paths = glob::glob_with(pattern, options)
+        .with_context(|| format!("invalid pattern '{}'", pattern))?;
+
+    let mut files = vec![];
+    for path in paths {
+        let path = path?;
+        if path.is_dir() {
+            files.extend(discover_in_directory(&path, filter)?);
+        } else if path.is_file() && is_request_path(&path) && filter.matches_path(&path) {
+            files.push(path);
+        }
+    }
+
+    match files.is_empty() {
+        true => Err(anyhow!("'{}' matches no request files", pattern)),
+        false => {
+            files.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+            Ok(files)
+        }
+    }
+}
+
+fn walk(dir: &Path, found: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("error reading directory {}", dir.to_string_lossy()))?;
+
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = entry.path();
+        if name.starts_with('.') {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            walk(&path, found)?;
+        } else if path.is_file() && is_request_file(&name) {
+            found.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn is_request_path(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| is_request_file(&name.to_string_lossy()))
+}
+
+fn is_request_file(name: &str) -> bool {
+    let name = name.to_lowercase();
+    !DEFAULT_PROFILE_FILES.contains(&name.as_str())
+        && REQUEST_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+}
+
+/// Compares paths so that numbers in them are ordered by value, e.g. `2-login.http`
+/// comes before `10-logout.http`.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (x, rest_a) = split_number(a);
+                let (y, rest_b) = split_number(b);
+                let x = x.trim_start_matches('0');
+                let y = y.trim_start_matches('0');
+                match x.len().cmp(&y.len()).then_with(|| x.cmp(y)) {
+                    Ordering::Equal => (a, b) = (rest_a, rest_b),
+                    ordering => return ordering,
+                }
+            }
+            (Some(x), Some(y)) => match x.cmp(&y) {
+                Ordering::Equal => (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]),
+                ordering => return ordering,
+            },
+        }
+    }
+}
+
+fn split_number(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use temp_dir::TempDir;
+
+    use super::*;
+    use crate::test_utils::{errmsg, write_test_file};
+
+    fn names(paths: Vec<PathBuf>, root: &Path) -> Vec<String> {
+        paths
+            .iter()
+            .map(|it| {
+                it.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
+
+    fn workdir() -> Result<TempDir> {
+        let workdir = TempDir::new()?;
+        for name in [
+            "10-logout.http",
+            "2-login.http",
+            "fhttp-config.json",
+            "fhttp-config.yaml",
+            "users/create.yaml",
+            "users/get.gql.http",
+            "users/body.txt",
+            ".hidden/secret.http",
+            "deps/token.json",
+        ] {
+            fs::create_dir_all(workdir.path().join(name).parent().unwrap())?;
+            write_test_file(&workdir, name, "GET http://localhost")?;
+        }
+
+        Ok(workdir)
+    }
+
+    #[test]
+    fn should_discover_request_files_in_natural_order() -> Result<()> {
+        let workdir = workdir()?;
+        let root = workdir.path();
+
+        assert_eq!(
+            names(
+                discover_in_directory(root, &RequestFilter::default())?,
+                root
+            ),
+            vec![
+                "2-login.http",
+                "10-logout.http",
+                "deps/token.json",
+                "users/create.yaml",
+                "users/get.gql.http",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_apply_include_and_exclude_patterns() -> Result<()> {
+        let workdir = workdir()?;
+        let root = workdir.path();
+
+        let filter =
+            RequestFilter::new(&["*.http", "users/*"], &["deps/**", "*.gql.http"], &[], &[])?;
+        assert_eq!(
+            names(discover_in_directory(root, &filter)?, root),
+            vec!["2-login.http", "10-logout.http", "users/create.yaml"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_discover_files_by_pattern() -> Result<()> {
+        let workdir = workdir()?;
+        let root = workdir.path();
+        let pattern = format!("{}/*", root.to_string_lossy());
+
+        assert_eq!(
+            names(
+                discover_by_pattern(&pattern, &RequestFilter::default())?,
+                root
+            ),
+            vec![
+                "2-login.http",
+                "10-logout.http",
+                "deps/token.json",
+                "users/create.yaml",
+                "users/get.gql.http",
+            ]
+        );
+        assert_eq!(
+            errmsg(discover_by_pattern(
+                "/nonexistent/*.http",
+                &RequestFilter::default()
+            )),
+            "'/nonexistent/*.http' matches no request files"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_filter_by_tags() -> Result<()> {
+        let filter = RequestFilter::new(&[], &[], &["smoke", "users"], &["slow"])?;
+        let tags = |tags: &[&str]| tags.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+
+        assert!(filter.matches_tags(&tags(&["smoke"])));
+        assert!(filter.matches_tags(&tags(&["users", "admin"])));
+        assert!(!filter.matches_tags(&tags(&["admin"])));
+        assert!(!filter.matches_tags(&tags(&["smoke", "slow"])));
+        assert!(!filter.matches_tags(&[]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_filter_requests_by_their_tags() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let api = write_test_file(
+            &workdir,
+            "api.http",
+            indoc!(
+                r##"
+                ### login
+                # @tags smoke, auth
+                POST http://localhost/login
+
+                ### delete-users
+                # @tags slow
+                DELETE http://localhost/users
+
+                ### health
+                GET http://localhost/health
+            "##
+            ),
+        )?;
+        let users = write_test_file(
+            &workdir,
+            "users.yaml",
+            "method: GET\nurl: http://localhost/users\ntags: [smoke]\n",
+        )?;
+
+        let mut requests = RequestSource::all_from_file(&api, false)?;
+        requests.extend(RequestSource::all_from_file(&users, false)?);
+        let ids = |requests: Vec<RequestSource>| {
+            requests
+                .iter()
+                .map(|it| it.id().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let filter = RequestFilter::new(&[], &[], &["smoke"], &[])?;
+        assert_eq!(
+            ids(filter.filter_requests(requests)),
+            vec![
+                format!("{}#login", api.to_str()),
+                users.to_str().to_string()
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 
@@ -6,6 +7,33 @@ pub fn load_file_recursively<P: AsRef<Path>>(path: P) -> Result<String> {
     recursive_file_loader::load_file_recursively(path).map_err(anyhow::Error::new)
 }
 
+/// All files pulled in by `${include(...)}` and `${include_indent(...)}` expressions in the
+/// given file, directly or through other included files. Files that can't be read are
+/// listed but not searched.
+pub fn included_files<P: AsRef<Path>>(path: P) -> Vec<PathBuf> {
+    let mut ret = vec![];
+    let mut pending = vec![path.as_ref().to_path_buf()];
+
+    while let Some(file) = pending.pop() {
+        let Ok(text) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for captures in regex!(r#"\$\{include(?:_indent)?\("([^"]*)"\)}"#).captures_iter(&text) {
+            let include = Path::new(captures.get(1).unwrap().as_str());
+            let include = match include.is_absolute() {
+                true => include.to_path_buf(),
+                false => file.parent().unwrap().join(include),
+            };
+            if include != path.as_ref() && !ret.contains(&include) {
+                ret.push(include.clone());
+                pending.push(include);
+            }
+        }
+    }
+
+    ret
+}
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
@@ -33,6 +61,21 @@ mod test {
         assert_ok!(result, expectation);
     }
 
+    #[test]
+    fn should_list_included_files() {
+        let dir = root().join("resources/nested_file_includes/normal");
+        let result = included_files(dir.join("start.txt"));
+
+        assert_eq!(
+            result,
+            vec![
+                dir.join("level-1.txt").path_buf(),
+                dir.join("level-3.txt").path_buf(),
+                dir.join("level-2.txt").path_buf(),
+            ]
+        );
+    }
+
     #[test]
     fn should_detect_cyclic_dependencies() {
         let one = root().join("resources/nested_file_includes/cyclic_dependency/level-1.txt");
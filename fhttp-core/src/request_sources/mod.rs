@@ -4,21 +4,25 @@ use std::path::Path;
 #[cfg(test)]
 use std::path::PathBuf;
 
-use crate::parsers::{parse_gql_str, parse_str};
-use crate::path_utils::{canonicalize, CanonicalizedPathBuf, RelativePath};
+use crate::parsers::{parse_gql_str, parse_str, split_requests, RequestBlock};
+use crate::path_utils::{canonicalize, CanonicalizedPathBuf};
+use crate::request::metadata::parse_tags;
 use crate::preprocessing::dependant::{request_dependencies, Dependant};
 use crate::request_sources::request_dependency_eval::RequestDependencyEval;
+use crate::request_sources::request_id::RequestId;
 use crate::request_sources::request_wrapper::RequestWrapper;
 use crate::request_sources::structured_request_source::{
     parse_request_from_json, parse_request_from_yaml,
 };
-use crate::{Config, Profile, ResponseStore};
-use anyhow::Result;
+use crate::{Client, Config, Profile, ResponseStore};
+use anyhow::{anyhow, Context, Result};
 use file_includes::load_file_recursively;
 use variable_support::replace_evals;
 
+pub mod discovery;
 pub mod file_includes;
 pub mod request_dependency_eval;
+pub mod request_id;
 pub mod request_wrapper;
 pub mod structured_request_source;
 pub mod variable_support;
@@ -32,32 +36,78 @@ pub struct Preprocessed;
 pub struct RequestSource<State = Raw> {
     state: PhantomData<State>,
     pub source_path: CanonicalizedPathBuf,
+    /// set for requests from files containing several requests
+    pub name: Option<String>,
     pub text: String,
     pub dependency: bool,
 }
 
 impl<State> RequestSource<State> {
+    /// Loads a file containing a single request.
     pub fn from_file<P: AsRef<Path>>(path: P, dependency: bool) -> Result<Self> {
         let path = canonicalize(path.as_ref())?;
-        let content = load_file_recursively(&path)?;
+        Self::load(&RequestId::new(path, None), dependency)
+    }
+
+    /// Loads the identified request. Requests from files containing several requests
+    /// must be identified by their name.
+    pub fn load(id: &RequestId, dependency: bool) -> Result<Self> {
+        let (content, blocks) = read_request_file(&id.path)?;
+
+        match (blocks, &id.name) {
+            (None, None) => Self::_new(id.path.clone(), None, content, dependency),
+            (None, Some(name)) => Err(anyhow!(
+                "'{}' contains a single request, refer to it without '#{}'",
+                id.path,
+                name
+            )),
+            (Some(_), None) => Err(anyhow!(
+                "'{}' contains several requests, refer to one of them as '{}#name'",
+                id.path,
+                id.path
+            )),
+            (Some(blocks), Some(name)) => {
+                let block = blocks
+                    .into_iter()
+                    .find(|block| &block.name == name)
+                    .ok_or_else(|| {
+                        anyhow!("'{}' contains no request named '{}'", id.path, name)
+                    })?;
+                Self::_new(id.path.clone(), Some(block.name), block.text, dependency)
+            }
+        }
+    }
 
-        Self::_new(path, content, dependency)
+    /// Loads all requests of a file.
+    pub fn all_from_file<P: AsRef<Path>>(path: P, dependency: bool) -> Result<Vec<Self>> {
+        let path = canonicalize(path.as_ref())?;
+        let (content, blocks) = read_request_file(&path)?;
+
+        match blocks {
+            None => Ok(vec![Self::_new(path, None, content, dependency)?]),
+            Some(blocks) => blocks
+                .into_iter()
+                .map(|block| Self::_new(path.clone(), Some(block.name), block.text, dependency))
+                .collect(),
+        }
     }
 
     #[cfg(test)]
     pub fn new<P: Into<PathBuf>, T: Into<String>>(path: P, text: T) -> Result<Self> {
         let path = canonicalize(&path.into())?;
-        RequestSource::_new(path, text, false)
+        RequestSource::_new(path, None, text, false)
     }
 
     fn _new<S: Into<String>>(
         path: CanonicalizedPathBuf,
+        name: Option<String>,
         text: S,
         dependency: bool,
     ) -> Result<Self> {
         let ret = RequestSource {
             state: PhantomData,
             source_path: path,
+            name,
             text: text.into(),
             dependency,
         };
@@ -65,12 +115,52 @@ impl<State> RequestSource<State> {
         Ok(ret)
     }
 
-    pub fn unescaped_dependency_paths(&self) -> Result<Vec<CanonicalizedPathBuf>> {
+    pub fn id(&self) -> RequestId {
+        RequestId::new(self.source_path.clone(), self.name.clone())
+    }
+
+    pub fn unescaped_dependency_ids(&self) -> Result<Vec<RequestId>> {
         self.unescaped_dependencies()?
             .into_iter()
-            .map(|dep| self.get_dependency_path(dep.path))
+            .map(|dep| RequestId::resolve(&self.source_path, dep.path))
             .collect()
     }
+
+    /// The tags of the request, read from the unprocessed text so that requests can be
+    /// filtered before their variables are evaluated.
+    pub fn tags(&self) -> Vec<String> {
+        match is_structured(&self.source_path) {
+            true => serde_yaml::from_str::<serde_yaml::Value>(&self.text)
+                .ok()
+                .and_then(|value| serde_yaml::from_value(value.get("tags")?.clone()).ok())
+                .unwrap_or_default(),
+            false => regex!(r"(?m)^#\s*@tags[ \t]+(.*\S)")
+                .captures(&self.text)
+                .map(|captures| parse_tags(captures.get(1).unwrap().as_str()))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Reads a request file, splitting it into its requests if it contains several. A file with
+/// a single request stays addressable without a name, even if it starts with a `###` title.
+fn read_request_file(
+    path: &CanonicalizedPathBuf,
+) -> Result<(String, Option<Vec<RequestBlock>>)> {
+    let content = load_file_recursively(path)?;
+    let blocks = match is_structured(path) {
+        true => None,
+        false => split_requests(&content)
+            .with_context(|| format!("failed to parse file {}", path.to_str()))?
+            .filter(|blocks| blocks.len() > 1),
+    };
+
+    Ok((content, blocks))
+}
+
+fn is_structured(path: &CanonicalizedPathBuf) -> bool {
+    let path = path.to_str().to_lowercase();
+    path.ends_with(".json") || path.ends_with(".yaml") || path.ends_with(".yml")
 }
 
 impl RequestSource<Raw> {
@@ -78,6 +168,7 @@ impl RequestSource<Raw> {
         self,
         profile: &Profile,
         config: &Config,
+        client: &Client,
         response_store: &ResponseStore,
     ) -> Result<RequestSource<Preprocessed>> {
         let new_text = replace_evals(
@@ -86,6 +177,7 @@ impl RequestSource<Raw> {
             self.dependency,
             profile,
             config,
+            client,
             response_store,
         )?;
 
@@ -93,6 +185,7 @@ impl RequestSource<Raw> {
             text: new_text,
             state: PhantomData,
             source_path: self.source_path,
+            name: self.name,
             dependency: self.dependency,
         })
     }
@@ -112,7 +205,7 @@ impl RequestSource<Preprocessed> {
         };
 
         Ok(RequestWrapper {
-            source_path: self.source_path,
+            id: RequestId::new(self.source_path, self.name),
             request,
         })
     }
@@ -132,12 +225,13 @@ impl<T> AsRef<Path> for RequestSource<T> {
 
 impl<T> PartialEq for RequestSource<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.source_path == other.source_path
+        self.source_path == other.source_path && self.name == other.name
     }
 }
 
 impl Hash for RequestSource {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.source_path.hash(state);
+        self.name.hash(state);
     }
 }
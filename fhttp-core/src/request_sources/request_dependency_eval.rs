@@ -3,13 +3,21 @@ use std::ops::Range;
 
 pub struct RequestDependencyEval<'a> {
     pub path: &'a str,
+    /// set for `request_header(...)` expressions
+    pub header: Option<&'a str>,
     pub base_eval: BaseEvaluation,
 }
 
 impl<'a> RequestDependencyEval<'a> {
-    pub fn new(path: &'a str, range: Range<usize>, backslashes: Range<usize>) -> Self {
+    pub fn new(
+        path: &'a str,
+        header: Option<&'a str>,
+        range: Range<usize>,
+        backslashes: Range<usize>,
+    ) -> Self {
         RequestDependencyEval {
             path,
+            header,
             base_eval: BaseEvaluation::new(range, backslashes),
         }
     }
@@ -0,0 +1,125 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::path_utils::{canonicalize, get_dependency_path, CanonicalizedPathBuf};
+
+/// Identifies a single request: the file it's defined in and, for files that
+/// contain several `###`-separated requests, its name within that file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId {
+    pub path: CanonicalizedPathBuf,
+    pub name: Option<String>,
+}
+
+impl RequestId {
+    pub fn new(path: CanonicalizedPathBuf, name: Option<String>) -> Self {
+        RequestId { path, name }
+    }
+
+    /// Resolves a reference like `token.http`, `api.http#login` or `#login` (a request in the
+    /// same file as `origin`). Relative paths are resolved against the location of `origin`.
+    pub fn resolve<O: AsRef<Path>>(origin: O, reference: &str) -> Result<Self> {
+        let origin = origin.as_ref();
+        let (path, name) = split_reference(origin, reference);
+        let path = match path.is_empty() {
+            true => canonicalize(origin)?,
+            false => get_dependency_path(origin, path)?,
+        };
+
+        Ok(RequestId::new(path, name.map(str::to_owned)))
+    }
+}
+
+/// Splits a request reference into its path and, if given, the request name after the `#`.
+/// References to existing files, resolved against `origin`, are never split, so paths may
+/// contain `#`.
+pub fn split_reference<O: AsRef<Path>>(origin: O, reference: &str) -> (&str, Option<&str>) {
+    if get_dependency_path(origin, reference).is_ok() {
+        return (reference, None);
+    }
+
+    match reference.rsplit_once('#') {
+        Some((path, name)) => (path, Some(name)),
+        None => (reference, None),
+    }
+}
+
+impl From<CanonicalizedPathBuf> for RequestId {
+    fn from(path: CanonicalizedPathBuf) -> Self {
+        RequestId::new(path, None)
+    }
+}
+
+impl Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name {
+            Some(ref name) => write!(f, "{}#{}", self.path, name),
+            None => self.path.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use temp_dir::TempDir;
+
+    use crate::test_utils::{root, write_test_file};
+
+    use super::*;
+
+    #[test]
+    fn should_resolve_references() -> Result<()> {
+        let origin = root().join("resources/it/requests/1.http");
+        let other = root().join("resources/it/requests/2.http");
+
+        assert_eq!(
+            RequestId::resolve(&origin, "2.http")?,
+            RequestId::new(other.clone(), None)
+        );
+        assert_eq!(
+            RequestId::resolve(&origin, "2.http#login")?,
+            RequestId::new(other, Some("login".to_string()))
+        );
+        assert_eq!(
+            RequestId::resolve(&origin, "#login")?,
+            RequestId::new(origin, Some("login".to_string()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_split_paths_of_existing_files_containing_a_hash() -> Result<()> {
+        let workdir = TempDir::new()?;
+        let origin = write_test_file(&workdir, "origin.http", "GET http://localhost")?;
+        let file = write_test_file(&workdir, "issue#42.http", "GET http://localhost")?;
+
+        assert_eq!(
+            split_reference(&origin, "issue#42.http"),
+            ("issue#42.http", None)
+        );
+        assert_eq!(
+            split_reference(&origin, "issue#42.http#login"),
+            ("issue#42.http", Some("login"))
+        );
+        assert_eq!(
+            RequestId::resolve(&origin, "issue#42.http")?,
+            RequestId::new(file, None)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_display_the_name_after_the_path() {
+        let path = root().join("resources/it/requests/2.http");
+
+        assert_eq!(
+            RequestId::new(path.clone(), Some("login".to_string())).to_string(),
+            format!("{}#login", path.to_str())
+        );
+        assert_eq!(RequestId::from(path.clone()).to_string(), path.to_str());
+    }
+}
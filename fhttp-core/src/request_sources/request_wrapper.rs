@@ -1,7 +1,7 @@
 use crate::parsers::Request;
-use crate::path_utils::CanonicalizedPathBuf;
+use crate::request_sources::request_id::RequestId;
 
 pub struct RequestWrapper {
-    pub source_path: CanonicalizedPathBuf,
+    pub id: RequestId,
     pub request: Request,
 }
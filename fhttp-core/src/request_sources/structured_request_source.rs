@@ -1,13 +1,16 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Method;
 use serde::Deserialize;
 
 use crate::path_utils::{CanonicalizedPathBuf, RelativePath};
+use crate::postprocessing::assertion::Assertion;
 use crate::request::body::{Body, MultipartPart};
+use crate::request::metadata::RequestMetadata;
+use crate::request::status_set::StatusSet;
 use crate::request::Request;
 use crate::ResponseHandler;
 
@@ -18,6 +21,16 @@ struct StructuredRequestSource {
     headers: Option<HashMap<String, String>>,
     response_handler: Option<StructuredResponseHandler>,
     body: Option<StructuredBody>,
+    name: Option<String>,
+    timeout: Option<u64>,
+    no_redirect: Option<bool>,
+    retries: Option<u32>,
+    retry_on: Option<Vec<u16>>,
+    retry_non_idempotent: Option<bool>,
+    expect_status: Option<Vec<u16>>,
+    #[serde(rename = "assert")]
+    assertions: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -122,6 +135,22 @@ impl TryFrom<(&CanonicalizedPathBuf, StructuredRequestSource)> for Request {
             None => Ok(HeaderMap::new()),
         }?;
 
+        let mut assertions = vec![];
+        for text in value.assertions.unwrap_or_default() {
+            assertions.extend(Assertion::parse_all(&text)?);
+        }
+
+        let retry_on = value
+            .retry_on
+            .map(|it| StatusSet::try_from(it.as_slice()))
+            .transpose()
+            .context("invalid value for 'retry_on'")?;
+        let expect_status = value
+            .expect_status
+            .map(|it| StatusSet::try_from(it.as_slice()))
+            .transpose()
+            .context("invalid value for 'expect_status'")?;
+
         Ok(Request {
             method: Method::from_str(&value.method)?,
             url: value.url.to_string(),
@@ -133,6 +162,17 @@ impl TryFrom<(&CanonicalizedPathBuf, StructuredRequestSource)> for Request {
             response_handler: value
                 .response_handler
                 .and_then(StructuredResponseHandler::response_handler),
+            assertions,
+            metadata: RequestMetadata {
+                name: value.name,
+                timeout_ms: value.timeout,
+                no_redirect: value.no_redirect,
+                retries: value.retries,
+                retry_on,
+                retry_non_idempotent: value.retry_non_idempotent,
+                expect_status,
+                tags: value.tags.unwrap_or_default(),
+            },
         })
     }
 }
@@ -183,7 +223,9 @@ mod tests {
                 url: "http://localhost/foo".to_string(),
                 headers: HeaderMap::new(),
                 body: Body::Plain("".to_string()),
-                response_handler: None
+                response_handler: None,
+                assertions: vec![],
+                metadata: RequestMetadata::default(),
             }
         );
 
@@ -221,7 +263,9 @@ mod tests {
                 url: "http://localhost/foo".to_string(),
                 headers,
                 body: Body::Plain("".to_string()),
-                response_handler: None
+                response_handler: None,
+                assertions: vec![],
+                metadata: RequestMetadata::default(),
             }
         );
 
@@ -253,6 +297,8 @@ mod tests {
                 response_handler: Some(ResponseHandler::Json {
                     json_path: "$.data".to_string()
                 }),
+                assertions: vec![],
+                metadata: RequestMetadata::default(),
             }
         );
 
@@ -284,6 +330,8 @@ mod tests {
                 response_handler: Some(ResponseHandler::Deno {
                     program: "setResult('ok!');".to_string()
                 }),
+                assertions: vec![],
+                metadata: RequestMetadata::default(),
             }
         );
 
@@ -310,7 +358,9 @@ mod tests {
                 url: "http://localhost/foo".to_string(),
                 headers: HeaderMap::new(),
                 body: Body::Plain("plain body".to_string()),
-                response_handler: None
+                response_handler: None,
+                assertions: vec![],
+                metadata: RequestMetadata::default(),
             }
         );
 
@@ -377,7 +427,9 @@ mod tests {
                         mime_str: Some("image/png".to_string()),
                     },
                 ]),
-                response_handler: None
+                response_handler: None,
+                assertions: vec![],
+                metadata: RequestMetadata::default(),
             }
         );
 
@@ -402,7 +454,9 @@ mod tests {
                 url: "http://localhost/foo".to_string(),
                 headers: HeaderMap::new(),
                 body: Body::Plain("hello there".to_string()),
-                response_handler: None
+                response_handler: None,
+                assertions: vec![],
+                metadata: RequestMetadata::default(),
             }
         );
 
@@ -469,7 +523,9 @@ mod tests {
                         mime_str: Some("image/png".to_string()),
                     },
                 ]),
-                response_handler: None
+                response_handler: None,
+                assertions: vec![],
+                metadata: RequestMetadata::default(),
             }
         );
 
@@ -495,10 +551,127 @@ mod tests {
                 url: "http://localhost/foo".to_string(),
                 headers: HeaderMap::new(),
                 body: Body::Plain("".to_string()),
-                response_handler: Some(ResponseHandler::Rhai { program: "program".to_string() })
+                response_handler: Some(ResponseHandler::Rhai { program: "program".to_string() }),
+                assertions: vec![],
+                metadata: RequestMetadata::default(),
             }
         );
 
         Ok(())
     }
+
+    #[test]
+    fn should_parse_request_with_retry_settings() -> Result<()> {
+        let result = parse_request_from_yaml(
+            &root(),
+            indoc! {r#"
+            method: POST
+            url: http://localhost/foo
+            retries: 3
+            retry_on: [502, 503]
+            retry_non_idempotent: true
+        "#},
+        )?;
+
+        assert_eq!(
+            result.metadata,
+            RequestMetadata {
+                retries: Some(3),
+                retry_on: Some(StatusSet::try_from([502, 503].as_slice())?),
+                retry_non_idempotent: Some(true),
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_request_with_expected_statuses() -> Result<()> {
+        let result = parse_request_from_json(
+            &root(),
+            indoc! {r#"
+            {
+                "method": "DELETE",
+                "url": "http://localhost/foo",
+                "expect_status": [204, 404]
+            }
+        "#},
+        )?;
+
+        assert_eq!(
+            result.metadata.expect_status,
+            Some(StatusSet::try_from([204, 404].as_slice())?)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_reject_invalid_statuses() {
+        let result = parse_request_from_json(
+            &root(),
+            indoc! {r#"
+            {
+                "method": "DELETE",
+                "url": "http://localhost/foo",
+                "expect_status": [204, 700]
+            }
+        "#},
+        );
+
+        assert_eq!(
+            format!("{:#}", result.unwrap_err()),
+            "invalid value for 'expect_status': invalid status code '700'"
+        );
+    }
+
+    #[test]
+    fn should_parse_request_with_metadata() -> Result<()> {
+        let result = parse_request_from_yaml(
+            &root(),
+            indoc! {r#"
+            name: create user
+            method: POST
+            url: http://localhost/foo
+            timeout: 5000
+            no_redirect: true
+            tags: [smoke, users]
+        "#},
+        )?;
+
+        assert_eq!(
+            result.metadata,
+            RequestMetadata {
+                name: Some("create user".to_string()),
+                timeout_ms: Some(5000),
+                no_redirect: Some(true),
+                tags: vec!["smoke".to_string(), "users".to_string()],
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn should_parse_request_with_assertions() -> Result<()> {
+        let result = parse_request_from_yaml(
+            &root(),
+            indoc! {r#"
+            method: GET
+            url: http://localhost/foo
+            assert:
+              - status == 200
+              - jsonpath $.items.length > 0; header etag exists
+        "#},
+        )?;
+
+        assert_eq!(
+            result.assertions,
+            Assertion::parse_all("status == 200\njsonpath $.items.length > 0\nheader etag exists")?
+        );
+
+        Ok(())
+    }
 }
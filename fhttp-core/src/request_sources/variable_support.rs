@@ -1,14 +1,14 @@
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use regex::Captures;
 use uuid::Uuid;
 
-use crate::path_utils::get_dependency_path;
 use crate::preprocessing::dependant::request_dependencies;
 use crate::preprocessing::evaluation::{BaseEvaluation, Evaluation};
 use crate::preprocessing::random_numbers::{parse_min_max, random_int, RandomNumberEval};
-use crate::{Config, Profile, ResponseStore};
+use crate::request_sources::request_id::RequestId;
+use crate::{Client, Config, Profile, ResponseStore};
 
 #[derive(Debug)]
 pub struct EnvVarOccurrence<'a> {
@@ -29,9 +29,10 @@ pub fn replace_evals(
     dependency: bool,
     profile: &Profile,
     config: &Config,
+    client: &Client,
     response_store: &ResponseStore,
 ) -> Result<String> {
-    let text = replace_env_vars(text, dependency, profile, config, response_store)?;
+    let text = replace_env_vars(text, dependency, profile, config, client, response_store)?;
     let text = replace_uuids(text);
     let text = replace_random_ints(text)?;
     let text = replace_request_dependencies(text, base_path, response_store)?;
@@ -68,6 +69,7 @@ fn replace_env_vars(
     dependency: bool,
     profile: &Profile,
     config: &Config,
+    client: &Client,
     response_store: &ResponseStore,
 ) -> Result<String> {
     let variables = get_env_vars(&text);
@@ -81,6 +83,7 @@ fn replace_env_vars(
                 profile.get(
                     occurrence.name,
                     config,
+                    client,
                     response_store,
                     occurrence.default,
                     dependency,
@@ -168,7 +171,13 @@ fn replace_request_dependencies(
 
         for eval in reversed_evals {
             eval.replace(&mut buffer, || {
-                Ok(response_store.get(&get_dependency_path(&base_path, eval.path)?))
+                let id = RequestId::resolve(&base_path, eval.path)?;
+                match eval.header {
+                    None => Ok(response_store.get(&id)),
+                    Some(name) => response_store
+                        .get_header(&id, name)
+                        .ok_or_else(|| anyhow!("response of '{}' has no header '{}'", id, name)),
+                }
             })?;
         }
 
@@ -183,7 +192,7 @@ mod replace_variables {
     use indoc::indoc;
 
     use crate::preprocessing::random_numbers::RANDOM_INT_CALLS;
-    use crate::test_utils::root;
+    use crate::test_utils::{client, root};
     use crate::RequestSource;
 
     use super::*;
@@ -209,6 +218,7 @@ mod replace_variables {
         let req = req.replace_variables(
             &Profile::empty(env::current_dir().unwrap()),
             &Config::default(),
+            &client(),
             &ResponseStore::new(),
         )?;
 
@@ -248,6 +258,7 @@ mod replace_variables {
         let req = req.replace_variables(
             &Profile::empty(env::current_dir().unwrap()),
             &Config::default(),
+            &client(),
             &ResponseStore::new(),
         )?;
 
@@ -286,6 +297,7 @@ mod replace_variables {
         let req = req.replace_variables(
             &Profile::empty(env::current_dir().unwrap()),
             &Config::default(),
+            &client(),
             &ResponseStore::new(),
         )?;
 
@@ -319,6 +331,7 @@ mod replace_variables {
         let req = req.replace_variables(
             &Profile::empty(env::current_dir().unwrap()),
             &Config::default(),
+            &client(),
             &ResponseStore::new(),
         )?;
 
@@ -358,6 +371,7 @@ mod replace_variables {
         let req = req.replace_variables(
             &Profile::empty(env::current_dir().unwrap()),
             &Config::default(),
+            &client(),
             &ResponseStore::new(),
         )?;
 
@@ -388,6 +402,7 @@ mod replace_variables {
         req.replace_variables(
             &Profile::empty(env::current_dir().unwrap()),
             &Config::default(),
+            &client(),
             &ResponseStore::new(),
         )?;
 
@@ -412,7 +427,7 @@ mod replace_variables {
                 env::current_dir().unwrap(),
                 format!("GET ${{randomInt({})}}", i32::MIN as i64 - 1)
             )?
-            .replace_variables(&profile, &config, &response_store),
+            .replace_variables(&profile, &config, &client(), &response_store),
             format!("min param out of bounds: {}..{}", i32::MIN, i32::MAX)
         );
 
@@ -421,13 +436,13 @@ mod replace_variables {
                 env::current_dir().unwrap(),
                 format!("${{randomInt(0, {})}}", i32::MAX as i64 + 1)
             )?
-            .replace_variables(&profile, &config, &response_store),
+            .replace_variables(&profile, &config, &client(), &response_store),
             format!("max param out of bounds: {}..{}", i32::MIN, i32::MAX)
         );
 
         assert_err!(
             RequestSource::new(env::current_dir().unwrap(), "${randomInt(3, 2)}")?
-                .replace_variables(&profile, &config, &response_store),
+                .replace_variables(&profile, &config, &client(), &response_store),
             "min cannot be greater than max"
         );
 
@@ -454,6 +469,7 @@ mod replace_variables {
         let req = req.replace_variables(
             &Profile::empty(env::current_dir().unwrap()),
             &Config::default(),
+            &client(),
             &ResponseStore::new(),
         )?;
 
@@ -499,7 +515,7 @@ mod replace_variables {
             "#
             ),
         )?;
-        let req = req.replace_variables(&profile, &config, &response_store)?;
+        let req = req.replace_variables(&profile, &config, &client(), &response_store)?;
 
         assert_eq!(
             req.text,
@@ -517,4 +533,55 @@ mod replace_variables {
 
         Ok(())
     }
+
+    #[test]
+    fn should_replace_request_header_dependencies() -> Result<()> {
+        use reqwest::header::HeaderMap;
+
+        let path = root().join("resources/test/requests/dummy.http");
+        let profile = Profile::empty(env::current_dir().unwrap());
+        let config = Config::default();
+        let response_store = {
+            let mut headers = HeaderMap::new();
+            headers.insert("location", "/resources/123".parse().unwrap());
+            let mut tmp = ResponseStore::new();
+            tmp.store(path.clone(), "FOO");
+            tmp.store_headers(path.clone(), headers);
+            tmp
+        };
+
+        let req = RequestSource::new(
+            env::current_dir().unwrap(),
+            indoc!(
+                r#"
+                GET server${request_header("../resources/test/requests/dummy.http", "Location")}
+
+                \${request_header("../resources/test/requests/dummy.http", "Location")}
+            "#
+            ),
+        )?;
+        let req = req.replace_variables(&profile, &config, &client(), &response_store)?;
+
+        assert_eq!(
+            req.text,
+            indoc!(
+                r#"
+                GET server/resources/123
+
+                ${request_header("../resources/test/requests/dummy.http", "Location")}
+            "#
+            )
+        );
+
+        let req = RequestSource::new(
+            env::current_dir().unwrap(),
+            r#"GET ${request_header("../resources/test/requests/dummy.http", "etag")}"#,
+        )?;
+        assert_err!(
+            req.replace_variables(&profile, &config, &client(), &response_store),
+            format!("response of '{}' has no header 'etag'", path.to_str())
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,61 @@
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+/// Replaces secrets in output.
+pub const MASK: &str = "****";
+
+/// The values of all secrets resolved in this run, longest first.
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Remembers a value that came from a secret source, e.g. a password store, so it can be
+/// masked in output.
+pub fn register_secret(value: &str) {
+    if value.is_empty() {
+        return;
+    }
+
+    let mut secrets = SECRETS.write().unwrap();
+    if !secrets.iter().any(|it| it == value) {
+        secrets.push(value.to_owned());
+        // longer secrets first, in case one secret contains another
+        secrets.sort_by_key(|it| std::cmp::Reverse(it.len()));
+    }
+}
+
+/// Whether the value was registered as a secret.
+pub fn is_secret(value: &str) -> bool {
+    SECRETS.read().unwrap().iter().any(|it| it == value)
+}
+
+/// Masks all registered secrets in the text.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let secrets = SECRETS.read().unwrap();
+    let mut ret = Cow::Borrowed(text);
+    for secret in secrets.iter() {
+        if ret.contains(secret.as_str()) {
+            ret = Cow::Owned(ret.replace(secret.as_str(), MASK));
+        }
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_mask_registered_secrets() {
+        register_secret("redact-test-token");
+        register_secret("redact-test-token-with-suffix");
+        register_secret("");
+
+        assert_eq!(
+            redact("authorization: redact-test-token-with-suffix, redact-test-token"),
+            "authorization: ****, ****"
+        );
+        assert!(matches!(redact("public information"), Cow::Borrowed(_)));
+        assert!(is_secret("redact-test-token"));
+        assert!(!is_secret("public information"));
+    }
+}
@@ -0,0 +1,44 @@
+/// Quotes text for posix shells in single quotes, so nothing in it is evaluated. Text made of
+/// characters without a special meaning stays as it is, e.g. `echo` or `--silent`.
+pub fn single_quote(text: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_-+=/.,:@%".contains(c);
+    match !text.is_empty() && text.chars().all(safe) {
+        true => text.to_owned(),
+        false => format!("'{}'", text.replace('\'', r"'\''")),
+    }
+}
+
+/// Quotes text for posix shells in double quotes, leaving `$` alone so that evaluations like
+/// `$(pass path)` and variables like `${TOKEN}` still work.
+pub fn dollar_preserving_quote(text: &str) -> String {
+    let mut ret = String::from('"');
+    for c in text.chars() {
+        if matches!(c, '"' | '\\' | '`') {
+            ret.push('\\');
+        }
+        ret.push(c);
+    }
+    ret.push('"');
+
+    ret
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_single_quote_unless_safe() {
+        assert_eq!(single_quote("--silent"), "--silent");
+        assert_eq!(single_quote(""), "''");
+        assert_eq!(single_quote("it's $HOME"), r"'it'\''s $HOME'");
+    }
+
+    #[test]
+    fn should_double_quote_but_keep_evaluations() {
+        assert_eq!(
+            dollar_preserving_quote(r#"say "hi" \ `now` $(pass token)"#),
+            r#""say \"hi\" \\ \`now\` $(pass token)""#
+        );
+    }
+}
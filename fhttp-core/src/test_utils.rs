@@ -52,3 +52,9 @@ pub fn write_test_file<S: AsRef<str>>(
     std::fs::write(&file, content.as_ref().as_bytes())?;
     canonicalize(&file)
 }
+
+#[cfg(test)]
+pub fn client() -> crate::Client {
+    crate::Client::new(&crate::Config::default(), std::sync::Arc::new(crate::CookieJar::new()))
+        .unwrap()
+}
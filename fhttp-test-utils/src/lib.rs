@@ -1,3 +1,3 @@
 mod write_test_file;
 
-pub use write_test_file::write_test_file;
+pub use write_test_file::{write_test_file, write_test_files};
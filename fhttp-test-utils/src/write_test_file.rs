@@ -11,3 +11,17 @@ pub fn write_test_file<S: AsRef<str>>(
     std::fs::write(&file, content.as_ref().as_bytes())?;
     canonicalize(&file)
 }
+
+/// Creates a temporary directory containing the given files, by their relative paths, e.g. a
+/// profile file and the requests using it. Missing parent directories are created.
+pub fn write_test_files(files: &[(&str, &str)]) -> Result<TempDir> {
+    let workdir = TempDir::new()?;
+    for (filename, content) in files {
+        if let Some(parent) = workdir.child(filename).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_test_file(&workdir, *filename, *content)?;
+    }
+
+    Ok(workdir)
+}
@@ -1,19 +1,36 @@
-use clap::{ArgAction, Parser};
+use clap::{ArgAction, Parser, Subcommand};
+use fhttp_core::execution::dry_run::DryRunFormat;
+use fhttp_core::execution::export::{find_exporter, Exporter};
+use fhttp_core::request::status_set::StatusSet;
 use fhttp_core::Config;
 
 #[derive(Parser, Debug, Clone, Default)]
-#[command(author, version, about)]
+#[command(author, version, about, subcommand_negates_reqs = true)]
 pub struct Args {
-    #[arg(required = true, help = "the request files to execute")]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[arg(
+        required = true,
+        help = "the request files, directories or glob patterns to execute"
+    )]
     pub files: Vec<String>,
 
     #[arg(
+        long,
+        help = "execute the requests again whenever their files or the profile file change"
+    )]
+    pub watch: bool,
+
+    #[arg(
+        global = true,
         long,
         help = "fail the program instead of prompting for missing environment variables"
     )]
     pub no_prompt: bool,
 
     #[arg(
+        global = true,
         short,
         long,
         env = "FHTTP_PROFILE",
@@ -22,38 +39,298 @@ pub struct Args {
     pub profile: Option<String>,
 
     #[arg(
+        global = true,
         short = 'f',
         long,
         env = "FHTTP_PROFILE_FILE",
-        help = "profile file to use. defaults to fhttp-config.json. can be set by env var FHTTP_PROFILE_FILE"
+        help = "profile file to use, in JSON, YAML or TOML format. defaults to fhttp-config.json, .yaml, .yml or .toml. can be set by env var FHTTP_PROFILE_FILE"
     )]
     pub profile_file: Option<String>,
 
-    #[arg(short, long, action = ArgAction::Count, help = "sets the level of verbosity")]
+    #[arg(
+        global = true,
+        long,
+        value_name = "FILE",
+        help = "read environment variables from this file. defaults to the .env file next to the profile file"
+    )]
+    pub env_file: Option<String>,
+
+    #[arg(
+        global = true,
+        long = "var",
+        value_name = "NAME=VALUE",
+        value_parser = parse_variable,
+        help = "set a variable, overriding profile variables and environment variables"
+    )]
+    pub vars: Vec<(String, String)>,
+
+    #[arg(
+        global = true,
+        long = "var-file",
+        value_name = "FILE",
+        help = "read variables from a json file or a file of NAME=VALUE lines. --var takes precedence"
+    )]
+    pub var_files: Vec<String>,
+
+    #[arg(global = true, short, long, action = ArgAction::Count, help = "sets the level of verbosity")]
     pub verbose: u8,
 
-    #[arg(short, long, help = "suppress log outputs")]
+    #[arg(global = true, short, long, help = "suppress log outputs")]
     pub quiet: bool,
 
     #[arg(
+        global = true,
         short = 'P',
         long,
         help = "print request file paths instead of method and url"
     )]
     pub print_paths: bool,
 
-    #[arg(short, long, help = "time out after this many ms on each request")]
+    #[arg(
+        global = true,
+        short,
+        long,
+        help = "time out after this many ms on each request"
+    )]
     pub timeout_ms: Option<u64>,
 
     #[arg(
+        global = true,
         short,
         long,
         help = "print curl commands instead of executing given requests. Dependencies are still executed"
     )]
     pub curl: bool,
 
-    #[arg(short, long, help = "redirect output to the specified file")]
+    #[arg(
+        global = true,
+        long,
+        value_name = "FORMAT",
+        value_parser = find_exporter,
+        conflicts_with = "curl",
+        help = "print the requests as snippets instead of executing them: curl, httpie, wget, python, fetch or reqwest. Dependencies are still executed. wget can't send multipart requests, they are printed as comments"
+    )]
+    pub export: Option<&'static dyn Exporter>,
+
+    #[arg(
+        global = true,
+        long,
+        requires = "curl",
+        help = "with --curl, print a bash script that also runs the dependencies with curl and passes their responses on in shell variables"
+    )]
+    pub script: bool,
+
+    #[arg(
+        global = true,
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "http",
+        conflicts_with = "curl",
+        help = "print the resolved requests instead of executing them, as .http requests or with --dry-run=json or --dry-run=yaml as json or yaml. Dependencies are replaced with placeholders"
+    )]
+    pub dry_run: Option<DryRunFormat>,
+
+    #[arg(
+        global = true,
+        long,
+        help = "don't mask the values of secrets in logs, curl commands, errors and reports"
+    )]
+    pub show_secrets: bool,
+
+    #[arg(
+        global = true,
+        short,
+        long,
+        help = "print the response status line and headers before the response body"
+    )]
+    pub include_headers: bool,
+
+    #[arg(
+        global = true,
+        short,
+        long,
+        help = "redirect output to the specified file"
+    )]
     pub out: Option<String>,
+
+    #[arg(
+        global = true,
+        long,
+        help = "read cookies from and write them to this file (netscape/curl format)"
+    )]
+    pub cookie_jar: Option<String>,
+
+    #[arg(
+        global = true,
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "execute up to N independent requests at the same time"
+    )]
+    pub parallel: Option<u16>,
+
+    #[arg(
+        global = true,
+        long,
+        default_value_t = 0,
+        help = "retry failed requests up to N times"
+    )]
+    pub retries: u32,
+
+    #[arg(
+        global = true,
+        long,
+        value_name = "STATUSES",
+        help = "statuses to retry on, e.g. \"429,500-504\". Defaults to 429,502,503,504"
+    )]
+    pub retry_on: Option<StatusSet>,
+
+    #[arg(
+        global = true,
+        long,
+        help = "also retry requests with non-idempotent methods like POST and PATCH"
+    )]
+    pub retry_non_idempotent: bool,
+
+    #[arg(
+        global = true,
+        long,
+        value_name = "MS",
+        help = "base delay between retries in ms, doubled after each attempt. Defaults to 500"
+    )]
+    pub retry_delay_ms: Option<u64>,
+
+    #[arg(
+        global = true,
+        long,
+        value_name = "PATTERN",
+        help = "only run files found in directories or by glob patterns that match PATTERN"
+    )]
+    pub include: Vec<String>,
+
+    #[arg(
+        global = true,
+        long,
+        value_name = "PATTERN",
+        help = "skip files found in directories or by glob patterns that match PATTERN"
+    )]
+    pub exclude: Vec<String>,
+
+    #[arg(
+        global = true,
+        long,
+        value_name = "TAG",
+        help = "only run requests tagged with TAG"
+    )]
+    pub tag: Vec<String>,
+
+    #[arg(
+        global = true,
+        long,
+        value_name = "TAG",
+        help = "skip requests tagged with TAG"
+    )]
+    pub exclude_tag: Vec<String>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Execute requests as tests: keep going after failures and report the results
+    Test(TestArgs),
+    /// Inspect the profiles of the profile file
+    Profiles(ProfilesArgs),
+    /// List the variables requests and their dependencies reference and where they resolve from
+    Vars(VarsArgs),
+    /// Convert commands of other tools into request files
+    Import(ImportArgs),
+}
+
+impl Command {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Test(_) => "test",
+            Command::Profiles(_) => "profiles",
+            Command::Vars(_) => "vars",
+            Command::Import(_) => "import",
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct TestArgs {
+    #[arg(
+        required = true,
+        help = "the request files, directories or glob patterns to test"
+    )]
+    pub files: Vec<String>,
+
+    #[arg(long, value_name = "FILE", help = "write a JUnit XML report to FILE")]
+    pub junit: Option<String>,
+
+    #[arg(long, value_name = "FILE", help = "write a TAP report to FILE")]
+    pub tap: Option<String>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ProfilesArgs {
+    #[command(subcommand)]
+    pub command: ProfilesCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProfilesCommand {
+    /// List the names of all profiles
+    List,
+    /// Print the variables of a profile merged with the default profile, without resolving them
+    Show {
+        #[arg(help = "the profile to show. defaults to --profile, or only the default profile")]
+        name: Option<String>,
+    },
+    /// Resolve all variables of a profile, including secrets and requests, and report failures
+    Check {
+        #[arg(help = "the profile to check. defaults to --profile, or only the default profile")]
+        name: Option<String>,
+    },
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct VarsArgs {
+    #[arg(
+        required = true,
+        help = "the request files, directories or glob patterns to analyze"
+    )]
+    pub files: Vec<String>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ImportArgs {
+    #[command(subcommand)]
+    pub command: ImportCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ImportCommand {
+    /// Convert a curl command into a request file
+    Curl {
+        #[arg(help = "the curl command. read from stdin if omitted")]
+        command: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            help = "the format of the request file: http, json or yaml. defaults to the extension of --out, otherwise http or, for multipart text parts, yaml"
+        )]
+        format: Option<DryRunFormat>,
+    },
+}
+
+fn parse_variable(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_owned(), value.to_owned())),
+        _ => Err(format!("expected NAME=VALUE, got '{}'", arg)),
+    }
 }
 
 impl From<Args> for Config {
@@ -66,5 +343,15 @@ impl From<Args> for Config {
             val.timeout_ms,
             val.curl,
         )
+        .with_include_headers(val.include_headers)
+        .with_parallelism(val.parallel.map(usize::from))
+        .with_retries(val.retries)
+        .with_retry_on(val.retry_on)
+        .with_retry_non_idempotent(val.retry_non_idempotent)
+        .with_retry_delay_ms(val.retry_delay_ms)
+        .with_show_secrets(val.show_secrets)
+        .with_dry_run(val.dry_run)
+        .with_curl_script(val.script)
+        .with_export(val.export)
     }
 }
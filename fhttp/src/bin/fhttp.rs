@@ -1,143 +1,875 @@
-use std::collections::HashMap;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use std::{env, mem};
 
 use anyhow::{anyhow, Result};
 use clap::Parser;
-use fhttp::Args;
-use itertools::Itertools;
+use fhttp::{
+    Args, Command, FileWatcher, ImportArgs, ImportCommand, ProfilesArgs, ProfilesCommand, TestArgs,
+    TestCase, TestReport, TestStatus, VarsArgs,
+};
 
 use fhttp_core::execution::curl::Curl;
-use fhttp_core::path_utils::CanonicalizedPathBuf;
-use fhttp_core::Client;
+use fhttp_core::execution::curl_script::{dependency_assignment, SCRIPT_PREAMBLE};
+use fhttp_core::execution::dry_run::{DryRun, DryRunFormat};
+use fhttp_core::parsers::curl_command::parse_curl_command;
+use fhttp_core::path_utils::canonicalize;
+use fhttp_core::preprocessing::request_preprocessor::Outcome;
+use fhttp_core::profiles::{
+    load_variable_file, ProfileVariable, VariableOrigin, DEFAULT_PROFILE_FILES,
+};
+use fhttp_core::request::metadata::RequestMetadata;
+use fhttp_core::request::Request;
+use fhttp_core::request_sources::discovery::{
+    discover_by_pattern, discover_in_directory, is_glob_pattern, RequestFilter,
+};
+use fhttp_core::request_sources::request_id::{split_reference, RequestId};
+use fhttp_core::request_sources::Preprocessed;
+use fhttp_core::secrets;
 use fhttp_core::Requestpreprocessor;
-use fhttp_core::{Config, Profile, Profiles, RequestSource};
+use fhttp_core::{Client, CookieJar, ResponseStore};
+use fhttp_core::{Config, Profile, Profiles, RequestSource, Response};
 
-fn main() -> Result<()> {
-    let mut args = Args::parse();
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let show_secrets = args.show_secrets;
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            let message = format!("Error: {:?}", e);
+            match show_secrets {
+                true => eprintln!("{}", message),
+                false => eprintln!("{}", secrets::redact(&message)),
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(mut args: Args) -> Result<()> {
+    let command = args.command.take();
     let files = mem::take(&mut args.files);
-    let profile = mem::take(&mut args.profile);
-    let profile_file = mem::take(&mut args.profile_file);
-    let out = get_target_writer(&args.out)?;
-    let config = args.into();
+    let profile = ProfileOptions {
+        name: mem::take(&mut args.profile),
+        file: mem::take(&mut args.profile_file),
+        vars: mem::take(&mut args.vars),
+        var_files: mem::take(&mut args.var_files),
+        env_file: mem::take(&mut args.env_file),
+    };
+    let cookie_jar = mem::take(&mut args.cookie_jar);
+    let filter = RequestFilter::new(&args.include, &args.exclude, &args.tag, &args.exclude_tag)?;
+    let out = mem::take(&mut args.out);
+    let watch = args.watch;
+    let config: Config = args.into();
 
-    do_it(files, profile, profile_file, out, config)
+    if let (Some(command), Some(flag)) = (&command, print_mode_flag(&config)) {
+        return Err(anyhow!(
+            "{} can't be used with the {} subcommand",
+            flag,
+            command.name()
+        ));
+    }
+
+    match command {
+        Some(Command::Test(test_args)) => run_tests(
+            test_args,
+            &filter,
+            &profile,
+            cookie_jar,
+            get_target_writer(&out)?,
+            config,
+        ),
+        Some(Command::Profiles(profiles_args)) => inspect_profiles(
+            profiles_args,
+            profile,
+            cookie_jar,
+            get_target_writer(&out)?,
+            config,
+        ),
+        Some(Command::Vars(vars_args)) => list_variables(
+            vars_args,
+            &filter,
+            &profile,
+            get_target_writer(&out)?,
+            config,
+        ),
+        Some(Command::Import(import_args)) => {
+            import_request(import_args, &out, get_target_writer(&out)?)
+        }
+        None if watch => watch_requests(files, &filter, &profile, cookie_jar, out, config),
+        None => do_it(
+            files,
+            &filter,
+            &profile,
+            cookie_jar,
+            get_target_writer(&out)?,
+            config,
+        ),
+    }
 }
 
 fn do_it(
     files: Vec<String>,
-    profile: Option<String>,
-    profile_file: Option<String>,
-    mut out: Box<dyn Write>,
+    filter: &RequestFilter,
+    profile: &ProfileOptions,
+    cookie_jar_file: Option<String>,
+    out: Box<dyn Write>,
     config: Config,
 ) -> Result<()> {
-    let profile = parse_profile(profile, profile_file)?;
-    let requested_files = files
-        .iter()
-        .map(|file| PathBuf::from_str(file).unwrap())
-        .collect::<Vec<_>>();
-    let requests: Vec<RequestSource> = validate_and_parse_files(&requested_files)?;
+    let profile = parse_profile(profile)?;
+    let requests: Vec<RequestSource> = validate_and_parse_files(&files, filter)?;
+
+    check_curl_requested_for_dependencies(&config, &requests)?;
+
+    let cookie_jar = load_cookie_jar(&cookie_jar_file)?;
+    let client = Client::new(&config, cookie_jar.clone())?;
+    let preprocessor = Requestpreprocessor::new(profile, requests, config, client.clone())?;
+
+    let result = execute(preprocessor, &client, out, &config);
+
+    // persist the session even if a later request failed
+    if let Some(path) = cookie_jar_file {
+        cookie_jar.save(path)?;
+    }
+
+    result
+}
+
+/// Executes the requests again whenever one of the files they are made of, the profile file or
+/// a var file changes. Responses of dependencies, e.g. tokens, are kept between runs unless one
+/// of their files changed, placeholders of dry runs and scripts aren't. Errors are printed and
+/// don't stop watching.
+fn watch_requests(
+    files: Vec<String>,
+    filter: &RequestFilter,
+    profile: &ProfileOptions,
+    cookie_jar_file: Option<String>,
+    out: Option<String>,
+    config: Config,
+) -> Result<()> {
+    let cookie_jar = load_cookie_jar(&cookie_jar_file)?;
+    let client = Client::new(&config, cookie_jar.clone())?;
+    let responses = Arc::new(RwLock::new(ResponseStore::new()));
+    let profile_files = match profile.file {
+        Some(ref file) => vec![file.as_str()],
+        None => DEFAULT_PROFILE_FILES.to_vec(),
+    }
+    .into_iter()
+    .chain(profile.var_files.iter().map(String::as_str))
+    .map(PathBuf::from)
+    .chain([profile.env_file()])
+    .collect::<Vec<_>>();
+    let mut source_files = HashMap::new();
+
+    loop {
+        let result = (|| {
+            let profile = parse_profile(profile)?;
+            let requests = validate_and_parse_files(&files, filter)?;
+            check_curl_requested_for_dependencies(&config, &requests)?;
+
+            let preprocessor = Requestpreprocessor::new(profile, requests, config, client.clone())?;
+            source_files = preprocessor.source_files();
+            let preprocessor = preprocessor.with_response_store(responses.clone());
+
+            execute(preprocessor, &client, get_target_writer(&out)?, &config)
+        })();
+        if let Err(e) = result {
+            eprintln!("{}", config.redact(&format!("Error: {:?}", e)));
+        }
+        if let Some(ref path) = cookie_jar_file {
+            cookie_jar.save(path)?;
+        }
+
+        let mut watcher = FileWatcher::new(
+            files
+                .iter()
+                .map(|arg| watched_path(arg))
+                .chain(profile_files.iter().cloned())
+                .chain(source_files.values().flatten().cloned()),
+        );
+        config.logln(
+            1,
+            format!("watching {} files for changes...", watcher.len()),
+        );
+        let changed = watcher.wait_for_changes();
+
+        let mut responses = responses.write().unwrap();
+        responses.remove_placeholders();
+        if profile_files.iter().any(|it| changed.contains(it)) {
+            *responses = ResponseStore::new();
+        }
+        for (id, files) in &source_files {
+            if !files.is_disjoint(&changed) {
+                responses.remove(id);
+            }
+        }
+    }
+}
+
+/// The path to watch for a request argument; for glob patterns, the directory before the
+/// first wildcard.
+fn watched_path(arg: &str) -> PathBuf {
+    let path = PathBuf::from(arg);
+    if path.exists() || !is_glob_pattern(arg) {
+        return PathBuf::from(split_reference(".", arg).0);
+    }
+
+    let base = path
+        .components()
+        .take_while(|it| !is_glob_pattern(&it.as_os_str().to_string_lossy()))
+        .collect::<PathBuf>();
+    match base.as_os_str().is_empty() {
+        true => PathBuf::from("."),
+        false => base,
+    }
+}
+
+fn execute(
+    preprocessor: Requestpreprocessor,
+    client: &Client,
+    out: Box<dyn Write>,
+    config: &Config,
+) -> Result<()> {
+    if let Some(format) = config.dry_run() {
+        let preprocessor = preprocessor.with_placeholder_dependencies();
+        return print_requests(preprocessor, format, out, config);
+    }
+    if config.curl_script() {
+        let preprocessor = preprocessor.with_shell_variable_dependencies();
+        return print_curl_script(preprocessor, out, config);
+    }
 
-    check_curl_requested_for_dependencies(&config, &requested_files, &requests)?;
+    match config.parallelism() {
+        Some(parallelism) => {
+            execute_requests_parallel(preprocessor, parallelism, client, out, config)
+        }
+        None => execute_requests(preprocessor, client, out, config),
+    }
+}
 
-    let mut preprocessor = Requestpreprocessor::new(profile, requests, config)?;
-    let client = Client::new();
+/// Prints the resolved requests without executing them. Dependencies were replaced with their
+/// stored responses or placeholders before.
+fn print_requests(
+    preprocessor: Requestpreprocessor,
+    format: DryRunFormat,
+    mut out: Box<dyn Write>,
+    config: &Config,
+) -> Result<()> {
+    for (index, req) in preprocessor.enumerate() {
+        let req = req?.parse()?;
+        if index > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "{}", config.redact(&req.request.dry_run(format)?))?;
+    }
+
+    Ok(())
+}
 
+/// Prints a bash script executing the requests with curl, without executing anything. Each
+/// dependency is assigned to a shell variable that the requests depending on it reference.
+fn print_curl_script(
+    preprocessor: Requestpreprocessor,
+    mut out: Box<dyn Write>,
+    config: &Config,
+) -> Result<()> {
+    let store = preprocessor.response_store();
+    let mut script = vec![SCRIPT_PREAMBLE.to_owned()];
+    for req in preprocessor {
+        let req = req?;
+        let dependency = req.dependency;
+        let req = req.parse()?;
+        script.push(match dependency {
+            true => {
+                let store = store.read().unwrap();
+                let variable = store.shell_variable(&req.id).unwrap();
+                dependency_assignment(&req.id, variable, &req.request)?
+            }
+            false => req.request.curl(),
+        });
+    }
+    writeln!(out, "{}", config.redact(&script.join("\n\n")))?;
+
+    Ok(())
+}
+
+/// The flag that makes fhttp print requests instead of executing them, which subcommands don't
+/// support.
+fn print_mode_flag(config: &Config) -> Option<&'static str> {
+    if config.dry_run().is_some() {
+        Some("--dry-run")
+    } else if config.curl_script() {
+        Some("--script")
+    } else if config.curl() {
+        Some("--curl")
+    } else if config.export().is_some() {
+        Some("--export")
+    } else {
+        None
+    }
+}
+
+fn load_cookie_jar(cookie_jar_file: &Option<String>) -> Result<Arc<CookieJar>> {
+    Ok(Arc::new(match cookie_jar_file {
+        Some(ref path) => CookieJar::load(path)?,
+        None => CookieJar::new(),
+    }))
+}
+
+fn execute_requests(
+    mut preprocessor: Requestpreprocessor,
+    client: &Client,
+    mut out: Box<dyn Write>,
+    config: &Config,
+) -> Result<()> {
     while !preprocessor.is_empty() {
         let req = preprocessor.next().unwrap()?;
         let dependency = req.dependency;
         let req = req.parse()?;
-        let path = req.source_path;
+        let id = req.id;
         let req = req.request;
 
-        let msg = match config.print_file_paths() {
-            true => format!("{}... ", &path.to_str()),
-            false => format!("{} {}... ", &req.method, req.url),
-        };
-
-        config.log(1, msg);
-        if config.curl() && !dependency {
-            println!("\n{}", req.curl());
+        config.log(1, request_message(config, &id, &req));
+        if let (Some(exporter), false) = (config.export(), dependency) {
+            println!("\n{}", config.redact(&exporter.export(&req)?));
         } else {
-            let resp = client.exec(
-                req.method,
-                &req.url,
-                req.headers,
-                req.body,
-                req.response_handler,
-                config.timeout(),
-            )?;
+            let metadata = req.metadata.clone();
+            let resp = client.exec(req)?;
             config.logln(1, format!("{}", resp.status()));
 
-            if !resp.status().is_success() {
-                let msg = if resp.body().trim().is_empty() {
-                    "no response body"
-                } else {
-                    resp.body()
+            check_response_status(&resp, &metadata)?;
+
+            preprocessor.notify_response(&id, &resp);
+
+            if !dependency {
+                write_response(&mut out, config, &resp)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Executes the requests concurrently, log lines are printed as the requests finish while
+/// their output is buffered and written in the planned order.
+fn execute_requests_parallel(
+    preprocessor: Requestpreprocessor,
+    parallelism: usize,
+    client: &Client,
+    mut out: Box<dyn Write>,
+    config: &Config,
+) -> Result<()> {
+    let results = preprocessor.execute_parallel(parallelism, |req| {
+        let dependency = req.dependency;
+        let req = req.parse()?;
+        let msg = request_message(config, &req.id, &req.request);
+        let req = req.request;
+
+        if let (Some(exporter), false) = (config.export(), dependency) {
+            config.logln(1, msg);
+            let snippet = config.redact(&exporter.export(&req)?).into_owned();
+            return Ok((None, ParallelOutput::Snippet(snippet)));
+        }
+
+        let metadata = req.metadata.clone();
+        let resp = client.exec(req);
+        let resp = match resp {
+            Ok(resp) => resp,
+            Err(e) => {
+                config.logln(1, msg);
+                return Err(e);
+            }
+        };
+        config.logln(1, format!("{}{}", msg, resp.status()));
+
+        check_response_status(&resp, &metadata)?;
+
+        let mut output = vec![];
+        if !dependency {
+            write_response(&mut output, config, &resp)?;
+        }
+
+        Ok((Some(resp), ParallelOutput::Response(output)))
+    });
+
+    for result in results {
+        match result? {
+            ParallelOutput::Snippet(snippet) => println!("\n{}", snippet),
+            ParallelOutput::Response(output) => out.write_all(&output)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Executes all requests, keeping going after failures, and reports which of them passed.
+/// Requests that only ran as dependencies are reported if they failed.
+fn run_tests(
+    args: TestArgs,
+    filter: &RequestFilter,
+    profile: &ProfileOptions,
+    cookie_jar_file: Option<String>,
+    mut out: Box<dyn Write>,
+    config: Config,
+) -> Result<()> {
+    let profile = parse_profile(profile)?;
+    let requests: Vec<RequestSource> = validate_and_parse_files(&args.files, filter)?;
+    let cookie_jar = load_cookie_jar(&cookie_jar_file)?;
+    let client = Client::new(&config, cookie_jar.clone())?;
+    let preprocessor = Requestpreprocessor::new(profile, requests, config, client.clone())?;
+
+    let durations = Mutex::new(HashMap::new());
+    let outcomes = preprocessor.execute_all(config.parallelism().unwrap_or(1), |req| {
+        let id = req.id();
+        let start = Instant::now();
+        let result = execute_test(&client, &config, req);
+        durations.lock().unwrap().insert(id, start.elapsed());
+        result.map(|resp| (Some(resp), ()))
+    });
+
+    if let Some(path) = cookie_jar_file {
+        cookie_jar.save(path)?;
+    }
+
+    let cwd = canonicalize(&env::current_dir()?)?;
+    let durations = durations.into_inner().unwrap();
+    let report = TestReport {
+        cases: outcomes
+            .into_iter()
+            .filter_map(|outcome| {
+                let status = match outcome.result {
+                    Outcome::Done(Ok(())) if outcome.dependency => return None,
+                    Outcome::Done(Ok(())) => TestStatus::Passed,
+                    Outcome::Done(Err(e)) => {
+                        TestStatus::Failed(config.redact(&format!("{:#}", e)).into_owned())
+                    }
+                    Outcome::Skipped(dep) => TestStatus::Skipped(format!(
+                        "dependency {} failed",
+                        test_name(&dep, cwd.as_ref()).0
+                    )),
                 };
-                return Err(anyhow!("{}", msg));
+                let (name, file) = test_name(&outcome.id, cwd.as_ref());
+                Some(TestCase {
+                    name,
+                    file,
+                    duration: durations
+                        .get(&outcome.id)
+                        .copied()
+                        .unwrap_or(Duration::ZERO),
+                    status,
+                })
+            })
+            .collect(),
+    };
+
+    report.write_summary(&mut out)?;
+    if let Some(path) = args.junit {
+        report.write_junit(&mut File::create(path)?)?;
+    }
+    if let Some(path) = args.tap {
+        report.write_tap(&mut File::create(path)?)?;
+    }
+
+    match report.failed() {
+        0 => Ok(()),
+        failed => Err(anyhow!("{} of {} tests failed", failed, report.cases.len())),
+    }
+}
+
+fn inspect_profiles(
+    args: ProfilesArgs,
+    mut profile: ProfileOptions,
+    cookie_jar_file: Option<String>,
+    mut out: Box<dyn Write>,
+    config: Config,
+) -> Result<()> {
+    match args.command {
+        ProfilesCommand::List => {
+            let path = match profile.file {
+                Some(ref file) => PathBuf::from(file),
+                None => Profiles::find_default().ok_or_else(|| anyhow!("no profile file found"))?,
+            };
+            let mut names = Profiles::parse(&path)?.into_keys().collect::<Vec<_>>();
+            names.sort();
+            for name in names {
+                writeln!(out, "{}", name)?;
             }
 
-            preprocessor.notify_response(&path, resp.body());
+            Ok(())
+        }
+        ProfilesCommand::Show { name } => {
+            profile.name = name.or(profile.name);
+            let profile = parse_profile(&profile)?;
 
-            if !dependency {
-                writeln!(&mut out, "{}", resp.body())?;
-                // println!("{}", resp.body());
+            let lines = profile
+                .annotated_variables()
+                .into_iter()
+                .map(|(name, variable, source)| {
+                    let value = config.redact(&variable.describe()).into_owned();
+                    (name, value, source.unwrap_or("unknown source"))
+                })
+                .collect::<Vec<_>>();
+            let name_width = lines.iter().map(|it| it.0.len()).max().unwrap_or(0);
+            let value_width = lines.iter().map(|it| it.1.len()).max().unwrap_or(0);
+            for (name, value, source) in lines {
+                writeln!(
+                    out,
+                    "{:name_width$} = {:value_width$}  # {}",
+                    name, value, source
+                )?;
+            }
+
+            Ok(())
+        }
+        ProfilesCommand::Check { name } => {
+            profile.name = name.or(profile.name);
+            check_profile(parse_profile(&profile)?, cookie_jar_file, out, config)
+        }
+    }
+}
+
+/// Resolves every variable of the profile. Requests that variables are defined through are
+/// executed, along with their dependencies.
+fn check_profile(
+    profile: Profile,
+    cookie_jar_file: Option<String>,
+    mut out: Box<dyn Write>,
+    config: Config,
+) -> Result<()> {
+    let variables = profile.annotated_variables();
+
+    // requests that can't be loaded fail the variables defined through them, not the check
+    let mut ids: HashMap<&str, Result<RequestId, String>> = HashMap::new();
+    let mut requests = vec![];
+    for (_, variable, _) in &variables {
+        if let ProfileVariable::Request { request } = variable {
+            if ids.contains_key(request.as_str()) {
+                continue;
+            }
+            let loaded = RequestId::resolve(profile.source_path(), request)
+                .and_then(|id| Ok((RequestSource::load(&id, true)?, id)));
+            let id = match loaded {
+                Ok((source, id)) => {
+                    if !requests.iter().any(|it: &RequestSource| it.id() == id) {
+                        requests.push(source);
+                    }
+                    Ok(id)
+                }
+                Err(e) => Err(format!("{:#}", e)),
+            };
+            ids.insert(request, id);
+        }
+    }
+
+    let cookie_jar = load_cookie_jar(&cookie_jar_file)?;
+    let client = Client::new(&config, cookie_jar.clone())?;
+    let mut outcomes: HashMap<RequestId, Outcome<()>> = HashMap::new();
+    if !requests.is_empty() {
+        let responses = Arc::new(RwLock::new(ResponseStore::new()));
+        let preprocessor =
+            Requestpreprocessor::new(profile.clone(), requests, config, client.clone())?
+                .with_response_store(responses);
+        outcomes = preprocessor
+            .execute_all(config.parallelism().unwrap_or(1), |req| {
+                execute_test(&client, &config, req).map(|resp| (Some(resp), ()))
+            })
+            .into_iter()
+            .map(|outcome| (outcome.id, outcome.result))
+            .collect();
+
+        if let Some(path) = cookie_jar_file {
+            cookie_jar.save(path)?;
+        }
+    }
+
+    let width = variables.iter().map(|it| it.0.len()).max().unwrap_or(0);
+    let mut failed = 0;
+    for (name, variable, _) in &variables {
+        let result = match variable {
+            // several variables may be defined through the same request, each of them
+            // reports its outcome
+            ProfileVariable::Request { request } => match &ids[request.as_str()] {
+                Err(e) => Err(anyhow!("{}", e)),
+                Ok(id) => match outcomes.get(id) {
+                    Some(Outcome::Done(Ok(()))) => Ok(()),
+                    Some(Outcome::Done(Err(e))) => Err(anyhow!("{:#}", e)),
+                    Some(Outcome::Skipped(dep)) => Err(anyhow!("dependency {} failed", dep)),
+                    None => Err(anyhow!("request {} wasn't executed", id)),
+                },
+            },
+            _ => profile
+                .get(*name, &config, &client, &ResponseStore::new(), None, true)
+                .map(|_| ()),
+        };
+        match result {
+            Ok(()) => writeln!(out, "{:width$}  ok", name)?,
+            Err(e) => {
+                failed += 1;
+                let message = config.redact(&format!("{:#}", e)).into_owned();
+                writeln!(out, "{:width$}  failed: {}", name, message)?;
             }
         }
     }
 
+    match failed {
+        0 => Ok(()),
+        failed => Err(anyhow!(
+            "{} of {} variables failed",
+            failed,
+            variables.len()
+        )),
+    }
+}
+
+/// Prints the variables the requests and their dependencies reference, without resolving them.
+/// Unresolved variables are an error if fhttp may not prompt for them.
+fn list_variables(
+    args: VarsArgs,
+    filter: &RequestFilter,
+    profile: &ProfileOptions,
+    mut out: Box<dyn Write>,
+    config: Config,
+) -> Result<()> {
+    let profile = parse_profile(profile)?;
+    let requests: Vec<RequestSource> = validate_and_parse_files(&args.files, filter)?;
+    let client = Client::new(&config, Arc::new(CookieJar::new()))?;
+    let variables = Requestpreprocessor::new(profile, requests, config, client)?.variables();
+
+    let cwd = canonicalize(&env::current_dir()?)?;
+    let lines = variables
+        .iter()
+        .map(|usage| {
+            let requests = usage
+                .requests
+                .iter()
+                .map(|id| test_name(id, cwd.as_ref()).0)
+                .collect::<Vec<_>>();
+            (&usage.name, usage.origin.to_string(), requests.join(", "))
+        })
+        .collect::<Vec<_>>();
+    let name_width = lines.iter().map(|it| it.0.len()).max().unwrap_or(0);
+    let origin_width = lines.iter().map(|it| it.1.len()).max().unwrap_or(0);
+    for (name, origin, requests) in lines {
+        writeln!(
+            out,
+            "{:name_width$}  {:origin_width$}  # {}",
+            name, origin, requests
+        )?;
+    }
+
+    let unresolved = variables
+        .iter()
+        .filter(|usage| usage.origin == VariableOrigin::Unresolved)
+        .count();
+    match unresolved {
+        0 => Ok(()),
+        _ if config.prompt_missing_env_vars() => Ok(()),
+        unresolved => Err(anyhow!(
+            "{} of {} variables are unresolved",
+            unresolved,
+            variables.len()
+        )),
+    }
+}
+
+fn import_request(
+    args: ImportArgs,
+    target: &Option<String>,
+    mut out: Box<dyn Write>,
+) -> Result<()> {
+    let ImportCommand::Curl { command, format } = args.command;
+    let command = match command {
+        Some(command) => command,
+        None => {
+            let mut command = String::new();
+            std::io::stdin().read_to_string(&mut command)?;
+            command
+        }
+    };
+    let out_dir = target
+        .as_deref()
+        .and_then(|it| Path::new(it).parent())
+        .unwrap_or_else(|| Path::new("."));
+    let request = parse_curl_command(&command, out_dir)?;
+
+    let format =
+        format.or_else(
+            || match target.as_deref().and_then(|it| Path::new(it).extension()) {
+                Some(ext) if ext == "http" => Some(DryRunFormat::Http),
+                Some(ext) if ext == "json" => Some(DryRunFormat::Json),
+                Some(ext) if ext == "yaml" || ext == "yml" => Some(DryRunFormat::Yaml),
+                _ => None,
+            },
+        );
+    let text = match format {
+        Some(format) => request.dry_run(format)?,
+        // .http files can't express multipart text parts, unless asked for one explicitly
+        None => request
+            .dry_run(DryRunFormat::Http)
+            .or_else(|_| request.dry_run(DryRunFormat::Yaml))?,
+    };
+    writeln!(out, "{}", text.trim_end())?;
+
     Ok(())
 }
 
-fn validate_and_parse_files(files: &[PathBuf]) -> Result<Vec<RequestSource>> {
-    let non_existent = files.iter().filter(|it| !it.exists()).collect::<Vec<_>>();
+fn execute_test(
+    client: &Client,
+    config: &Config,
+    req: RequestSource<Preprocessed>,
+) -> Result<Response> {
+    let req = req.parse()?;
+    let msg = request_message(config, &req.id, &req.request);
+    let metadata = req.request.metadata.clone();
 
-    if !non_existent.is_empty() {
-        let msg = non_existent
-            .iter()
-            .map(|file| format!("'{}' does not exist", file.to_str().unwrap()))
-            .join("\n");
-        return Err(anyhow!("{}", msg));
+    let resp = client.exec(req.request);
+    let resp = match resp {
+        Ok(resp) => resp,
+        Err(e) => {
+            config.logln(1, msg);
+            return Err(e);
+        }
+    };
+    config.logln(1, format!("{}{}", msg, resp.status()));
+
+    check_response_status(&resp, &metadata)?;
+
+    Ok(resp)
+}
+
+/// The request's path relative to `cwd` if possible, with its name appended, and the path alone.
+fn test_name(id: &RequestId, cwd: &Path) -> (String, String) {
+    let path: &Path = id.path.as_ref();
+    let file = path.strip_prefix(cwd).unwrap_or(path).display().to_string();
+
+    match id.name {
+        Some(ref name) => (format!("{}#{}", file, name), file),
+        None => (file.clone(), file),
     }
+}
 
-    let non_file = files.iter().filter(|it| !it.is_file()).collect::<Vec<_>>();
+enum ParallelOutput {
+    Snippet(String),
+    Response(Vec<u8>),
+}
 
-    if !non_file.is_empty() {
-        let msg = non_file
-            .iter()
-            .map(|file| format!("'{}' is not a file", file.to_str().unwrap()))
-            .join("\n");
+fn request_message(config: &Config, id: &RequestId, req: &Request) -> String {
+    match (config.print_file_paths(), &req.metadata.name) {
+        (true, _) => format!("{}... ", id),
+        (false, Some(name)) => format!("{}... ", name),
+        (false, None) => format!("{} {}... ", &req.method, req.url),
+    }
+}
+
+fn check_response_status(resp: &Response, metadata: &RequestMetadata) -> Result<()> {
+    if !metadata.is_expected_status(*resp.status()) {
+        let msg = if resp.body().trim().is_empty() {
+            "no response body"
+        } else {
+            resp.body()
+        };
         return Err(anyhow!("{}", msg));
     }
 
+    Ok(())
+}
+
+fn write_response<W: Write + ?Sized>(out: &mut W, config: &Config, resp: &Response) -> Result<()> {
+    if config.include_headers() {
+        writeln!(out, "{}", resp.status())?;
+        for (name, value) in resp.headers() {
+            writeln!(
+                out,
+                "{}: {}",
+                name,
+                String::from_utf8_lossy(value.as_bytes())
+            )?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out, "{}", resp.body())?;
+
+    Ok(())
+}
+
+/// Arguments may be request files, directories, which are searched for request files, or glob
+/// patterns. Files may be followed by `#name` to run a single request of a file containing
+/// several. Requests found more than once are executed once.
+fn validate_and_parse_files(
+    files: &[String],
+    filter: &RequestFilter,
+) -> Result<Vec<RequestSource>> {
+    let mut errors = vec![];
+    let mut targets = vec![];
+    for arg in files {
+        let path = PathBuf::from_str(arg).unwrap();
+        let (file, name) = split_reference(".", arg);
+        let file = PathBuf::from_str(file).unwrap();
+
+        if path.is_dir() {
+            match discover_in_directory(&path, filter) {
+                Ok(found) => targets.extend(found.into_iter().map(|it| (it, None))),
+                Err(e) => errors.push(e.to_string()),
+            }
+        } else if path.exists() {
+            targets.push((path, None));
+        } else if file.exists() {
+            targets.push((file, name));
+        } else if is_glob_pattern(arg) {
+            match discover_by_pattern(arg, filter) {
+                Ok(found) => targets.extend(found.into_iter().map(|it| (it, None))),
+                Err(e) => errors.push(e.to_string()),
+            }
+        } else {
+            errors.push(format!("'{}' does not exist", file.to_str().unwrap()));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(anyhow!("{}", errors.join("\n")));
+    }
+
     let mut ret = vec![];
-    for file in files {
-        ret.push(RequestSource::from_file(file, false)?);
+    for (file, name) in targets {
+        match name {
+            Some(name) => {
+                let id = RequestId::new(canonicalize(&file)?, Some(name.to_owned()));
+                ret.push(RequestSource::load(&id, false)?);
+            }
+            None => ret.extend(RequestSource::all_from_file(&file, false)?),
+        }
     }
 
-    Ok(ret)
+    let mut seen = HashSet::new();
+    let ret = filter
+        .filter_requests(ret)
+        .into_iter()
+        .filter(|req| seen.insert(req.id()))
+        .collect::<Vec<_>>();
+
+    match ret.is_empty() {
+        true => Err(anyhow!("no requests to execute")),
+        false => Ok(ret),
+    }
 }
 
 fn check_curl_requested_for_dependencies(
     program: &Config,
-    requested_files: &[PathBuf],
     requests: &[RequestSource],
 ) -> Result<()> {
-    use fhttp_core::path_utils;
-
-    if program.curl() {
-        let requested_files = requested_files
-            .iter()
-            .map(|it| path_utils::canonicalize(it))
-            .collect::<Result<Vec<CanonicalizedPathBuf>>>()?;
+    if program.export().is_some() && !program.curl_script() {
+        let requested_ids = requests.iter().map(RequestSource::id).collect::<Vec<_>>();
         let dependencies = requests
             .iter()
-            .map(|req| Ok((req.source_path.clone(), req.unescaped_dependency_paths()?)))
-            .collect::<Result<Vec<(CanonicalizedPathBuf, Vec<CanonicalizedPathBuf>)>>>()?;
+            .map(|req| Ok((req.id(), req.unescaped_dependency_ids()?)))
+            .collect::<Result<Vec<(RequestId, Vec<RequestId>)>>>()?;
         let dependencies = dependencies
             .into_iter()
             .flat_map(|(source, deps)| {
@@ -147,13 +879,13 @@ fn check_curl_requested_for_dependencies(
             })
             .collect::<HashMap<_, _>>();
 
-        for possible_dependency in requested_files {
+        for possible_dependency in requested_ids {
             if let Some(dependency_of) = dependencies.get(&possible_dependency) {
                 return Err(
                     anyhow!(
                         "{}\nis a dependency of\n{}.\nIf you want me to print the curl snippet for both requests you'll need to do them separately.",
-                        possible_dependency.to_str(),
-                        dependency_of.to_str(),
+                        possible_dependency,
+                        dependency_of,
                     )
                 );
             }
@@ -163,8 +895,49 @@ fn check_curl_requested_for_dependencies(
     Ok(())
 }
 
-fn parse_profile(profile: Option<String>, profile_file: Option<String>) -> Result<Profile> {
-    let profile_path = profile_file.map(|it| PathBuf::from_str(&it).unwrap());
+/// Where to load the profile from, and the variables overriding it.
+struct ProfileOptions {
+    name: Option<String>,
+    file: Option<String>,
+    vars: Vec<(String, String)>,
+    var_files: Vec<String>,
+    env_file: Option<String>,
+}
+
+impl ProfileOptions {
+    /// The given `.env` file, or the one next to the profile file.
+    fn env_file(&self) -> PathBuf {
+        match self.env_file {
+            Some(ref path) => PathBuf::from(path),
+            None => match self.file {
+                Some(ref file) => Path::new(file).with_file_name(".env"),
+                None => PathBuf::from(".env"),
+            },
+        }
+    }
+}
+
+/// Loads the profile and overrides its variables with those of the var files and `--var`
+/// arguments, in that order. Variables of the `.env` file are used if neither the profile nor
+/// the environment defines them.
+fn parse_profile(options: &ProfileOptions) -> Result<Profile> {
+    let mut profile = load_profile(options.name.as_deref(), options.file.as_deref())?;
+
+    let env_file = options.env_file();
+    if options.env_file.is_some() || env_file.exists() {
+        profile.set_dotenv_variables(load_variable_file(env_file)?);
+    }
+
+    for file in &options.var_files {
+        profile.override_with_values(load_variable_file(file)?, &format!("var file {}", file));
+    }
+    profile.override_with_values(options.vars.iter().cloned(), "--var");
+
+    Ok(profile)
+}
+
+fn load_profile(profile: Option<&str>, profile_file: Option<&str>) -> Result<Profile> {
+    let profile_path = profile_file.map(|it| PathBuf::from_str(it).unwrap());
 
     let path = match profile_path {
         Some(profile_path) => match profile_path.exists() {
@@ -174,13 +947,10 @@ fn parse_profile(profile: Option<String>, profile_file: Option<String>) -> Resul
                 profile_path.to_str().unwrap()
             )),
         },
-        None => {
-            let profile_path = PathBuf::from_str("fhttp-config.json").unwrap();
-            match profile_path.exists() {
-                true => Ok(profile_path),
-                false => return Ok(Profile::empty(env::current_dir().unwrap())),
-            }
-        }
+        None => match Profiles::find_default() {
+            Some(profile_path) => Ok(profile_path),
+            None => return Ok(Profile::empty(env::current_dir().unwrap())),
+        },
     }?;
 
     let mut profiles = Profiles::parse(&path)?;
@@ -188,7 +958,7 @@ fn parse_profile(profile: Option<String>, profile_file: Option<String>) -> Resul
         .remove("default")
         .unwrap_or_else(|| Profile::empty(&path));
     let profile = match profile {
-        Some(ref name) => profiles.remove(name).ok_or_else(|| {
+        Some(name) => profiles.remove(name).ok_or_else(|| {
             anyhow!(
                 "profile '{}' not found in '{}'",
                 name,
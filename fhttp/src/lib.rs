@@ -4,5 +4,11 @@ extern crate fhttp_test_utils;
 extern crate temp_dir;
 
 mod args;
+mod test_report;
+mod watch;
 
-pub use args::Args;
+pub use args::{
+    Args, Command, ImportArgs, ImportCommand, ProfilesArgs, ProfilesCommand, TestArgs, VarsArgs,
+};
+pub use test_report::{TestCase, TestReport, TestStatus};
+pub use watch::FileWatcher;
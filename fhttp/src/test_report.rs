@@ -0,0 +1,274 @@
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+
+/// The results of a `fhttp test` run.
+#[derive(Debug, Default)]
+pub struct TestReport {
+    pub cases: Vec<TestCase>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub name: String,
+    /// the request file, used as classname in JUnit reports
+    pub file: String,
+    pub duration: Duration,
+    pub status: TestStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestStatus {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+impl TestReport {
+    fn count(&self, predicate: fn(&TestStatus) -> bool) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| predicate(&case.status))
+            .count()
+    }
+
+    pub fn passed(&self) -> usize {
+        self.count(|status| matches!(status, TestStatus::Passed))
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(|status| matches!(status, TestStatus::Failed(_)))
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|status| matches!(status, TestStatus::Skipped(_)))
+    }
+
+    fn duration(&self) -> Duration {
+        self.cases.iter().map(|case| case.duration).sum()
+    }
+
+    /// One line per test, failure messages indented below it, followed by a summary.
+    pub fn write_summary<W: Write + ?Sized>(&self, out: &mut W) -> Result<()> {
+        for case in &self.cases {
+            match case.status {
+                TestStatus::Passed => writeln!(
+                    out,
+                    "ok      {} ({}ms)",
+                    case.name,
+                    case.duration.as_millis()
+                )?,
+                TestStatus::Failed(ref message) => {
+                    writeln!(
+                        out,
+                        "FAILED  {} ({}ms)",
+                        case.name,
+                        case.duration.as_millis()
+                    )?;
+                    for line in message.lines() {
+                        writeln!(out, "        {}", line)?;
+                    }
+                }
+                TestStatus::Skipped(ref reason) => {
+                    writeln!(out, "skipped {} ({})", case.name, reason)?
+                }
+            }
+        }
+        writeln!(
+            out,
+            "\n{} passed, {} failed, {} skipped",
+            self.passed(),
+            self.failed(),
+            self.skipped()
+        )?;
+
+        Ok(())
+    }
+
+    pub fn write_junit<W: Write + ?Sized>(&self, out: &mut W) -> Result<()> {
+        let counts = format!(
+            r#"tests="{}" failures="{}" skipped="{}" time="{:.3}""#,
+            self.cases.len(),
+            self.failed(),
+            self.skipped(),
+            self.duration().as_secs_f64()
+        );
+
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(out, "<testsuites {}>", counts)?;
+        writeln!(out, r#"  <testsuite name="fhttp" {}>"#, counts)?;
+        for case in &self.cases {
+            let attributes = format!(
+                r#"name="{}" classname="{}" time="{:.3}""#,
+                xml_escape(&case.name),
+                xml_escape(&case.file),
+                case.duration.as_secs_f64()
+            );
+            match case.status {
+                TestStatus::Passed => writeln!(out, "    <testcase {}/>", attributes)?,
+                TestStatus::Failed(ref message) => {
+                    writeln!(out, "    <testcase {}>", attributes)?;
+                    writeln!(
+                        out,
+                        r#"      <failure message="{}">{}</failure>"#,
+                        xml_escape(message.lines().next().unwrap_or_default()),
+                        xml_escape(message)
+                    )?;
+                    writeln!(out, "    </testcase>")?;
+                }
+                TestStatus::Skipped(ref reason) => {
+                    writeln!(out, "    <testcase {}>", attributes)?;
+                    writeln!(out, r#"      <skipped message="{}"/>"#, xml_escape(reason))?;
+                    writeln!(out, "    </testcase>")?;
+                }
+            }
+        }
+        writeln!(out, "  </testsuite>")?;
+        writeln!(out, "</testsuites>")?;
+
+        Ok(())
+    }
+
+    pub fn write_tap<W: Write + ?Sized>(&self, out: &mut W) -> Result<()> {
+        writeln!(out, "TAP version 13")?;
+        writeln!(out, "1..{}", self.cases.len())?;
+        for (index, case) in self.cases.iter().enumerate() {
+            let number = index + 1;
+            match case.status {
+                TestStatus::Passed => writeln!(out, "ok {} - {}", number, case.name)?,
+                TestStatus::Failed(ref message) => {
+                    writeln!(out, "not ok {} - {}", number, case.name)?;
+                    writeln!(out, "  ---")?;
+                    writeln!(out, "  message: |")?;
+                    for line in message.lines() {
+                        writeln!(out, "    {}", line)?;
+                    }
+                    writeln!(out, "  ...")?;
+                }
+                TestStatus::Skipped(ref reason) => writeln!(
+                    out,
+                    "ok {} - {} # SKIP {}",
+                    number,
+                    case.name,
+                    reason.replace('\n', " ")
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn report() -> TestReport {
+        TestReport {
+            cases: vec![
+                TestCase {
+                    name: "api.http#login".to_string(),
+                    file: "api.http".to_string(),
+                    duration: Duration::from_millis(12),
+                    status: TestStatus::Passed,
+                },
+                TestCase {
+                    name: "api.http#create-user".to_string(),
+                    file: "api.http".to_string(),
+                    duration: Duration::from_millis(30),
+                    status: TestStatus::Failed(
+                        "assertion 'status == 201' failed, actual value: 200\nassertion 'header location exists' failed, no value found".to_string(),
+                    ),
+                },
+                TestCase {
+                    name: "api.http#delete-user".to_string(),
+                    file: "api.http".to_string(),
+                    duration: Duration::ZERO,
+                    status: TestStatus::Skipped(
+                        "dependency api.http#create-user failed".to_string(),
+                    ),
+                },
+            ],
+        }
+    }
+
+    fn write(f: impl Fn(&TestReport, &mut Vec<u8>) -> Result<()>) -> String {
+        let mut out = vec![];
+        f(&report(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn should_write_a_summary() {
+        assert_eq!(
+            write(|report, out| report.write_summary(out)),
+            indoc!(
+                "
+                ok      api.http#login (12ms)
+                FAILED  api.http#create-user (30ms)
+                        assertion 'status == 201' failed, actual value: 200
+                        assertion 'header location exists' failed, no value found
+                skipped api.http#delete-user (dependency api.http#create-user failed)
+
+                1 passed, 1 failed, 1 skipped
+            "
+            )
+        );
+    }
+
+    #[test]
+    fn should_write_junit_xml() {
+        assert_eq!(
+            write(|report, out| report.write_junit(out)),
+            indoc!(
+                r#"
+                <?xml version="1.0" encoding="UTF-8"?>
+                <testsuites tests="3" failures="1" skipped="1" time="0.042">
+                  <testsuite name="fhttp" tests="3" failures="1" skipped="1" time="0.042">
+                    <testcase name="api.http#login" classname="api.http" time="0.012"/>
+                    <testcase name="api.http#create-user" classname="api.http" time="0.030">
+                      <failure message="assertion &apos;status == 201&apos; failed, actual value: 200">assertion &apos;status == 201&apos; failed, actual value: 200
+                assertion &apos;header location exists&apos; failed, no value found</failure>
+                    </testcase>
+                    <testcase name="api.http#delete-user" classname="api.http" time="0.000">
+                      <skipped message="dependency api.http#create-user failed"/>
+                    </testcase>
+                  </testsuite>
+                </testsuites>
+            "#
+            )
+        );
+    }
+
+    #[test]
+    fn should_write_tap() {
+        assert_eq!(
+            write(|report, out| report.write_tap(out)),
+            indoc!(
+                "
+                TAP version 13
+                1..3
+                ok 1 - api.http#login
+                not ok 2 - api.http#create-user
+                  ---
+                  message: |
+                    assertion 'status == 201' failed, actual value: 200
+                    assertion 'header location exists' failed, no value found
+                  ...
+                ok 3 - api.http#delete-user # SKIP dependency api.http#create-user failed
+            "
+            )
+        );
+    }
+}
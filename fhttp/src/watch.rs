@@ -0,0 +1,154 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Detects changes to files by polling their modification times. For directories, the
+/// modification times of the directory and its subdirectories are watched, which change when
+/// files are added, removed or renamed.
+#[derive(Debug)]
+pub struct FileWatcher {
+    paths: Vec<PathBuf>,
+    snapshot: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+impl FileWatcher {
+    pub fn new<I: IntoIterator<Item = PathBuf>>(paths: I) -> Self {
+        let paths = paths
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let snapshot = snapshot(&paths);
+
+        FileWatcher { paths, snapshot }
+    }
+
+    /// The number of watched files and directories.
+    pub fn len(&self) -> usize {
+        self.snapshot.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshot.is_empty()
+    }
+
+    /// Returns the files and directories that were created, modified or removed since the
+    /// last call, or since the watcher was created.
+    pub fn changes(&mut self) -> HashSet<PathBuf> {
+        let snapshot = snapshot(&self.paths);
+        let changed = snapshot
+            .iter()
+            .filter(|(path, modified)| self.snapshot.get(*path) != Some(*modified))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.snapshot
+                    .keys()
+                    .filter(|path| !snapshot.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        self.snapshot = snapshot;
+
+        changed
+    }
+
+    /// Blocks until something changes. Waits for a short moment after the first change, as
+    /// editors often write files in several steps.
+    pub fn wait_for_changes(&mut self) -> HashSet<PathBuf> {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let mut changed = self.changes();
+            if !changed.is_empty() {
+                thread::sleep(POLL_INTERVAL);
+                changed.extend(self.changes());
+                return changed;
+            }
+        }
+    }
+}
+
+fn snapshot(paths: &[PathBuf]) -> HashMap<PathBuf, Option<SystemTime>> {
+    let mut ret = HashMap::new();
+    for path in paths {
+        match path.is_dir() {
+            true => snapshot_directory(path, &mut ret),
+            false => {
+                ret.insert(path.clone(), modified(path));
+            }
+        }
+    }
+
+    ret
+}
+
+fn snapshot_directory(dir: &Path, snapshot: &mut HashMap<PathBuf, Option<SystemTime>>) {
+    snapshot.insert(dir.to_path_buf(), modified(dir));
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type().is_ok_and(|it| it.is_dir()) {
+            snapshot_directory(&entry.path(), snapshot);
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|it| it.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use fhttp_test_utils::write_test_file;
+    use temp_dir::TempDir;
+
+    use super::*;
+
+    fn set_modified(path: &Path, seconds: u64) {
+        fs::File::open(path)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    #[test]
+    fn should_report_changed_files() {
+        let workdir = TempDir::new().unwrap();
+        let request = PathBuf::from(write_test_file(&workdir, "req.http", "GET /").unwrap());
+        let include = PathBuf::from(write_test_file(&workdir, "body.txt", "body").unwrap());
+        let missing = workdir.path().join("missing.txt");
+
+        let mut watcher = FileWatcher::new(vec![request.clone(), include.clone(), missing.clone()]);
+        assert_eq!(watcher.len(), 3);
+        assert!(watcher.changes().is_empty());
+
+        set_modified(&include, 1000);
+        fs::write(&missing, "now it exists").unwrap();
+        assert_eq!(watcher.changes(), HashSet::from([include, missing.clone()]));
+
+        fs::remove_file(&missing).unwrap();
+        assert_eq!(watcher.changes(), HashSet::from([missing]));
+        assert!(watcher.changes().is_empty());
+    }
+
+    #[test]
+    fn should_report_new_files_in_directories() {
+        let workdir = TempDir::new().unwrap();
+        let dir = workdir.path().join("requests");
+        let subdir = dir.join("users");
+        fs::create_dir_all(&subdir).unwrap();
+        set_modified(&subdir, 1000);
+
+        let mut watcher = FileWatcher::new(vec![dir.clone()]);
+        assert_eq!(watcher.len(), 2);
+
+        fs::write(subdir.join("get.http"), "GET /").unwrap();
+        assert_eq!(watcher.changes(), HashSet::from([subdir]));
+    }
+}
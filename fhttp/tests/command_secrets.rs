@@ -0,0 +1,84 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_files;
+use rstest::{fixture, rstest};
+use temp_dir::TempDir;
+
+#[fixture]
+fn workdir() -> TempDir {
+    write_test_files(&[
+        (
+            "fhttp-config.json",
+            r#"{
+                "default": {
+                    "variables": {
+                        "TOKEN": {
+                            "command": ["sh", "-c", "echo $PREFIX-token"],
+                            "env": { "PREFIX": "secret" }
+                        },
+                        "FAILING": { "command": ["sh", "-c", "echo denied >&2; exit 1"] }
+                    }
+                }
+            }"#,
+        ),
+        (
+            "req.http",
+            "GET ${env(URL)}/items\nauthorization: ${env(TOKEN)}",
+        ),
+        ("failing.http", "GET ${env(URL)}/${env(FAILING)}"),
+    ])
+    .unwrap()
+}
+
+#[rstest]
+fn should_resolve_command_secrets(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+
+    let mock = server
+        .mock("GET", "/items")
+        .match_header("authorization", "secret-token")
+        .with_body("OK")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", server.url())
+        .arg("req.http")
+        .assert()
+        .success()
+        .stdout("OK\n");
+
+    mock.assert();
+}
+
+#[rstest]
+fn should_print_command_secrets_as_command_substitutions_in_curl_mode(workdir: TempDir) {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", "http://localhost")
+        .args(["--curl", "req.http"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            r#"-H "authorization: $(PREFIX=secret sh -c 'echo $PREFIX-token')""#,
+        ));
+}
+
+#[rstest]
+fn should_report_failing_commands(workdir: TempDir) {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", "http://localhost")
+        .arg("failing.http")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "failed with exit status: 1: 'denied'",
+        ));
+}
@@ -0,0 +1,80 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use temp_dir::TempDir;
+
+#[test]
+fn should_share_cookies_between_requests() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let login = write_test_file(&workdir, "login.http", "POST ${env(URL)}/login").unwrap();
+    let me = write_test_file(&workdir, "me.http", "GET ${env(URL)}/me").unwrap();
+
+    let login_mock = server
+        .mock("POST", "/login")
+        .expect(1)
+        .with_header("set-cookie", "session=abc; Path=/; HttpOnly")
+        .create();
+    let me_mock = server
+        .mock("GET", "/me")
+        .expect(1)
+        .match_header("cookie", "session=abc")
+        .with_body("gordon")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(login.to_str())
+        .arg(me.to_str())
+        .assert()
+        .success()
+        .stdout("\ngordon\n");
+
+    login_mock.assert();
+    me_mock.assert();
+}
+
+#[test]
+fn should_load_and_save_cookie_jars() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let me = write_test_file(&workdir, "me.http", "GET ${env(URL)}/me").unwrap();
+    let jar = write_test_file(
+        &workdir,
+        "cookies.txt",
+        "# Netscape HTTP Cookie File\n127.0.0.1\tFALSE\t/\tFALSE\t0\tsession\tabc\n",
+    )
+    .unwrap();
+
+    let me_mock = server
+        .mock("GET", "/me")
+        .expect(1)
+        .match_header("cookie", "session=abc")
+        .with_header("set-cookie", "theme=dark; Path=/")
+        .with_body("gordon")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg("--cookie-jar")
+        .arg(jar.to_str())
+        .arg(me.to_str())
+        .assert()
+        .success()
+        .stdout("gordon\n");
+
+    me_mock.assert();
+    assert_eq!(
+        std::fs::read_to_string(&jar).unwrap(),
+        "# Netscape HTTP Cookie File\n127.0.0.1\tFALSE\t/\tFALSE\t0\tsession\tabc\n127.0.0.1\tFALSE\t/\tFALSE\t0\ttheme\tdark\n",
+    );
+}
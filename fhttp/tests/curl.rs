@@ -8,6 +8,7 @@ use async_std::task::block_on;
 use fhttp_core::execution::curl::Curl;
 use fhttp_core::path_utils::canonicalize;
 use fhttp_core::request::body::Body;
+use fhttp_core::request::metadata::RequestMetadata;
 use fhttp_core::request::Request;
 use fhttp_test_utils::write_test_file;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
@@ -53,6 +54,8 @@ async fn test() -> Result<()> {
         headers,
         body: Body::Plain(body.clone()),
         response_handler: None,
+        assertions: vec![],
+        metadata: RequestMetadata::default(),
     }.curl();
 
     let workdir = TempDir::new()?;
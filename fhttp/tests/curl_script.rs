@@ -0,0 +1,174 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_files;
+use indoc::indoc;
+use rstest::{fixture, rstest};
+use temp_dir::TempDir;
+
+#[fixture]
+fn workdir() -> TempDir {
+    write_test_files(&[
+        (
+            "fhttp-config.json",
+            indoc!(
+                r#"
+                {
+                    "default": {
+                        "variables": {
+                            "PASSWORD": { "command": ["echo", "s3cret"] },
+                            "TOKEN": { "request": "token.http" }
+                        }
+                    }
+                }
+            "#
+            ),
+        ),
+        (
+            "token.http",
+            indoc!(
+                r#"
+                POST ${env(URL)}/token
+
+                ${env(PASSWORD)}
+
+                > {%
+                    json $.token
+                %}
+            "#
+            ),
+        ),
+        ("user.http", "POST ${env(URL)}/users"),
+        (
+            "req.http",
+            indoc!(
+                r#"
+                GET ${env(URL)}${request_header("user.http", "location")}
+                authorization: Bearer ${env(TOKEN)}
+            "#
+            ),
+        ),
+    ])
+    .unwrap()
+}
+
+#[rstest]
+fn should_print_a_script_running_the_dependencies(workdir: TempDir) {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", "http://localhost")
+        .args(["--curl", "--script", "req.http"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("#!/usr/bin/env bash\n"))
+        .stdout(predicates::str::ends_with(indoc!(
+            r#"
+            TOKEN=$(curl -X POST \
+            -d "$(echo s3cret)" \
+            --url "http://localhost/token" \
+            --silent --show-error --fail --dump-header "$headers/TOKEN" \
+            | jq -rc .token)
+
+            USER=$(curl -X POST \
+            --url "http://localhost/users" \
+            --silent --show-error --fail --dump-header "$headers/USER")
+
+            curl -X GET \
+            -H "authorization: Bearer ${TOKEN}" \
+            --url "http://localhost$(response_header USER location)"
+        "#
+        )));
+}
+
+#[rstest]
+fn should_run_exported_scripts_without_fhttp(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+
+    let token = server
+        .mock("POST", "/token")
+        .match_body("s3cret")
+        .with_body(r#"{ "token": "jwt" }"#)
+        .create();
+    let user = server
+        .mock("POST", "/users")
+        .with_status(201)
+        .with_header("location", "/users/42")
+        .create();
+    let get = server
+        .mock("GET", "/users/42")
+        .match_header("authorization", "Bearer jwt")
+        .with_body("jane")
+        .create();
+
+    let output = Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args(["--curl", "--script", "req.http"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    Command::new("bash")
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stdout("jane");
+
+    token.assert();
+    user.assert();
+    get.assert();
+}
+
+#[test]
+fn should_require_curl() {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .args(["--script", "req.http"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--curl"));
+}
+
+#[test]
+fn should_give_dependencies_with_the_same_file_name_distinct_variables() {
+    let workdir = write_test_files(&[
+        ("a/token.http", "POST http://localhost/a"),
+        ("b/token.http", "POST http://localhost/b"),
+        (
+            "req.http",
+            r#"GET http://localhost/${request("a/token.http")}/${request("b/token.http")}"#,
+        ),
+    ])
+    .unwrap();
+
+    let output = Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args(["--curl", "--script", "req.http"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let script = String::from_utf8(output).unwrap();
+
+    // the variables are numbered in the order the dependencies are planned in
+    let (a, b) = match script.contains("TOKEN=$(curl -X POST \\\n--url \"http://localhost/a\"") {
+        true => ("TOKEN", "TOKEN_2"),
+        false => ("TOKEN_2", "TOKEN"),
+    };
+    assert!(script.contains(&format!(
+        "{}=$(curl -X POST \\\n--url \"http://localhost/a\"",
+        a
+    )));
+    assert!(script.contains(&format!(
+        "{}=$(curl -X POST \\\n--url \"http://localhost/b\"",
+        b
+    )));
+    assert!(script.contains(&format!("--url \"http://localhost/${{{}}}/${{{}}}\"", a, b)));
+}
@@ -0,0 +1,72 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use temp_dir::TempDir;
+
+#[test]
+fn should_apply_directives_to_single_requests() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let req = write_test_file(
+        &workdir,
+        "login.http",
+        r#"# @name login
+# @no-redirect
+# @expect-status 302
+POST ${env(URL)}/login"#,
+    )
+    .unwrap();
+
+    let login_mock = server
+        .mock("POST", "/login")
+        .expect(1)
+        .with_status(302)
+        .with_header("location", "/home")
+        .with_body("redirecting")
+        .create();
+    let home_mock = server.mock("GET", "/home").expect(0).create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(req.to_str())
+        .assert()
+        .success()
+        .stderr("login... 302 Found\n")
+        .stdout("redirecting\n");
+
+    login_mock.assert();
+    home_mock.assert();
+}
+
+#[test]
+fn should_treat_unknown_directives_as_comments() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let req = write_test_file(
+        &workdir,
+        "req.http",
+        r#"# @author jane
+GET ${env(URL)}/users"#,
+    )
+    .unwrap();
+
+    let mock = server.mock("GET", "/users").with_body("jane").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(req.to_str())
+        .assert()
+        .success()
+        .stdout("jane\n");
+
+    mock.assert();
+}
@@ -0,0 +1,105 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_files;
+use rstest::{fixture, rstest};
+use temp_dir::TempDir;
+
+#[fixture]
+fn workdir() -> TempDir {
+    write_test_files(&[
+        (
+            "api/10-health.http",
+            "# @tags smoke\nGET ${env(URL)}/health",
+        ),
+        (
+            "api/2-login.http",
+            "# @tags smoke, auth\nPOST ${env(URL)}/login",
+        ),
+        (
+            "api/users/list.yaml",
+            "method: GET\nurl: ${env(URL)}/users\ntags: [slow]\n",
+        ),
+        ("api/users/notes.txt", "not a request"),
+    ])
+    .unwrap()
+}
+
+#[rstest]
+fn should_execute_all_requests_of_a_directory_in_order(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+
+    server.mock("POST", "/login").with_body("login").create();
+    server.mock("GET", "/health").with_body("health").create();
+    server.mock("GET", "/users").with_body("users").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .arg("api")
+        .assert()
+        .success()
+        .stdout("login\nhealth\nusers\n");
+}
+
+#[rstest]
+fn should_filter_by_patterns_and_tags(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+
+    server.mock("POST", "/login").with_body("login").create();
+    server.mock("GET", "/health").with_body("health").create();
+    let users_mock = server.mock("GET", "/users").expect(0).create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args(["api", "--exclude", "users/*", "--tag", "smoke"])
+        .assert()
+        .success()
+        .stdout("login\nhealth\n");
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args(["api", "--exclude-tag", "auth", "--exclude-tag", "slow"])
+        .assert()
+        .success()
+        .stdout("health\n");
+
+    users_mock.assert();
+}
+
+#[rstest]
+fn should_expand_glob_patterns(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+
+    server.mock("POST", "/login").with_body("login").create();
+    server.mock("GET", "/health").with_body("health").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args(["api/*.http", "api/2-login.http"])
+        .assert()
+        .success()
+        .stdout("login\nhealth\n");
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .arg("api/*.json")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "'api/*.json' matches no request files",
+        ));
+}
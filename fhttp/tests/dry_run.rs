@@ -0,0 +1,113 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use indoc::{formatdoc, indoc};
+use rstest::rstest;
+use temp_dir::TempDir;
+
+#[test]
+fn should_print_resolved_requests_without_executing_them() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(
+        &workdir,
+        "fhttp-config.json",
+        r#"{ "default": { "variables": { "TOKEN": { "request": "token.http" } } } }"#,
+    )
+    .unwrap();
+    write_test_file(&workdir, "token.http", "POST ${env(URL)}/token").unwrap();
+    write_test_file(&workdir, "user.http", "POST ${env(URL)}/users").unwrap();
+    write_test_file(
+        &workdir,
+        "req.http",
+        indoc!(
+            r#"
+            PATCH ${env(URL)}/users/${request("user.http")}
+            authorization: Bearer ${env(TOKEN)}
+
+            { "name": "${env(NAME, "jane")}" }
+        "#
+        ),
+    )
+    .unwrap();
+    write_test_file(&workdir, "other.http", "GET ${env(URL)}/other").unwrap();
+
+    let mock = server
+        .mock("POST", mockito::Matcher::Any)
+        .expect(0)
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args(["--dry-run", "req.http", "other.http"])
+        .assert()
+        .success()
+        .stdout(formatdoc!(
+            r#"
+            PATCH {url}/users/<response of user.http>
+            authorization: Bearer <response of token.http>
+
+            {{ "name": "jane" }}
+
+            GET {url}/other
+        "#,
+            url = url,
+        ));
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args(["--dry-run=json", "req.http"])
+        .assert()
+        .success()
+        .stdout(formatdoc!(
+            r#"
+            {{
+              "method": "PATCH",
+              "url": "{url}/users/<response of user.http>",
+              "headers": {{
+                "authorization": "Bearer <response of token.http>"
+              }},
+              "body": "{{ \"name\": \"jane\" }}"
+            }}
+        "#,
+            url = url,
+        ));
+
+    mock.assert();
+}
+
+#[test]
+fn should_reject_unknown_formats() {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .args(["--dry-run=xml", "req.http"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "unknown format 'xml', expected http, json or yaml",
+        ));
+}
+
+#[rstest]
+#[case(&["test", "--dry-run", "req.http"], "--dry-run can't be used with the test subcommand")]
+#[case(&["profiles", "check", "--dry-run=json"], "--dry-run can't be used with the profiles subcommand")]
+#[case(&["test", "--curl", "req.http"], "--curl can't be used with the test subcommand")]
+#[case(&["test", "--curl", "--script", "req.http"], "--script can't be used with the test subcommand")]
+#[case(&["--export", "python", "test", "req.http"], "--export can't be used with the test subcommand")]
+fn should_reject_print_modes_for_subcommands(#[case] args: &[&str], #[case] message: &str) {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .args(args)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(message));
+}
@@ -0,0 +1,235 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use indoc::indoc;
+use predicates::prelude::*;
+use temp_dir::TempDir;
+
+/// Puts fake `sops` and `age` programs on the path. sops "decrypts" values by removing the
+/// `ENC[...]` around them, age by applying rot13 after checking the identity file, or the keys
+/// on stdin.
+fn fake_tools(workdir: &TempDir) -> String {
+    fs::create_dir(workdir.path().join("bin")).unwrap();
+    let tools = [
+        (
+            "bin/sops",
+            indoc!(
+                r#"
+                #!/bin/sh
+                [ "$1" = "--decrypt" ] || exit 1
+                sed -e '/^sops:/,$d' -e 's/ENC\[\(.*\)\]/\1/' "$2"
+                "#
+            ),
+        ),
+        (
+            "bin/age",
+            indoc!(
+                r#"
+                #!/bin/sh
+                [ "$1" = "--decrypt" ] || exit 1
+                shift
+                found=""
+                while [ "$1" = "--identity" ]; do
+                    case "$2" in
+                        -) [ "$(cat)" = "$EXPECTED_KEY" ] && found=1 ;;
+                        *) [ "$2" = "$EXPECTED_IDENTITY" ] && found=1 ;;
+                    esac
+                    shift 2
+                done
+                [ -n "$found" ] || { echo "no identity" >&2; exit 1; }
+                tr 'A-Za-z' 'N-ZA-Mn-za-m' < "$1"
+                "#
+            ),
+        ),
+    ];
+    for (name, script) in tools {
+        let path = write_test_file(workdir, name, script).unwrap();
+        fs::set_permissions(path.to_str(), fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    format!(
+        "{}:{}",
+        workdir.path().join("bin").to_str().unwrap(),
+        env::var("PATH").unwrap()
+    )
+}
+
+#[test]
+fn should_decrypt_sops_encrypted_profiles() {
+    let mut server = mockito::Server::new();
+    let workdir = TempDir::new().unwrap();
+    let path = fake_tools(&workdir);
+
+    write_test_file(
+        &workdir,
+        "fhttp-config.yaml",
+        indoc!(
+            r#"
+            default:
+              variables:
+                TOKEN: ENC[s3cr3t]
+            sops:
+              mac: ENC[mac]
+              version: 3.8.1
+            "#
+        ),
+    )
+    .unwrap();
+    write_test_file(&workdir, "req.http", "GET ${env(URL)}/${env(TOKEN)}").unwrap();
+
+    let request = server.mock("GET", "/s3cr3t").with_body("OK").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("PATH", path)
+        .env("URL", server.url())
+        .arg("req.http")
+        .assert()
+        .success()
+        .stdout("OK\n");
+
+    request.assert();
+}
+
+#[test]
+fn should_decrypt_age_encrypted_profiles() {
+    let mut server = mockito::Server::new();
+    let workdir = TempDir::new().unwrap();
+    let path = fake_tools(&workdir);
+
+    let identity = write_test_file(&workdir, "keys.txt", "AGE-SECRET-KEY-1").unwrap();
+    // rot13 of a TOML profile setting TOKEN to "s3cr3t"
+    write_test_file(
+        &workdir,
+        "staging.toml.age",
+        "[qrsnhyg.inevnoyrf]\nGBXRA = \"f3pe3g\"\n",
+    )
+    .unwrap();
+    write_test_file(&workdir, "req.http", "GET ${env(URL)}/${env(TOKEN)}").unwrap();
+
+    let request = server.mock("GET", "/s3cr3t").with_body("OK").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("PATH", &path)
+        .env("URL", server.url())
+        .env("SOPS_AGE_KEY_FILE", identity.to_str())
+        .env("EXPECTED_IDENTITY", identity.to_str())
+        .args(["-f", "staging.toml.age", "req.http"])
+        .assert()
+        .success()
+        .stdout("OK\n");
+
+    request.assert();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("PATH", &path)
+        .env("URL", server.url())
+        .env("SOPS_AGE_KEY_FILE", "wrong-keys.txt")
+        .env("EXPECTED_IDENTITY", identity.to_str())
+        .args(["-f", "staging.toml.age", "req.http"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "error decrypting staging.toml.age",
+        ))
+        .stderr(predicates::str::contains("no identity"));
+}
+
+#[test]
+fn should_decrypt_age_encrypted_profiles_with_keys_from_the_environment() {
+    let mut server = mockito::Server::new();
+    let workdir = TempDir::new().unwrap();
+    let path = fake_tools(&workdir);
+
+    write_test_file(
+        &workdir,
+        "staging.toml.age",
+        "[qrsnhyg.inevnoyrf]\nGBXRA = \"f3pe3g\"\n",
+    )
+    .unwrap();
+    write_test_file(&workdir, "req.http", "GET ${env(URL)}/${env(TOKEN)}").unwrap();
+
+    let request = server.mock("GET", "/s3cr3t").with_body("OK").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("PATH", &path)
+        .env("URL", server.url())
+        .env("HOME", workdir.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("SOPS_AGE_KEY_FILE")
+        .env("SOPS_AGE_KEY", "AGE-SECRET-KEY-1")
+        .env("EXPECTED_KEY", "AGE-SECRET-KEY-1")
+        .args(["-f", "staging.toml.age", "req.http"])
+        .assert()
+        .success()
+        .stdout("OK\n");
+
+    request.assert();
+}
+
+#[test]
+fn should_mask_values_of_encrypted_profiles() {
+    let workdir = TempDir::new().unwrap();
+    let path = fake_tools(&workdir);
+
+    write_test_file(
+        &workdir,
+        "fhttp-config.yaml",
+        indoc!(
+            r#"
+            default:
+              variables:
+                TOKEN: ENC[s3cr3t-token]
+                PORT: ENC["8080"]
+                HOST: example.com
+            sops:
+              mac: ENC[mac]
+              version: 3.8.1
+            "#
+        ),
+    )
+    .unwrap();
+    write_test_file(
+        &workdir,
+        "req.http",
+        "GET http://${env(HOST)}:${env(PORT)}/users\nauthorization: ${env(TOKEN)}",
+    )
+    .unwrap();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("PATH", &path)
+        .args(["profiles", "show"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "TOKEN = **** (encrypted profile file)",
+        ))
+        .stdout(predicates::str::contains("PORT  = 8080"))
+        .stdout(predicates::str::contains("HOST  = example.com"))
+        .stdout(predicates::str::contains("s3cr3t").not());
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("PATH", &path)
+        .args(["--dry-run", "req.http"])
+        .assert()
+        .success()
+        .stdout("GET http://example.com:8080/users\nauthorization: ****\n");
+}
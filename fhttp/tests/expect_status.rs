@@ -0,0 +1,78 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use predicates::str::contains;
+use temp_dir::TempDir;
+
+#[test]
+fn should_accept_expected_statuses() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let req = write_test_file(
+        &workdir,
+        "delete.http",
+        r#"# @expect-status 204,404
+DELETE ${env(URL)}/resource
+
+> {%
+    json $.message
+%}"#,
+    )
+    .unwrap();
+
+    let mock = server
+        .mock("DELETE", "/resource")
+        .expect(1)
+        .with_status(404)
+        .with_body(r#"{ "message": "gone" }"#)
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(req.to_str())
+        .assert()
+        .success()
+        .stdout("gone\n");
+
+    mock.assert();
+}
+
+#[test]
+fn should_fail_on_statuses_that_are_not_expected() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let req = write_test_file(
+        &workdir,
+        "create.yaml",
+        r#"method: POST
+url: ${env(URL)}/resource
+expect_status: [409]
+"#,
+    )
+    .unwrap();
+
+    let mock = server
+        .mock("POST", "/resource")
+        .expect(1)
+        .with_status(201)
+        .with_body("created")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(req.to_str())
+        .assert()
+        .failure()
+        .stderr(contains("created"));
+
+    mock.assert();
+}
@@ -0,0 +1,131 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_files;
+use indoc::{formatdoc, indoc};
+use rstest::{fixture, rstest};
+use temp_dir::TempDir;
+
+#[fixture]
+fn workdir() -> TempDir {
+    write_test_files(&[
+        (
+            "fhttp-config.json",
+            r#"{ "default": { "variables": { "API_KEY": { "command": ["echo", "k3y"] } } } }"#,
+        ),
+        ("id.http", "POST ${env(URL)}/ids"),
+        (
+            "req.http",
+            indoc!(
+                r#"
+                PUT ${env(URL)}/users/${request("id.http")}
+                x-api-key: ${env(API_KEY)}
+
+                {"name": "jane"}
+            "#
+            ),
+        ),
+    ])
+    .unwrap()
+}
+
+#[rstest]
+fn should_export_shell_commands_with_secret_evaluations(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let id = server.mock("POST", "/ids").with_body("42").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args(["--export", "httpie", "req.http"])
+        .assert()
+        .success()
+        .stdout(formatdoc!(
+            r#"
+
+            http --ignore-stdin PUT "{url}/users/42" \
+            "x-api-key:$(echo k3y)" \
+            --raw "{{\"name\": \"jane\"}}"
+        "#,
+            url = url,
+        ));
+
+    id.assert();
+}
+
+#[rstest]
+fn should_export_code_with_masked_secrets(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    server.mock("POST", "/ids").with_body("42").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args(["--export", "python", "req.http"])
+        .assert()
+        .success()
+        .stdout(formatdoc!(
+            r#"
+
+            import requests
+
+            response = requests.request(
+                "PUT",
+                "{url}/users/42",
+                headers={{
+                    "x-api-key": "****",
+                }},
+                data="{{\"name\": \"jane\"}}",
+            )
+            print(response.text)
+        "#,
+            url = url,
+        ));
+}
+
+#[test]
+fn should_export_multipart_requests_as_comments_for_wget() {
+    let workdir = write_test_files(&[
+        ("image.txt", "not really an image"),
+        (
+            "upload.http",
+            "POST http://localhost/upload\n\n${file(\"image\", \"image.txt\")}",
+        ),
+        ("users.http", "GET http://localhost/users"),
+    ])
+    .unwrap();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args(["--export", "wget", "upload.http", "users.http"])
+        .assert()
+        .success()
+        .stdout(indoc!(
+            r#"
+
+            # wget can't send multipart requests, export POST http://localhost/upload with another format, e.g. --export curl
+
+            wget --quiet --output-document=- --method=GET \
+            "http://localhost/users"
+        "#
+        ));
+}
+
+#[test]
+fn should_reject_unknown_formats() {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .args(["--export", "postman", "req.http"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "unknown export format 'postman', expected one of curl, httpie, wget, python, fetch, reqwest",
+        ));
+}
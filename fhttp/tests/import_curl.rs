@@ -0,0 +1,158 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use std::fs;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use indoc::{formatdoc, indoc};
+use temp_dir::TempDir;
+
+#[test]
+fn should_import_curl_commands_as_http_files() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+    let mock = server
+        .mock("POST", "/users")
+        .match_header("authorization", "Basic amFuZTpzM2NyZXQ=")
+        .match_header("content-type", "application/json")
+        .match_body(r#"{"name":"jane's"}"#)
+        .with_body("created")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args([
+            "import",
+            "curl",
+            &format!(
+                "curl '{}/users' \\\n  -u jane:s3cret \\\n  -H 'content-type: application/json' \\\n  --data-raw $'{{\"name\":\"jane\\'s\"}}' \\\n  --compressed",
+                url
+            ),
+            "-o",
+            "users.http",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(workdir.path().join("users.http")).unwrap(),
+        formatdoc!(
+            r#"
+            POST {url}/users
+            content-type: application/json
+            authorization: Basic amFuZTpzM2NyZXQ=
+
+            {{"name":"jane's"}}
+        "#,
+            url = url,
+        )
+    );
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .arg("users.http")
+        .assert()
+        .success()
+        .stdout("created\n");
+
+    mock.assert();
+}
+
+#[test]
+fn should_import_multipart_requests_from_stdin_as_yaml() {
+    let workdir = TempDir::new().unwrap();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args(["import", "curl"])
+        .write_stdin(indoc!(
+            r#"
+            curl -X PUT http://localhost/upload \
+              -F 'image=@image.jpg;type=image/jpeg' \
+              -F title=holiday
+        "#
+        ))
+        .assert()
+        .success()
+        .stdout(formatdoc!(
+            r#"
+            method: PUT
+            url: http://localhost/upload
+            body:
+            - name: image
+              filepath: image.jpg
+              mime: image/jpeg
+            - name: title
+              text: holiday
+        "#
+        ));
+}
+
+#[test]
+fn should_keep_uploaded_files_relative_to_the_out_file() {
+    let workdir = TempDir::new().unwrap();
+    write_test_file(&workdir, "image.jpg", "jpeg").unwrap();
+    fs::create_dir(workdir.path().join("requests")).unwrap();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args([
+            "import",
+            "curl",
+            "curl -F image=@image.jpg -F file=@missing.bin http://localhost/upload",
+            "-o",
+            "requests/upload.http",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(workdir.path().join("requests/upload.http")).unwrap(),
+        indoc!(
+            r#"
+            POST http://localhost/upload
+
+            ${file("image", "../image.jpg")}
+            ${file("file", "../missing.bin")}
+        "#
+        )
+    );
+}
+
+#[test]
+fn should_not_write_yaml_into_http_out_files() {
+    let workdir = TempDir::new().unwrap();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args([
+            "import",
+            "curl",
+            "curl -F title=x http://localhost/up",
+            "-o",
+            "req.http",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "multipart text parts can't be printed as a .http request",
+        ));
+}
+
+#[test]
+fn should_reject_unsupported_options() {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .args(["import", "curl", "curl -T file.txt http://localhost"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("unsupported curl option '-T'"));
+}
@@ -0,0 +1,165 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use temp_dir::TempDir;
+
+const API: &str = r##"### login
+POST ${env(URL)}/login
+
+### create-user
+POST ${env(URL)}/users
+
+${request("#login")}
+"##;
+
+#[test]
+fn should_execute_a_single_named_request_with_its_dependencies() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+    let api = write_test_file(&workdir, "api.http", API).unwrap();
+
+    let login_mock = server
+        .mock("POST", "/login")
+        .expect(1)
+        .with_body("token")
+        .create();
+    let users_mock = server
+        .mock("POST", "/users")
+        .match_body("token")
+        .expect(1)
+        .with_body("created")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(format!("{}#create-user", api.to_str()))
+        .assert()
+        .success()
+        .stdout("created\n");
+
+    login_mock.assert();
+    users_mock.assert();
+}
+
+#[test]
+fn should_execute_all_requests_of_a_file() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+    let api = write_test_file(&workdir, "api.http", API).unwrap();
+
+    let login_mock = server
+        .mock("POST", "/login")
+        .expect(1)
+        .with_body("token")
+        .create();
+    let users_mock = server
+        .mock("POST", "/users")
+        .match_body("token")
+        .expect(1)
+        .with_body("created")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(api.to_str())
+        .assert()
+        .success()
+        .stdout("token\ncreated\n");
+
+    login_mock.assert();
+    users_mock.assert();
+}
+
+#[test]
+fn should_fail_for_unknown_request_names() {
+    let workdir = TempDir::new().unwrap();
+    let api = write_test_file(&workdir, "api.http", API).unwrap();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .arg(format!("{}#delete-user", api.to_str()))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(format!(
+            "'{}' contains no request named 'delete-user'",
+            api.to_str()
+        )));
+}
+
+#[test]
+fn should_refer_to_a_single_titled_request_without_a_name() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+    write_test_file(
+        &workdir,
+        "token.http",
+        "### Get token\nPOST ${env(URL)}/token\n",
+    )
+    .unwrap();
+    let req = write_test_file(
+        &workdir,
+        "req.http",
+        r#"GET ${env(URL)}/users/${request("token.http")}"#,
+    )
+    .unwrap();
+
+    let token_mock = server
+        .mock("POST", "/token")
+        .expect(1)
+        .with_body("t0ken")
+        .create();
+    let users_mock = server
+        .mock("GET", "/users/t0ken")
+        .expect(1)
+        .with_body("jane")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(req.to_str())
+        .assert()
+        .success()
+        .stdout("jane\n");
+
+    token_mock.assert();
+    users_mock.assert();
+}
+
+#[test]
+fn should_execute_files_with_a_hash_in_their_path() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+    write_test_file(&workdir, "issue#42.http", "POST ${env(URL)}/token").unwrap();
+    let req = write_test_file(
+        &workdir,
+        "req#1.http",
+        r#"GET ${env(URL)}/users/${request("issue#42.http")}"#,
+    )
+    .unwrap();
+
+    server.mock("POST", "/token").with_body("t0ken").create();
+    let users_mock = server
+        .mock("GET", "/users/t0ken")
+        .with_body("jane")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(req.to_str())
+        .assert()
+        .success()
+        .stdout("jane\n");
+
+    users_mock.assert();
+}
@@ -0,0 +1,108 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use temp_dir::TempDir;
+
+#[test]
+fn should_execute_requests_in_parallel_and_keep_the_output_order() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(&workdir, "token.http", "POST ${env(URL)}/token").unwrap();
+    let one = write_test_file(
+        &workdir,
+        "1.http",
+        r#"GET ${env(URL)}/1
+Authorization: ${request("token.http")}"#,
+    )
+    .unwrap();
+    let two = write_test_file(&workdir, "2.http", "GET ${env(URL)}/2").unwrap();
+    let three = write_test_file(&workdir, "3.http", "GET ${env(URL)}/3").unwrap();
+
+    let token_mock = server
+        .mock("POST", "/token")
+        .expect(1)
+        .with_body("secret")
+        .create();
+    let one_mock = server
+        .mock("GET", "/1")
+        .expect(1)
+        .match_header("authorization", "secret")
+        .with_body("one")
+        .create();
+    let two_mock = server.mock("GET", "/2").expect(1).with_body("two").create();
+    let three_mock = server
+        .mock("GET", "/3")
+        .expect(1)
+        .with_body("three")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg("--parallel")
+        .arg("3")
+        .arg(one.to_str())
+        .arg(two.to_str())
+        .arg(three.to_str())
+        .assert()
+        .success()
+        .stdout("one\ntwo\nthree\n");
+
+    token_mock.assert();
+    one_mock.assert();
+    two_mock.assert();
+    three_mock.assert();
+}
+
+#[test]
+fn should_not_execute_dependants_of_failed_requests() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(&workdir, "token.http", "POST ${env(URL)}/token").unwrap();
+    let one = write_test_file(
+        &workdir,
+        "1.http",
+        r#"GET ${env(URL)}/1
+Authorization: ${request("token.http")}"#,
+    )
+    .unwrap();
+
+    let token_mock = server
+        .mock("POST", "/token")
+        .expect(1)
+        .with_status(500)
+        .with_body("nope")
+        .create();
+    let one_mock = server.mock("GET", "/1").expect(0).create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg("--parallel")
+        .arg("2")
+        .arg(one.to_str())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("nope"));
+
+    token_mock.assert();
+    one_mock.assert();
+}
+
+#[test]
+fn should_reject_a_parallelism_of_zero() {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .arg("--parallel")
+        .arg("0")
+        .arg("foo.http")
+        .assert()
+        .failure();
+}
@@ -0,0 +1,121 @@
+extern crate assert_cmd;
+extern crate indoc;
+extern crate mockito;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use indoc::indoc;
+use rstest::rstest;
+use temp_dir::TempDir;
+
+const YAML_PROFILE: &str = indoc!(
+    r#"
+    default:
+      variables:
+        # the token is fetched by a request
+        TOKEN:
+          request: token.http
+    test:
+      variables:
+        USER: yaml
+    "#
+);
+
+const TOML_PROFILE: &str = indoc!(
+    r#"
+    [default.variables]
+    # the token is fetched by a request
+    TOKEN = { request = "token.http" }
+
+    [test.variables]
+    USER = "toml"
+    "#
+);
+
+#[rstest]
+#[case("fhttp-config.yaml", YAML_PROFILE, "yaml")]
+#[case("fhttp-config.yml", YAML_PROFILE, "yaml")]
+#[case("fhttp-config.toml", TOML_PROFILE, "toml")]
+fn should_discover_yaml_and_toml_profiles(
+    #[case] file: &str,
+    #[case] content: &str,
+    #[case] user: &str,
+) {
+    let mut server = mockito::Server::new();
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(&workdir, file, content).unwrap();
+    write_test_file(&workdir, "token.http", "POST ${env(URL)}/token").unwrap();
+    write_test_file(
+        &workdir,
+        "req.http",
+        indoc!(
+            "
+            GET ${env(URL)}/users/${env(USER)}
+            authorization: ${env(TOKEN)}
+        "
+        ),
+    )
+    .unwrap();
+
+    server.mock("POST", "/token").with_body("secret").create();
+    let request = server
+        .mock("GET", format!("/users/{}", user).as_str())
+        .match_header("authorization", "secret")
+        .with_body("OK")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", server.url())
+        .args(["-p", "test", "req.http"])
+        .assert()
+        .success()
+        .stdout("OK\n");
+
+    request.assert();
+}
+
+#[test]
+fn should_choose_the_parser_by_extension() {
+    let mut server = mockito::Server::new();
+    let workdir = TempDir::new().unwrap();
+
+    std::fs::create_dir(workdir.path().join("profiles")).unwrap();
+    write_test_file(&workdir, "profiles/staging.toml", TOML_PROFILE).unwrap();
+    write_test_file(&workdir, "profiles/token.http", "POST ${env(URL)}/token").unwrap();
+    write_test_file(&workdir, "req.http", "GET ${env(URL)}/${env(TOKEN)}").unwrap();
+
+    server.mock("POST", "/token").with_body("secret").create();
+    let request = server.mock("GET", "/secret").with_body("OK").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", server.url())
+        .args(["-f", "profiles/staging.toml", "req.http"])
+        .assert()
+        .success()
+        .stdout("OK\n");
+
+    request.assert();
+}
+
+#[test]
+fn should_report_invalid_toml_profiles() {
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(&workdir, "fhttp-config.toml", "[default.variables\n").unwrap();
+    write_test_file(&workdir, "req.http", "GET http://localhost").unwrap();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .arg("req.http")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "error reading profile from fhttp-config.toml",
+        ));
+}
@@ -199,3 +199,58 @@ fn profile_through_cli_option_should_precede_env_var(test_data: TestData) {
 
     token.assert();
 }
+
+#[test]
+fn profiles_should_inherit_variables_of_extended_profiles() {
+    let mut server = mockito::Server::new();
+    let workdir = TempDir::new().unwrap();
+
+    std::fs::create_dir(workdir.path().join("profiles")).unwrap();
+    write_test_file(
+        &workdir,
+        "profiles/staging.json",
+        r#"{
+            "staging": {
+              "variables": {
+                "REGION": "none",
+                "TOKEN": { "request": "token.http" }
+              }
+            },
+            "staging-eu": {
+              "extends": "staging",
+              "variables": {
+                "REGION": "eu"
+              }
+            }
+          }
+        "#,
+    )
+    .unwrap();
+    write_test_file(&workdir, "profiles/token.http", "POST ${env(URL)}/token").unwrap();
+    write_test_file(
+        &workdir,
+        "req.http",
+        "GET ${env(URL)}/${env(REGION)}/${env(TOKEN)}",
+    )
+    .unwrap();
+
+    server.mock("POST", "/token").with_body("secret").create();
+    let request = server.mock("GET", "/eu/secret").with_body("OK").create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", server.url())
+        .args([
+            "-f",
+            "profiles/staging.json",
+            "-p",
+            "staging-eu",
+            "req.http",
+        ])
+        .assert()
+        .success()
+        .stdout("OK\n");
+
+    request.assert();
+}
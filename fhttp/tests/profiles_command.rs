@@ -0,0 +1,187 @@
+extern crate assert_cmd;
+extern crate indoc;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::{write_test_file, write_test_files};
+use indoc::indoc;
+use predicates::prelude::*;
+use rstest::{fixture, rstest};
+use temp_dir::TempDir;
+
+#[fixture]
+fn workdir() -> TempDir {
+    write_test_files(&[
+        (
+            "fhttp-config.yaml",
+            indoc!(
+                r#"
+                default:
+                  variables:
+                    URL: http://localhost
+                    TOKEN:
+                      request: token.http
+                staging:
+                  variables:
+                    PASSWORD:
+                      command: [echo, s3cr3t]
+                staging-eu:
+                  extends: staging
+                  variables:
+                    REGION: eu
+                broken:
+                  variables:
+                    PASSWORD:
+                      command: [sh, -c, "echo denied >&2; exit 1"]
+                "#
+            ),
+        ),
+        ("token.http", "POST ${env(API)}/token"),
+    ])
+    .unwrap()
+}
+
+#[rstest]
+fn should_list_profiles(workdir: TempDir) {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args(["profiles", "list"])
+        .assert()
+        .success()
+        .stdout("broken\ndefault\nstaging\nstaging-eu\n");
+}
+
+#[rstest]
+fn should_show_profiles_without_resolving_variables(workdir: TempDir) {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args([
+            "profiles",
+            "show",
+            "staging-eu",
+            "--var",
+            "URL=http://example.com",
+        ])
+        .assert()
+        .success()
+        .stdout(indoc!(
+            "
+            PASSWORD = **** (command echo s3cr3t)  # profile 'staging'
+            REGION   = eu                          # profile 'staging-eu'
+            TOKEN    = response of token.http      # profile 'default'
+            URL      = http://example.com          # --var
+            "
+        ));
+}
+
+#[rstest]
+fn should_check_profiles(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    let token = server
+        .mock("POST", "/token")
+        .with_body("token")
+        .expect(1)
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("API", server.url())
+        .args(["profiles", "check", "staging"])
+        .assert()
+        .success()
+        .stdout("PASSWORD  ok\nTOKEN     ok\nURL       ok\n");
+
+    token.assert();
+}
+
+#[rstest]
+fn should_report_variables_that_fail_to_resolve(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    server.mock("POST", "/token").with_status(500).create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("API", server.url())
+        .args(["profiles", "check", "broken"])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains(
+            "PASSWORD  failed: 'sh -c 'echo denied >&2; exit 1'' failed with exit status: 1: 'denied'",
+        ))
+        .stdout(predicates::str::contains("TOKEN     failed: "))
+        .stdout(predicates::str::contains("URL       ok"))
+        .stderr(predicates::str::contains("2 of 3 variables failed"))
+        .stdout(predicates::str::contains("s3cr3t").not());
+}
+
+#[test]
+fn should_report_every_variable_of_a_failed_request() {
+    let mut server = mockito::Server::new();
+    let workdir = TempDir::new().unwrap();
+    write_test_file(
+        &workdir,
+        "fhttp-config.yaml",
+        indoc!(
+            r#"
+            default:
+              variables:
+                A:
+                  request: token.http
+                B:
+                  request: token.http
+            "#
+        ),
+    )
+    .unwrap();
+    write_test_file(&workdir, "token.http", "POST ${env(API)}/token").unwrap();
+    server.mock("POST", "/token").with_status(500).create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("API", server.url())
+        .args(["profiles", "check"])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("A  failed: "))
+        .stdout(predicates::str::contains("B  failed: "))
+        .stderr(predicates::str::contains("2 of 2 variables failed"));
+}
+
+#[test]
+fn should_report_requests_that_fail_to_load_and_go_on() {
+    let workdir = TempDir::new().unwrap();
+    write_test_file(
+        &workdir,
+        "fhttp-config.yaml",
+        indoc!(
+            r#"
+            default:
+              variables:
+                A:
+                  request: missing.http
+                B:
+                  request: invalid.http
+                C: c
+            "#
+        ),
+    )
+    .unwrap();
+    write_test_file(&workdir, "invalid.http", "no request").unwrap();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args(["profiles", "check"])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("A  failed: "))
+        .stdout(predicates::str::contains("B  failed: "))
+        .stdout(predicates::str::contains("C  ok"))
+        .stderr(predicates::str::contains("2 of 3 variables failed"));
+}
@@ -0,0 +1,82 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use indoc::indoc;
+use temp_dir::TempDir;
+
+#[test]
+fn should_resolve_response_headers_of_dependencies() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(&workdir, "create.http", "POST ${env(URL)}/resources").unwrap();
+    let get = write_test_file(
+        &workdir,
+        "get.http",
+        r#"GET ${env(URL)}${request_header("create.http", "Location")}"#,
+    )
+    .unwrap();
+
+    let create_mock = server
+        .mock("POST", "/resources")
+        .expect(1)
+        .with_status(201)
+        .with_header("location", "/resources/123")
+        .create();
+    let get_mock = server
+        .mock("GET", "/resources/123")
+        .expect(1)
+        .with_body("resource 123")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(get.to_str())
+        .assert()
+        .success()
+        .stdout("resource 123\n");
+
+    create_mock.assert();
+    get_mock.assert();
+}
+
+#[test]
+fn should_print_headers_when_requested() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let get = write_test_file(&workdir, "get.http", "GET ${env(URL)}/resources/123").unwrap();
+
+    let get_mock = server
+        .mock("GET", "/resources/123")
+        .expect(1)
+        .with_header("x-ratelimit-remaining", "41")
+        .with_body("resource 123")
+        .create();
+
+    let assert = Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg("--include-headers")
+        .arg(get.to_str())
+        .assert()
+        .success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+
+    assert!(stdout.starts_with("200 OK\n"));
+    assert!(stdout.contains("x-ratelimit-remaining: 41\n"));
+    assert!(stdout.ends_with(indoc!(
+        "
+
+        resource 123
+        "
+    )));
+
+    get_mock.assert();
+}
@@ -0,0 +1,78 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use predicates::str::contains;
+use temp_dir::TempDir;
+
+#[test]
+fn should_retry_and_log_each_attempt() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let req = write_test_file(&workdir, "req.http", "GET ${env(URL)}/flaky").unwrap();
+
+    let mock = server
+        .mock("GET", "/flaky")
+        .expect(3)
+        .with_status(503)
+        .with_header("retry-after", "0")
+        .with_body("unavailable")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg("-v")
+        .arg("--retries")
+        .arg("2")
+        .arg(req.to_str())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "attempt 1 of 3 failed: 503 Service Unavailable, retrying in 0ms",
+        ))
+        .stderr(contains(
+            "attempt 2 of 3 failed: 503 Service Unavailable, retrying in 0ms",
+        ))
+        .stderr(contains("unavailable"));
+
+    mock.assert();
+}
+
+#[test]
+fn should_take_retry_settings_from_directives() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let req = write_test_file(
+        &workdir,
+        "req.http",
+        r#"# @retries 1
+# @retry-on 500
+# @retry-non-idempotent
+POST ${env(URL)}/flaky"#,
+    )
+    .unwrap();
+
+    let mock = server
+        .mock("POST", "/flaky")
+        .expect(2)
+        .with_status(500)
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg("--retry-delay-ms")
+        .arg("1")
+        .arg(req.to_str())
+        .assert()
+        .failure();
+
+    mock.assert();
+}
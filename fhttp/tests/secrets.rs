@@ -0,0 +1,87 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use std::fs;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_files;
+use predicates::prelude::*;
+use rstest::{fixture, rstest};
+use temp_dir::TempDir;
+
+#[fixture]
+fn workdir() -> TempDir {
+    write_test_files(&[
+        (
+            "fhttp-config.json",
+            r#"{
+                "default": {
+                    "variables": {
+                        "PASSWORD": { "command": ["echo", "s3cr3t"] }
+                    }
+                }
+            }"#,
+        ),
+        (
+            "login.http",
+            "POST ${env(URL)}/login\n\npassword=${env(PASSWORD)}",
+        ),
+    ])
+    .unwrap()
+}
+
+fn mock_login(server: &mut mockito::ServerGuard) -> mockito::Mock {
+    server
+        .mock("POST", "/login")
+        .with_status(401)
+        .with_body("wrong password 's3cr3t'")
+        .create()
+}
+
+#[rstest]
+fn should_mask_secrets_in_errors(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    mock_login(&mut server);
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", server.url())
+        .arg("login.http")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Error: wrong password '****'"))
+        .stderr(predicates::str::contains("s3cr3t").not());
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", server.url())
+        .args(["--show-secrets", "login.http"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Error: wrong password 's3cr3t'"));
+}
+
+#[rstest]
+fn should_mask_secrets_in_test_reports(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    mock_login(&mut server);
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", server.url())
+        .args(["test", "--junit", "report.xml", "login.http"])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("s3cr3t").not());
+
+    let report = fs::read_to_string(workdir.path().join("report.xml")).unwrap();
+    assert!(
+        report.contains("wrong password &apos;****&apos;"),
+        "{}",
+        report
+    );
+}
@@ -0,0 +1,176 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use std::fs;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use indoc::indoc;
+use temp_dir::TempDir;
+
+#[test]
+fn should_keep_going_after_failures_and_report_results() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(
+        &workdir,
+        "items.http",
+        indoc!(
+            r#"
+            GET ${env(URL)}/items
+
+            > {% assert
+                status == 200
+                jsonpath $.items.length > 0
+            %}
+        "#
+        ),
+    )
+    .unwrap();
+    write_test_file(
+        &workdir,
+        "token.http",
+        indoc!(
+            r#"
+            POST ${env(URL)}/token
+
+            > {% json $.token %}
+
+            > {% assert header x-request-id exists %}
+        "#
+        ),
+    )
+    .unwrap();
+    write_test_file(
+        &workdir,
+        "profile.http",
+        r#"GET ${env(URL)}/profile/${request("token.http")}"#,
+    )
+    .unwrap();
+
+    server
+        .mock("GET", "/items")
+        .with_body(r#"{ "items": [1] }"#)
+        .create();
+    server
+        .mock("POST", "/token")
+        .with_body(r#"{ "token": "secret" }"#)
+        .create();
+    let profile_mock = server.mock("GET", "/profile/secret").expect(0).create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args([
+            "test",
+            "items.http",
+            "profile.http",
+            "--junit",
+            "junit.xml",
+            "--tap",
+            "report.tap",
+        ])
+        .assert()
+        .failure()
+        .stdout(
+            predicates::str::is_match(
+                r"^ok      items.http \(\d+ms\)
+FAILED  token.http \(\d+ms\)
+        assertion 'header x-request-id exists' failed, no value found
+skipped profile.http \(dependency token.http failed\)
+
+1 passed, 1 failed, 1 skipped
+$",
+            )
+            .unwrap(),
+        )
+        .stderr(predicates::str::contains("1 of 3 tests failed"));
+
+    profile_mock.assert();
+
+    let junit = fs::read_to_string(workdir.path().join("junit.xml")).unwrap();
+    assert!(junit.contains(r#"<testsuite name="fhttp" tests="3" failures="1" skipped="1""#));
+    assert!(junit.contains(r#"<skipped message="dependency token.http failed"/>"#));
+
+    let tap = fs::read_to_string(workdir.path().join("report.tap")).unwrap();
+    assert!(tap.starts_with("TAP version 13\n1..3\nok 1 - items.http\nnot ok 2 - token.http\n"));
+    assert!(tap.ends_with("ok 3 - profile.http # SKIP dependency token.http failed\n"));
+}
+
+#[test]
+fn should_succeed_if_all_tests_pass() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(
+        &workdir,
+        "delete.http",
+        indoc!(
+            r#"
+            # @expect-status 404
+            DELETE ${env(URL)}/items/1
+
+            > {% assert status == 404; body contains "not found" %}
+        "#
+        ),
+    )
+    .unwrap();
+
+    server
+        .mock("DELETE", "/items/1")
+        .with_status(404)
+        .with_body("item not found")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .args(["test", "delete.http"])
+        .assert()
+        .success()
+        .stdout(predicates::str::ends_with(
+            "\n1 passed, 0 failed, 0 skipped\n",
+        ));
+}
+
+#[test]
+fn should_fail_requests_with_failed_assertions() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let req = write_test_file(
+        &workdir,
+        "items.http",
+        indoc!(
+            r#"
+            GET ${env(URL)}/items
+
+            > {% assert jsonpath $.items.length > 0 %}
+        "#
+        ),
+    )
+    .unwrap();
+
+    server
+        .mock("GET", "/items")
+        .with_body(r#"{ "items": [] }"#)
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .env("URL", &url)
+        .arg(req.to_str())
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicates::str::contains(
+            "assertion 'jsonpath $.items.length > 0' failed, actual value: 0",
+        ));
+}
@@ -0,0 +1,148 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use indoc::indoc;
+use temp_dir::TempDir;
+
+#[test]
+fn should_override_profile_and_environment_variables() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(
+        &workdir,
+        "fhttp-config.json",
+        indoc!(
+            r#"
+            {
+                "default": {
+                    "variables": {
+                        "PATH_1": "profile",
+                        "TOKEN": { "request": "token.http" }
+                    }
+                }
+            }
+        "#
+        ),
+    )
+    .unwrap();
+    write_test_file(&workdir, "vars.env", "PATH_1=file\nPATH_2=file\n").unwrap();
+    write_test_file(&workdir, "token.http", "POST ${env(URL)}/token").unwrap();
+    write_test_file(
+        &workdir,
+        "req.http",
+        indoc!(
+            r#"
+            GET ${env(URL)}/${env(PATH_1)}/${env(PATH_2)}/${env(PATH_3)}
+            authorization: ${env(TOKEN)}
+        "#
+        ),
+    )
+    .unwrap();
+
+    let token_mock = server.mock("POST", "/token").expect(0).create();
+    let mock = server
+        .mock("GET", "/file/arg/env")
+        .match_header("authorization", "fixed-token")
+        .with_body("ok")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .env("PATH_2", "env")
+        .env("PATH_3", "env")
+        .args([
+            "--var-file",
+            "vars.env",
+            "--var",
+            "PATH_2=arg",
+            "--var",
+            "TOKEN=fixed-token",
+            "req.http",
+        ])
+        .assert()
+        .success()
+        .stdout("ok\n");
+
+    token_mock.assert();
+    mock.assert();
+}
+
+#[test]
+fn should_reject_invalid_variables() {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .args(["--var", "NAME", "req.http"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("expected NAME=VALUE, got 'NAME'"));
+}
+
+#[test]
+fn should_read_variables_from_dotenv_files() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(
+        &workdir,
+        "fhttp-config.json",
+        r#"{ "default": { "variables": { "PATH_1": "profile" } } }"#,
+    )
+    .unwrap();
+    write_test_file(
+        &workdir,
+        ".env",
+        indoc!(
+            r#"
+            # local defaults
+            export PATH_1=dotenv
+            PATH_2=dotenv
+            PATH_3="${PATH_2}-interpolated"
+        "#
+        ),
+    )
+    .unwrap();
+    write_test_file(&workdir, "other.env", "PATH_3=other").unwrap();
+    write_test_file(
+        &workdir,
+        "req.http",
+        "GET ${env(URL)}/${env(PATH_1)}/${env(PATH_2)}/${env(PATH_3)}",
+    )
+    .unwrap();
+
+    server
+        .mock("GET", "/profile/env/env-interpolated")
+        .with_body("default")
+        .create();
+    server
+        .mock("GET", "/profile/env/other")
+        .with_body("other")
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .env("PATH_2", "env")
+        .arg("req.http")
+        .assert()
+        .success()
+        .stdout("default\n");
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", &url)
+        .env("PATH_2", "env")
+        .args(["--env-file", "other.env", "req.http"])
+        .assert()
+        .success()
+        .stdout("other\n");
+}
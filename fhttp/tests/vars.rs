@@ -0,0 +1,106 @@
+extern crate assert_cmd;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::write_test_file;
+use indoc::indoc;
+use temp_dir::TempDir;
+
+fn setup() -> TempDir {
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(
+        &workdir,
+        "fhttp-config.json",
+        indoc!(
+            r#"
+            {
+                "default": {
+                    "variables": {
+                        "URL": "http://localhost",
+                        "TOKEN": { "request": "token.http" }
+                    }
+                }
+            }
+        "#
+        ),
+    )
+    .unwrap();
+    write_test_file(&workdir, ".env", "CLIENT_ID=dotenv").unwrap();
+    write_test_file(
+        &workdir,
+        "token.http",
+        "POST ${env(URL)}/token/${env(CLIENT_ID)}/${env(FHTTP_VARS_SECRET)}",
+    )
+    .unwrap();
+    write_test_file(&workdir, "body.json", r#"{ "page": ${env(PAGE, "1")} }"#).unwrap();
+    write_test_file(
+        &workdir,
+        "req.http",
+        indoc!(
+            r#"
+            GET ${env(URL)}/${request("dep.http")}
+            authorization: ${env(TOKEN)}
+            x-user: ${env(FHTTP_VARS_USER)}
+
+            ${include("body.json")}
+        "#
+        ),
+    )
+    .unwrap();
+    write_test_file(&workdir, "dep.http", "GET ${env(URL)}/dep").unwrap();
+
+    workdir
+}
+
+#[test]
+fn should_list_the_variables_of_requests_and_their_dependencies() {
+    let workdir = setup();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("FHTTP_VARS_SECRET", "secret")
+        .args(["vars", "req.http"])
+        .assert()
+        .success()
+        .stdout(indoc!(
+            r#"
+            CLIENT_ID          .env file          # token.http
+            FHTTP_VARS_SECRET  environment        # token.http
+            FHTTP_VARS_USER    unresolved         # req.http
+            PAGE               default "1"        # req.http
+            TOKEN              profile 'default'  # req.http
+            URL                profile 'default'  # token.http, dep.http, req.http
+        "#
+        ));
+}
+
+#[test]
+fn should_fail_on_unresolved_variables_without_prompting() {
+    let workdir = setup();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .args(["vars", "--no-prompt", "req.http"])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("FHTTP_VARS_USER    unresolved"))
+        .stderr(predicates::str::contains("2 of 6 variables are unresolved"));
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("FHTTP_VARS_SECRET", "secret")
+        .args([
+            "vars",
+            "--no-prompt",
+            "--var",
+            "FHTTP_VARS_USER=me",
+            "req.http",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("FHTTP_VARS_USER    --var"));
+}
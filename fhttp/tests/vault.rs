@@ -0,0 +1,79 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use assert_cmd::Command;
+use fhttp_test_utils::{write_test_file, write_test_files};
+use rstest::{fixture, rstest};
+use temp_dir::TempDir;
+
+#[fixture]
+fn workdir() -> TempDir {
+    write_test_files(&[
+        (
+            "fhttp-config.json",
+            r#"{
+                "default": {
+                    "variables": {
+                        "SECRET": {
+                            "vault": "secret/data/app#client_secret",
+                            "namespace": "team"
+                        }
+                    }
+                }
+            }"#,
+        ),
+        ("req.http", "GET ${env(URL)}/${env(SECRET)}"),
+        ("other.http", "GET ${env(URL)}/${env(SECRET)}"),
+    ])
+    .unwrap()
+}
+
+#[rstest]
+fn should_resolve_vault_secrets_with_the_token_file(workdir: TempDir) {
+    let mut server = mockito::Server::new();
+    write_test_file(&workdir, ".vault-token", "file-token\n").unwrap();
+
+    let vault = server
+        .mock("GET", "/v1/secret/data/app")
+        .match_header("x-vault-token", "file-token")
+        .match_header("x-vault-namespace", "team")
+        .with_body(r#"{ "data": { "data": { "client_secret": "s3cr3t" }, "metadata": {} } }"#)
+        .expect(1)
+        .create();
+    let request = server
+        .mock("GET", "/s3cr3t")
+        .with_body("OK")
+        .expect(2)
+        .create();
+
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", server.url())
+        .env("VAULT_ADDR", server.url())
+        .env_remove("VAULT_TOKEN")
+        .env("HOME", workdir.path())
+        .args(["req.http", "other.http"])
+        .assert()
+        .success()
+        .stdout("OK\nOK\n");
+
+    vault.assert();
+    request.assert();
+}
+
+#[rstest]
+fn should_report_missing_vault_configuration(workdir: TempDir) {
+    Command::cargo_bin("fhttp")
+        .unwrap()
+        .current_dir(workdir.path())
+        .env("URL", "http://localhost")
+        .env_remove("VAULT_ADDR")
+        .arg("req.http")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "missing environment variable VAULT_ADDR for vault secrets",
+        ));
+}
@@ -0,0 +1,190 @@
+extern crate assert_cmd;
+extern crate mockito;
+extern crate temp_dir;
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use assert_cmd::cargo::cargo_bin;
+use fhttp_test_utils::write_test_file;
+use temp_dir::TempDir;
+
+struct Watch {
+    child: Child,
+    stdout: Receiver<String>,
+    stderr: Receiver<String>,
+}
+
+impl Watch {
+    fn next_output(&self) -> String {
+        self.stdout
+            .recv_timeout(Duration::from_secs(10))
+            .expect("no output")
+    }
+
+    fn wait_until_watching(&self) {
+        loop {
+            let line = self
+                .stderr
+                .recv_timeout(Duration::from_secs(10))
+                .expect("not watching");
+            if line.starts_with("watching") {
+                return;
+            }
+        }
+    }
+
+    /// The lines printed up to and including the first one containing `end`.
+    fn output_until(&self, end: &str) -> Vec<String> {
+        let mut ret = vec![];
+        loop {
+            let line = self.next_output();
+            let done = line.contains(end);
+            ret.push(line);
+            if done {
+                return ret;
+            }
+        }
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn lines<R: Read + Send + 'static>(input: R) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(input).lines().map_while(Result::ok) {
+            let _ = sender.send(line);
+        }
+    });
+
+    receiver
+}
+
+fn watch(workdir: &TempDir, url: &str, args: &[&str]) -> Watch {
+    let mut child = std::process::Command::new(cargo_bin("fhttp"))
+        .current_dir(workdir.path())
+        .env("URL", url)
+        .arg("--watch")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    Watch {
+        stdout: lines(child.stdout.take().unwrap()),
+        stderr: lines(child.stderr.take().unwrap()),
+        child,
+    }
+}
+
+#[test]
+fn should_execute_requests_again_and_keep_unchanged_dependencies() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let token = write_test_file(&workdir, "token.http", "POST ${env(URL)}/token").unwrap();
+    let items = write_test_file(
+        &workdir,
+        "items.http",
+        r#"GET ${env(URL)}/items/${request("token.http")}"#,
+    )
+    .unwrap();
+
+    let token_mock = server
+        .mock("POST", "/token")
+        .with_body("secret")
+        .expect(2)
+        .create();
+    server
+        .mock("GET", "/items/secret")
+        .with_body("items")
+        .create();
+    server
+        .mock("GET", "/users/secret")
+        .with_body("users")
+        .create();
+
+    let watch = watch(&workdir, &url, &["items.http"]);
+    assert_eq!(watch.next_output(), "items");
+    watch.wait_until_watching();
+
+    fs::write(&items, r#"GET ${env(URL)}/users/${request("token.http")}"#).unwrap();
+    assert_eq!(watch.next_output(), "users");
+    watch.wait_until_watching();
+
+    fs::write(&token, "POST ${env(URL)}/token\n").unwrap();
+    assert_eq!(watch.next_output(), "users");
+    watch.wait_until_watching();
+
+    token_mock.assert();
+}
+
+#[test]
+fn should_keep_watching_after_errors() {
+    let mut server = mockito::Server::new();
+    let url = server.url();
+    let workdir = TempDir::new().unwrap();
+
+    let body = write_test_file(&workdir, "body.txt", "{").unwrap();
+    write_test_file(
+        &workdir,
+        "items.http",
+        "POST ${env(URL)}/items\n\n${include(\"body.txt\")}",
+    )
+    .unwrap();
+
+    server
+        .mock("POST", "/items")
+        .match_body("{")
+        .with_status(400)
+        .create();
+    server
+        .mock("POST", "/items")
+        .match_body("{}")
+        .with_body("created")
+        .create();
+
+    let watch = watch(&workdir, &url, &["items.http"]);
+    watch.wait_until_watching();
+
+    fs::write(&body, "{}").unwrap();
+    assert_eq!(watch.next_output(), "created");
+}
+
+#[test]
+fn should_define_dependencies_in_every_script() {
+    let workdir = TempDir::new().unwrap();
+
+    write_test_file(&workdir, "token.http", "POST ${env(URL)}/token").unwrap();
+    let items = write_test_file(
+        &workdir,
+        "items.http",
+        r#"GET ${env(URL)}/items/${request("token.http")}"#,
+    )
+    .unwrap();
+
+    let watch = watch(
+        &workdir,
+        "http://localhost",
+        &["--curl", "--script", "items.http"],
+    );
+    let script = watch.output_until("/items/");
+    assert!(script.iter().any(|it| it.starts_with("TOKEN=$(curl")));
+    watch.wait_until_watching();
+
+    fs::write(&items, r#"GET ${env(URL)}/users/${request("token.http")}"#).unwrap();
+    let script = watch.output_until("/users/");
+    assert!(script.iter().any(|it| it.starts_with("TOKEN=$(curl")));
+}